@@ -0,0 +1,6 @@
+/// Compile the gRPC protocol files, `protoc` is vendored so it doesn't need to be installed
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    std::env::set_var("PROTOC", protoc_bin_vendored::protoc_bin_path()?);
+    tonic_build::configure().compile(&["proto/auth.proto", "proto/todo.proto"], &["proto"])?;
+    Ok(())
+}
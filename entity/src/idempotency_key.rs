@@ -0,0 +1,32 @@
+//! The `idempotency_key` table, the stored responses of the requests with an `Idempotency-Key` header
+
+use sea_orm::entity::prelude::*;
+
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Eq)]
+#[sea_orm(table_name = "idempotency_key")]
+pub struct Model {
+    #[sea_orm(primary_key)]
+    pub id: u32,
+    pub user_id: u32,
+    pub key: String,
+    /// The hash of the request method, path, query and body
+    pub fingerprint: String,
+    /// The status code of the stored response, `None` if the request is still in progress
+    pub status_code: Option<u16>,
+    /// The headers of the stored response as a JSON array of `[name, value]` pairs
+    pub headers: Option<String>,
+    pub body: Option<Vec<u8>>,
+    pub created_at: i64,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(
+        belongs_to = "super::user::Entity",
+        from = "Column::UserId",
+        to = "super::user::Column::Id"
+    )]
+    User,
+}
+
+impl ActiveModelBehavior for ActiveModel {}
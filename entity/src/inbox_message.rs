@@ -0,0 +1,40 @@
+//! The `inbox_message` table, the messages of the `inbox` notification channel
+
+use sea_orm::entity::prelude::*;
+
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Eq)]
+#[sea_orm(table_name = "inbox_message")]
+pub struct Model {
+    #[sea_orm(primary_key)]
+    pub id: u32,
+    #[sea_orm(unique_key)]
+    pub uuid: Uuid,
+    pub user_id: u32,
+    /// The id of the notification that the message is delivered by
+    pub notification_id: u32,
+    pub event: String,
+    pub todo_uuid: Uuid,
+    pub subject: String,
+    pub body: String,
+    pub created_at: i64,
+    /// The time that the user read the message, `None` if the message is unread
+    pub read_at: Option<i64>,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(
+        belongs_to = "super::notification::Entity",
+        from = "Column::NotificationId",
+        to = "super::notification::Column::Id"
+    )]
+    Notification,
+}
+
+impl Related<super::notification::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Notification.def()
+    }
+}
+
+impl ActiveModelBehavior for ActiveModel {}
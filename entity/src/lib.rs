@@ -2,5 +2,12 @@
 
 pub mod prelude;
 
+pub mod idempotency_key;
+pub mod inbox_message;
+pub mod notification;
+pub mod notification_preference;
 pub mod todo;
+pub mod todo_history;
 pub mod user;
+pub mod webhook;
+pub mod webhook_delivery;
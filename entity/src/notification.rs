@@ -0,0 +1,72 @@
+//! The `notification` table, the notifications of the todo events queued for the channels of the users
+
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use super::notification_preference::Channel;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, EnumIter, DeriveActiveEnum, Serialize, Deserialize)]
+#[sea_orm(rs_type = "String", db_type = "String(Some(1))")]
+#[serde(rename_all = "lowercase")]
+/// The status of a notification
+pub enum Status {
+    /// The notification is waiting to be dispatched
+    #[sea_orm(string_value = "pending")]
+    Pending,
+    /// The notification is sent through its channel
+    #[sea_orm(string_value = "sent")]
+    Sent,
+    /// All the attempts are failed, the notification will not be retried
+    #[sea_orm(string_value = "failed")]
+    Failed,
+}
+
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Eq)]
+#[sea_orm(table_name = "notification")]
+pub struct Model {
+    #[sea_orm(primary_key)]
+    pub id: u32,
+    #[sea_orm(unique_key)]
+    pub uuid: Uuid,
+    pub user_id: u32,
+    pub channel: Channel,
+    /// The email address or the URL that the notification is sent to, `None` for the inbox
+    pub target: Option<String>,
+    /// The id of the todo event (the todo history entry), `None` for the verification email
+    pub event_id: Option<u32>,
+    pub event: String,
+    /// The uuid of the todo of the event, `None` for the verification email
+    pub todo_uuid: Option<Uuid>,
+    pub subject: String,
+    pub body: String,
+    /// The JSON payload of the event, it's sent to the webhook channel
+    pub payload: String,
+    pub status: Status,
+    pub attempts: u32,
+    /// The time of the next attempt, `None` if the notification is not pending
+    pub next_attempt_at: Option<i64>,
+    /// The error of the last attempt, `None` if the last attempt is succeeded
+    pub error: Option<String>,
+    pub created_at: i64,
+    pub sent_at: Option<i64>,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(
+        belongs_to = "super::user::Entity",
+        from = "Column::UserId",
+        to = "super::user::Column::Id"
+    )]
+    User,
+    #[sea_orm(has_many = "super::inbox_message::Entity")]
+    InboxMessage,
+}
+
+impl Related<super::inbox_message::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::InboxMessage.def()
+    }
+}
+
+impl ActiveModelBehavior for ActiveModel {}
@@ -0,0 +1,55 @@
+//! The `notification_preference` table, the channels that the users choose for the todo events
+
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
+
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, Hash, EnumIter, DeriveActiveEnum, Serialize, Deserialize,
+)]
+#[sea_orm(rs_type = "String", db_type = "String(Some(1))")]
+#[serde(rename_all = "lowercase")]
+/// The channel that the notifications are delivered through
+pub enum Channel {
+    /// An email to the target address
+    #[sea_orm(string_value = "email")]
+    Email,
+    /// A `POST` request to the target URL
+    #[sea_orm(string_value = "webhook")]
+    Webhook,
+    /// A message in the in-app inbox of the user
+    #[sea_orm(string_value = "inbox")]
+    Inbox,
+}
+
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Eq)]
+#[sea_orm(table_name = "notification_preference")]
+pub struct Model {
+    #[sea_orm(primary_key)]
+    pub id: u32,
+    pub user_id: u32,
+    pub channel: Channel,
+    /// The email address or the URL that the notifications are sent to, `None` for the inbox
+    pub target: Option<String>,
+    /// The comma separated events that are sent to the channel
+    pub events: String,
+    /// The secret that the webhook requests are signed with, `None` for the other channels
+    pub secret: Option<String>,
+    /// The token that verifies the email address, `None` if it's verified or for the other channels
+    pub verification_token: Option<String>,
+    /// The time that the email address is verified (Unix timestamp), the notifications are sent
+    /// to the email address only after it's verified
+    pub verified_at: Option<i64>,
+    pub created_at: i64,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(
+        belongs_to = "super::user::Entity",
+        from = "Column::UserId",
+        to = "super::user::Column::Id"
+    )]
+    User,
+}
+
+impl ActiveModelBehavior for ActiveModel {}
@@ -1,4 +1,11 @@
 //! `SeaORM` Entity. Generated by sea-orm-codegen 0.10.2
 
+pub use super::idempotency_key::Entity as IdempotencyKey;
+pub use super::inbox_message::Entity as InboxMessage;
+pub use super::notification::Entity as Notification;
+pub use super::notification_preference::Entity as NotificationPreference;
 pub use super::todo::Entity as Todo;
+pub use super::todo_history::Entity as TodoHistory;
 pub use super::user::Entity as User;
+pub use super::webhook::Entity as Webhook;
+pub use super::webhook_delivery::Entity as WebhookDelivery;
@@ -62,6 +62,10 @@ pub struct Model {
     pub status: Status,
     pub created_at: i64,
     pub updated_at: i64,
+    pub completed_at: Option<i64>,
+    pub version: u32,
+    pub deleted_at: Option<i64>,
+    pub position: String,
 }
 
 #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
@@ -0,0 +1,62 @@
+//! The `todo_history` table, the history of the todo changes, each row is a change with the todo snapshot after it
+
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, PartialEq, Eq, EnumIter, DeriveActiveEnum, Serialize, Deserialize)]
+#[sea_orm(rs_type = "String", db_type = "String(Some(1))")]
+#[serde(rename_all = "lowercase")]
+/// The action that changed the todo
+pub enum Action {
+    /// The todo is created
+    #[sea_orm(string_value = "created")]
+    Created,
+    /// The todo is updated
+    #[sea_orm(string_value = "updated")]
+    Updated,
+    /// The todo is moved to the trash
+    #[sea_orm(string_value = "deleted")]
+    Deleted,
+    /// The todo is restored from the trash
+    #[sea_orm(string_value = "restored")]
+    Restored,
+}
+
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Eq)]
+#[sea_orm(table_name = "todo_history")]
+pub struct Model {
+    #[sea_orm(primary_key)]
+    pub id: u32,
+    pub todo_uuid: Uuid,
+    pub user_id: u32,
+    pub actor_id: u32,
+    pub action: Action,
+    /// The field-level changes as a JSON object, `{"field": {"old": .., "new": ..}}`
+    pub changes: String,
+    /// The todo after the change as a JSON object
+    pub snapshot: String,
+    pub request_id: String,
+    pub created_at: i64,
+    /// The time the change is undone, `None` if it's not undone
+    pub undone_at: Option<i64>,
+    /// The request id of the undone operation, `None` if the change is not made by an undo
+    pub undo_of: Option<String>,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(
+        belongs_to = "super::user::Entity",
+        from = "Column::ActorId",
+        to = "super::user::Column::Id"
+    )]
+    Actor,
+}
+
+impl Related<super::user::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Actor.def()
+    }
+}
+
+impl ActiveModelBehavior for ActiveModel {}
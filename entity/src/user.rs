@@ -11,6 +11,9 @@ pub struct Model {
     pub hashed_password: String,
     pub token_created_at: i64,
     pub created_at: i64,
+    /// The SHA-256 hash of the secret token of the calendar feed, `None` if the feed is disabled
+    #[sea_orm(unique)]
+    pub calendar_token: Option<String>,
 }
 
 #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
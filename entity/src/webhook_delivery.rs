@@ -0,0 +1,63 @@
+//! The `webhook_delivery` table, the deliveries of the todo events to the webhooks and their attempts
+
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, EnumIter, DeriveActiveEnum, Serialize, Deserialize)]
+#[sea_orm(rs_type = "String", db_type = "String(Some(1))")]
+#[serde(rename_all = "lowercase")]
+/// The status of a webhook delivery
+pub enum Status {
+    /// The delivery is waiting for its next attempt
+    #[sea_orm(string_value = "pending")]
+    Pending,
+    /// The payload is delivered
+    #[sea_orm(string_value = "succeeded")]
+    Succeeded,
+    /// All the attempts are failed, the delivery is in the dead-letter list
+    #[sea_orm(string_value = "failed")]
+    Failed,
+}
+
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Eq)]
+#[sea_orm(table_name = "webhook_delivery")]
+pub struct Model {
+    #[sea_orm(primary_key)]
+    pub id: u32,
+    #[sea_orm(unique_key)]
+    pub uuid: Uuid,
+    pub webhook_id: u32,
+    /// The id of the todo event (the todo history entry)
+    pub event_id: u32,
+    pub event: String,
+    /// The JSON payload that is sent to the webhook
+    pub payload: String,
+    pub status: Status,
+    pub attempts: u32,
+    /// The time of the next attempt, `None` if the delivery is not pending
+    pub next_attempt_at: Option<i64>,
+    /// The status code of the last attempt response, `None` if there is no response
+    pub response_status: Option<u16>,
+    /// The error of the last attempt, `None` if the last attempt is succeeded
+    pub error: Option<String>,
+    pub created_at: i64,
+    pub delivered_at: Option<i64>,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(
+        belongs_to = "super::webhook::Entity",
+        from = "Column::WebhookId",
+        to = "super::webhook::Column::Id"
+    )]
+    Webhook,
+}
+
+impl Related<super::webhook::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Webhook.def()
+    }
+}
+
+impl ActiveModelBehavior for ActiveModel {}
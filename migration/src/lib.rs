@@ -2,6 +2,17 @@ pub use sea_orm_migration::prelude::*;
 
 mod m20221112_051320_create_user_table;
 mod m20221112_051333_create_todo_table;
+mod m20230301_120000_add_todo_completed_at;
+mod m20230310_120000_add_todo_version;
+mod m20230320_120000_add_todo_deleted_at;
+mod m20230401_120000_create_todo_history_table;
+mod m20230410_120000_add_todo_history_undo;
+mod m20230420_120000_create_idempotency_key_table;
+mod m20230430_120000_add_todo_title_unique_index;
+mod m20230510_120000_add_todo_position;
+mod m20230520_120000_create_webhook_tables;
+mod m20230525_120000_add_user_calendar_token;
+mod m20230530_120000_create_notification_tables;
 
 pub struct Migrator;
 
@@ -11,6 +22,17 @@ impl MigratorTrait for Migrator {
         vec![
             Box::new(m20221112_051320_create_user_table::Migration),
             Box::new(m20221112_051333_create_todo_table::Migration),
+            Box::new(m20230301_120000_add_todo_completed_at::Migration),
+            Box::new(m20230310_120000_add_todo_version::Migration),
+            Box::new(m20230320_120000_add_todo_deleted_at::Migration),
+            Box::new(m20230401_120000_create_todo_history_table::Migration),
+            Box::new(m20230410_120000_add_todo_history_undo::Migration),
+            Box::new(m20230420_120000_create_idempotency_key_table::Migration),
+            Box::new(m20230430_120000_add_todo_title_unique_index::Migration),
+            Box::new(m20230510_120000_add_todo_position::Migration),
+            Box::new(m20230520_120000_create_webhook_tables::Migration),
+            Box::new(m20230525_120000_add_user_calendar_token::Migration),
+            Box::new(m20230530_120000_create_notification_tables::Migration),
         ]
     }
 }
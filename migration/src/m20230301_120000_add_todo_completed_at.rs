@@ -0,0 +1,48 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Todo::Table)
+                    .add_column(ColumnDef::new(Todo::CompletedAt).big_integer().null())
+                    .to_owned(),
+            )
+            .await?;
+        // The completed todos before this migration don't have a completion time,
+        // so the last update time is the best approximation of it
+        manager
+            .exec_stmt(
+                Query::update()
+                    .table(Todo::Table)
+                    .value(Todo::CompletedAt, Expr::col(Todo::UpdatedAt))
+                    .and_where(Expr::col(Todo::Status).eq("completed"))
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Todo::Table)
+                    .drop_column(Todo::CompletedAt)
+                    .to_owned(),
+            )
+            .await
+    }
+}
+
+#[derive(Iden)]
+enum Todo {
+    Table,
+    Status,
+    UpdatedAt,
+    CompletedAt,
+}
@@ -0,0 +1,82 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(TodoHistory::Table)
+                    .col(
+                        ColumnDef::new(TodoHistory::Id)
+                            .big_unsigned()
+                            .not_null()
+                            .auto_increment()
+                            .primary_key(),
+                    )
+                    .col(ColumnDef::new(TodoHistory::TodoUuid).uuid().not_null())
+                    .col(
+                        ColumnDef::new(TodoHistory::UserId)
+                            .big_unsigned()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(TodoHistory::ActorId)
+                            .big_unsigned()
+                            .not_null(),
+                    )
+                    .col(ColumnDef::new(TodoHistory::Action).string().not_null())
+                    .col(ColumnDef::new(TodoHistory::Changes).text().not_null())
+                    .col(ColumnDef::new(TodoHistory::Snapshot).text().not_null())
+                    .col(ColumnDef::new(TodoHistory::RequestId).string().not_null())
+                    .col(
+                        ColumnDef::new(TodoHistory::CreatedAt)
+                            .big_integer()
+                            .not_null(),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx-todo_history-todo_uuid")
+                    .table(TodoHistory::Table)
+                    .col(TodoHistory::TodoUuid)
+                    .to_owned(),
+            )
+            .await?;
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx-todo_history-user_id")
+                    .table(TodoHistory::Table)
+                    .col(TodoHistory::UserId)
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(TodoHistory::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(Iden)]
+enum TodoHistory {
+    Table,
+    Id,
+    TodoUuid,
+    UserId,
+    ActorId,
+    Action,
+    Changes,
+    Snapshot,
+    RequestId,
+    CreatedAt,
+}
@@ -0,0 +1,53 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // Sqlite supports only one alter option per statement
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(TodoHistory::Table)
+                    .add_column(ColumnDef::new(TodoHistory::UndoneAt).big_integer().null())
+                    .to_owned(),
+            )
+            .await?;
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(TodoHistory::Table)
+                    .add_column(ColumnDef::new(TodoHistory::UndoOf).string().null())
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(TodoHistory::Table)
+                    .drop_column(TodoHistory::UndoOf)
+                    .to_owned(),
+            )
+            .await?;
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(TodoHistory::Table)
+                    .drop_column(TodoHistory::UndoneAt)
+                    .to_owned(),
+            )
+            .await
+    }
+}
+
+#[derive(Iden)]
+enum TodoHistory {
+    Table,
+    UndoneAt,
+    UndoOf,
+}
@@ -0,0 +1,63 @@
+use std::env;
+
+use sea_orm_migration::prelude::*;
+use sea_orm_migration::sea_orm::{ConnectionTrait, Statement};
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+/// Returns the maximum length of the todo title, the renamed titles are truncated to fit in it
+fn max_todo_title_length() -> u64 {
+    env::var("MAXIMUM_TODO_TITLE_LENGTH")
+        .ok()
+        .and_then(|length| length.parse().ok())
+        .unwrap_or(100)
+}
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let db = manager.get_connection();
+        let backend = manager.get_database_backend();
+        // Rename the duplicate titles that created by concurrent requests, keep the oldest todo title.
+        // The title is truncated before adding the id, so the renamed title doesn't exceed the maximum length
+        db.execute(Statement::from_string(
+            backend,
+            format!(
+                r#"UPDATE "todo"
+                SET "title" = substr("title", 1, {} - length(' (' || "id" || ')')) || ' (' || "id" || ')'
+                WHERE "deleted_at" IS NULL AND "id" NOT IN (
+                    SELECT MIN("id") FROM "todo" WHERE "deleted_at" IS NULL GROUP BY "user_id", "title"
+                )"#,
+                max_todo_title_length()
+            ),
+        ))
+        .await?;
+        // The todos in the trash are excluded, so their titles can be used again.
+        // The partial indexes are not supported by the index builder
+        db.execute(Statement::from_string(
+            backend,
+            r#"CREATE UNIQUE INDEX "idx-todo-user_id-title" ON "todo" ("user_id", "title")
+            WHERE "deleted_at" IS NULL"#
+                .to_owned(),
+        ))
+        .await
+        .map(|_| ())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_index(
+                Index::drop()
+                    .name("idx-todo-user_id-title")
+                    .table(Todo::Table)
+                    .to_owned(),
+            )
+            .await
+    }
+}
+
+#[derive(Iden)]
+enum Todo {
+    Table,
+}
@@ -0,0 +1,77 @@
+use sea_orm_migration::prelude::*;
+use sea_orm_migration::sea_orm::ConnectionTrait;
+
+/// The digits of the rank keys, in ascending ASCII order
+const DIGITS: &[u8] = b"0123456789ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz";
+
+/// Returns the rank key of an existing todo, the keys keep the creation order.
+/// The keys have a fixed length and don't use the `0` digit, so none of them ends with it
+fn initial_position(id: u64) -> String {
+    let base = DIGITS.len() as u64 - 1;
+    let mut id = id;
+    let mut key = [DIGITS[1]; 6];
+    for digit in key.iter_mut().rev() {
+        *digit = DIGITS[(id % base) as usize + 1];
+        id /= base;
+    }
+    String::from_utf8_lossy(&key).into_owned()
+}
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Todo::Table)
+                    .add_column(
+                        ColumnDef::new(Todo::Position)
+                            .string()
+                            .not_null()
+                            .default(""),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        let db = manager.get_connection();
+        let backend = manager.get_database_backend();
+        let ids = db
+            .query_all(backend.build(Query::select().column(Todo::Id).from(Todo::Table)))
+            .await?;
+        for row in ids {
+            let id: u32 = row.try_get("", &Todo::Id.to_string())?;
+            db.execute(
+                backend.build(
+                    Query::update()
+                        .table(Todo::Table)
+                        .value(Todo::Position, initial_position(id as u64))
+                        .and_where(Expr::col(Todo::Id).eq(id)),
+                ),
+            )
+            .await?;
+        }
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Todo::Table)
+                    .drop_column(Todo::Position)
+                    .to_owned(),
+            )
+            .await
+    }
+}
+
+#[derive(Iden)]
+enum Todo {
+    Table,
+    Id,
+    Position,
+}
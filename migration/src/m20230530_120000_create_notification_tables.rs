@@ -0,0 +1,254 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(NotificationPreference::Table)
+                    .col(
+                        ColumnDef::new(NotificationPreference::Id)
+                            .big_unsigned()
+                            .not_null()
+                            .auto_increment()
+                            .primary_key(),
+                    )
+                    .col(
+                        ColumnDef::new(NotificationPreference::UserId)
+                            .big_unsigned()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(NotificationPreference::Channel)
+                            .string()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(NotificationPreference::Target)
+                            .string()
+                            .null(),
+                    )
+                    .col(
+                        ColumnDef::new(NotificationPreference::Events)
+                            .string()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(NotificationPreference::Secret)
+                            .string()
+                            .null(),
+                    )
+                    .col(
+                        ColumnDef::new(NotificationPreference::VerificationToken)
+                            .string()
+                            .null(),
+                    )
+                    .col(
+                        ColumnDef::new(NotificationPreference::VerifiedAt)
+                            .big_integer()
+                            .null(),
+                    )
+                    .col(
+                        ColumnDef::new(NotificationPreference::CreatedAt)
+                            .big_integer()
+                            .not_null(),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx-notification_preference-user_id-channel")
+                    .table(NotificationPreference::Table)
+                    .col(NotificationPreference::UserId)
+                    .col(NotificationPreference::Channel)
+                    .unique()
+                    .to_owned(),
+            )
+            .await?;
+        manager
+            .create_table(
+                Table::create()
+                    .table(Notification::Table)
+                    .col(
+                        ColumnDef::new(Notification::Id)
+                            .big_unsigned()
+                            .not_null()
+                            .auto_increment()
+                            .primary_key(),
+                    )
+                    .col(
+                        ColumnDef::new(Notification::Uuid)
+                            .uuid()
+                            .not_null()
+                            .unique_key(),
+                    )
+                    .col(
+                        ColumnDef::new(Notification::UserId)
+                            .big_unsigned()
+                            .not_null(),
+                    )
+                    .col(ColumnDef::new(Notification::Channel).string().not_null())
+                    .col(ColumnDef::new(Notification::Target).string().null())
+                    .col(ColumnDef::new(Notification::EventId).big_unsigned().null())
+                    .col(ColumnDef::new(Notification::Event).string().not_null())
+                    .col(ColumnDef::new(Notification::TodoUuid).uuid().null())
+                    .col(ColumnDef::new(Notification::Subject).string().not_null())
+                    .col(ColumnDef::new(Notification::Body).text().not_null())
+                    .col(ColumnDef::new(Notification::Payload).text().not_null())
+                    .col(ColumnDef::new(Notification::Status).string().not_null())
+                    .col(
+                        ColumnDef::new(Notification::Attempts)
+                            .unsigned()
+                            .not_null()
+                            .default(0),
+                    )
+                    .col(
+                        ColumnDef::new(Notification::NextAttemptAt)
+                            .big_integer()
+                            .null(),
+                    )
+                    .col(ColumnDef::new(Notification::Error).text().null())
+                    .col(
+                        ColumnDef::new(Notification::CreatedAt)
+                            .big_integer()
+                            .not_null(),
+                    )
+                    .col(ColumnDef::new(Notification::SentAt).big_integer().null())
+                    .to_owned(),
+            )
+            .await?;
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx-notification-status-next_attempt_at")
+                    .table(Notification::Table)
+                    .col(Notification::Status)
+                    .col(Notification::NextAttemptAt)
+                    .to_owned(),
+            )
+            .await?;
+        manager
+            .create_table(
+                Table::create()
+                    .table(InboxMessage::Table)
+                    .col(
+                        ColumnDef::new(InboxMessage::Id)
+                            .big_unsigned()
+                            .not_null()
+                            .auto_increment()
+                            .primary_key(),
+                    )
+                    .col(
+                        ColumnDef::new(InboxMessage::Uuid)
+                            .uuid()
+                            .not_null()
+                            .unique_key(),
+                    )
+                    .col(
+                        ColumnDef::new(InboxMessage::UserId)
+                            .big_unsigned()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(InboxMessage::NotificationId)
+                            .big_unsigned()
+                            .not_null(),
+                    )
+                    .col(ColumnDef::new(InboxMessage::Event).string().not_null())
+                    .col(ColumnDef::new(InboxMessage::TodoUuid).uuid().not_null())
+                    .col(ColumnDef::new(InboxMessage::Subject).string().not_null())
+                    .col(ColumnDef::new(InboxMessage::Body).text().not_null())
+                    .col(
+                        ColumnDef::new(InboxMessage::CreatedAt)
+                            .big_integer()
+                            .not_null(),
+                    )
+                    .col(ColumnDef::new(InboxMessage::ReadAt).big_integer().null())
+                    .to_owned(),
+            )
+            .await?;
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx-inbox_message-user_id-read_at")
+                    .table(InboxMessage::Table)
+                    .col(InboxMessage::UserId)
+                    .col(InboxMessage::ReadAt)
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(InboxMessage::Table).to_owned())
+            .await?;
+        manager
+            .drop_table(Table::drop().table(Notification::Table).to_owned())
+            .await?;
+        manager
+            .drop_table(
+                Table::drop()
+                    .table(NotificationPreference::Table)
+                    .to_owned(),
+            )
+            .await
+    }
+}
+
+#[derive(Iden)]
+enum NotificationPreference {
+    Table,
+    Id,
+    UserId,
+    Channel,
+    Target,
+    Events,
+    Secret,
+    VerificationToken,
+    VerifiedAt,
+    CreatedAt,
+}
+
+#[derive(Iden)]
+enum Notification {
+    Table,
+    Id,
+    Uuid,
+    UserId,
+    Channel,
+    Target,
+    EventId,
+    Event,
+    TodoUuid,
+    Subject,
+    Body,
+    Payload,
+    Status,
+    Attempts,
+    NextAttemptAt,
+    Error,
+    CreatedAt,
+    SentAt,
+}
+
+#[derive(Iden)]
+enum InboxMessage {
+    Table,
+    Id,
+    Uuid,
+    UserId,
+    NotificationId,
+    Event,
+    TodoUuid,
+    Subject,
+    Body,
+    CreatedAt,
+    ReadAt,
+}
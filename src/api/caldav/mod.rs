@@ -0,0 +1,45 @@
+use actix_web::{
+    http::{Method, StatusCode},
+    middleware::{DefaultHeaders, ErrorHandlers},
+    web,
+};
+
+pub mod propfind;
+pub mod report;
+pub mod resource;
+pub mod utils;
+pub mod xml;
+
+/// Returns the WebDAV method with the given name
+fn dav_method(name: &str) -> Method {
+    // Safety: The WebDAV method names are valid tokens
+    Method::from_bytes(name.as_bytes()).unwrap()
+}
+
+/// Initialize the CalDAV routes, the user principal is `/caldav/` and the todos calendar is `/caldav/todos/`
+pub fn init_routes(cfg: &mut web::ServiceConfig) {
+    cfg.service(
+        web::scope("/caldav")
+            .wrap(ErrorHandlers::new().handler(StatusCode::UNAUTHORIZED, utils::add_challenge))
+            .wrap(DefaultHeaders::new().add(("DAV", "1, calendar-access")))
+            .service(
+                web::resource(["", "/"])
+                    .route(web::method(dav_method("PROPFIND")).to(propfind::home))
+                    .route(web::method(Method::OPTIONS).to(resource::options)),
+            )
+            .service(
+                web::resource(["/todos", "/todos/"])
+                    .route(web::method(dav_method("PROPFIND")).to(propfind::calendar))
+                    .route(web::method(dav_method("REPORT")).to(report::report))
+                    .route(web::method(Method::OPTIONS).to(resource::options)),
+            )
+            .service(
+                web::resource("/todos/{name}")
+                    .route(web::get().to(resource::get))
+                    .route(web::put().to(resource::put))
+                    .route(web::delete().to(resource::delete))
+                    .route(web::method(dav_method("PROPFIND")).to(propfind::todo))
+                    .route(web::method(Method::OPTIONS).to(resource::options)),
+            ),
+    );
+}
@@ -0,0 +1,120 @@
+use actix_web::{web, HttpRequest, HttpResponse};
+use sea_orm::DatabaseConnection;
+
+use crate::{
+    api::caldav::{
+        utils::{self, Resource, CALENDAR_PATH, HOME_PATH},
+        xml::{self, Element, Multistatus, DAV_NS},
+    },
+    errors::{Error as ApiError, ErrorTrait, Result as ApiResult},
+    schemas::todo::TodoSchema,
+};
+
+/// Parse the PROPFIND request body, if the body is not a `propfind` element returns an error 400
+fn parse_propfind(body: &[u8]) -> ApiResult<Option<Element>> {
+    match xml::parse(body)? {
+        Some(request) if !request.is(DAV_NS, "propfind") => Err(ApiError::BadRequest(
+            "The PROPFIND body should be a `propfind` element".to_owned(),
+        )),
+        request => Ok(request),
+    }
+}
+
+/// Add the response of the resource with the requested properties of the PROPFIND request
+fn add_response(
+    multistatus: &mut Multistatus,
+    request: Option<&Element>,
+    href: &str,
+    resource: &Resource,
+) {
+    let properties = utils::requested_properties(request, resource);
+    utils::add_response(multistatus, href, resource, &properties);
+}
+
+/// Get the properties of the user principal, it's the calendar home too.
+/// With the depth `1` the properties of the todos calendar are included
+pub async fn home(
+    req: HttpRequest,
+    db: web::Data<DatabaseConnection>,
+    body: web::Bytes,
+) -> ApiResult<HttpResponse> {
+    let db = db.get_ref();
+    let user = utils::caldav_auth(&req, db).await?;
+    let request = parse_propfind(&body)?;
+
+    let mut multistatus = Multistatus::default();
+    add_response(
+        &mut multistatus,
+        request.as_ref(),
+        HOME_PATH,
+        &Resource::Home(&user),
+    );
+    if utils::depth(&req) > 0 {
+        let todos = utils::calendar_todos(user.id, db).await?;
+        add_response(
+            &mut multistatus,
+            request.as_ref(),
+            CALENDAR_PATH,
+            &Resource::Calendar(utils::ctag(&todos)),
+        );
+    }
+    Ok(multistatus.finish())
+}
+
+/// Get the properties of the todos calendar, with the depth `1` the properties of its todos are included
+pub async fn calendar(
+    req: HttpRequest,
+    db: web::Data<DatabaseConnection>,
+    body: web::Bytes,
+) -> ApiResult<HttpResponse> {
+    let db = db.get_ref();
+    let user = utils::caldav_auth(&req, db).await?;
+    let request = parse_propfind(&body)?;
+    let todos = utils::calendar_todos(user.id, db).await?;
+
+    let mut multistatus = Multistatus::default();
+    add_response(
+        &mut multistatus,
+        request.as_ref(),
+        CALENDAR_PATH,
+        &Resource::Calendar(utils::ctag(&todos)),
+    );
+    if utils::depth(&req) > 0 {
+        for todo in &todos {
+            add_response(
+                &mut multistatus,
+                request.as_ref(),
+                &utils::todo_href(&todo.uuid),
+                &Resource::Todo(todo),
+            );
+        }
+    }
+    Ok(multistatus.finish())
+}
+
+/// Get the properties of a todo resource
+pub async fn todo(
+    req: HttpRequest,
+    db: web::Data<DatabaseConnection>,
+    name: web::Path<String>,
+    body: web::Bytes,
+) -> ApiResult<HttpResponse> {
+    let db = db.get_ref();
+    let user = utils::caldav_auth(&req, db).await?;
+    let request = parse_propfind(&body)?;
+    let todo = match utils::resource_uuid(&name) {
+        Some(uuid) => utils::find_todo(uuid, user.id, db).await?,
+        None => None,
+    }
+    .map(TodoSchema::from)
+    .not_found_err("There is no todo with the given uuid")?;
+
+    let mut multistatus = Multistatus::default();
+    add_response(
+        &mut multistatus,
+        request.as_ref(),
+        &utils::todo_href(&todo.uuid),
+        &Resource::Todo(&todo),
+    );
+    Ok(multistatus.finish())
+}
@@ -0,0 +1,133 @@
+use actix_web::{web, HttpRequest, HttpResponse};
+use sea_orm::DatabaseConnection;
+
+use crate::{
+    api::{
+        caldav::{
+            utils::{self, Resource},
+            xml::{self, Element, Multistatus, CALDAV_NS, DAV_NS},
+        },
+        todo::ical,
+    },
+    errors::{Error as ApiError, ErrorTrait, Result as ApiResult},
+    schemas::todo::TodoSchema,
+};
+
+/// Returns the value of the VTODO property of the todo, `None` if the todo doesn't have the property
+fn property_value(todo: &TodoSchema, name: &str) -> Option<String> {
+    match name.to_uppercase().as_str() {
+        "UID" => Some(todo.uuid.to_string()),
+        "SUMMARY" => Some(todo.title.clone()),
+        "STATUS" => Some(ical::ical_status(&todo.status).to_owned()),
+        "CREATED" => Some(ical::format_time(todo.created_at)),
+        "DTSTAMP" | "LAST-MODIFIED" => Some(ical::format_time(todo.updated_at)),
+        "SEQUENCE" => Some(todo.version.saturating_sub(1).to_string()),
+        "COMPLETED" => todo.completed_at.map(ical::format_time),
+        _ => None,
+    }
+}
+
+/// Returns `true` if the todo matches the property filter
+fn matches_property(todo: &TodoSchema, filter: &Element) -> bool {
+    let value = property_value(todo, filter.attribute("name").unwrap_or_default());
+    if filter.child(CALDAV_NS, "is-not-defined").is_some() {
+        return value.is_none();
+    }
+    let Some(value) = value else {
+        return false;
+    };
+    filter
+        .children_named(CALDAV_NS, "text-match")
+        .all(|text_match| {
+            let negate = text_match.attribute("negate-condition") == Some("yes");
+            let contains = value
+                .to_lowercase()
+                .contains(&text_match.text.to_lowercase());
+            contains != negate
+        })
+}
+
+/// Returns `true` if the todo matches the component filter of the given component
+/// (`VCALENDAR` or `VTODO`), the time ranges are not checked
+fn matches_component(todo: &TodoSchema, filter: &Element, component: &str) -> bool {
+    if !filter
+        .attribute("name")
+        .map_or(false, |name| name.eq_ignore_ascii_case(component))
+    {
+        return false;
+    }
+    if filter.child(CALDAV_NS, "is-not-defined").is_some() {
+        return false;
+    }
+    let nested_matches = filter
+        .children_named(CALDAV_NS, "comp-filter")
+        .all(|nested| match component {
+            "VCALENDAR" if nested.attribute("name") == Some("VTODO") => {
+                matches_component(todo, nested, "VTODO")
+            }
+            // The todos have no other components
+            _ => nested.child(CALDAV_NS, "is-not-defined").is_some(),
+        });
+    nested_matches
+        && filter
+            .children_named(CALDAV_NS, "prop-filter")
+            .all(|property| component == "VTODO" && matches_property(todo, property))
+}
+
+/// Returns `true` if the todo matches the filter of the calendar query (See RFC 4791 §9.7)
+fn matches_filter(todo: &TodoSchema, filter: &Element) -> bool {
+    filter
+        .children_named(CALDAV_NS, "comp-filter")
+        .all(|calendar| matches_component(todo, calendar, "VCALENDAR"))
+}
+
+/// Add the response of the todo with the requested properties of the report
+fn add_response(multistatus: &mut Multistatus, request: &Element, href: &str, todo: &TodoSchema) {
+    let resource = Resource::Todo(todo);
+    let properties = utils::requested_properties(Some(request), &resource);
+    utils::add_response(multistatus, href, &resource, &properties);
+}
+
+/// Query the todos of the calendar, the `calendar-query` report returns the todos that match the filter
+/// and the `calendar-multiget` report returns the todos of the given hrefs
+pub async fn report(
+    req: HttpRequest,
+    db: web::Data<DatabaseConnection>,
+    body: web::Bytes,
+) -> ApiResult<HttpResponse> {
+    let db = db.get_ref();
+    let user = utils::caldav_auth(&req, db).await?;
+    let request = xml::parse(&body)?.bad_request_err("The REPORT body is required")?;
+    let todos = utils::calendar_todos(user.id, db).await?;
+
+    let mut multistatus = Multistatus::default();
+    if request.is(CALDAV_NS, "calendar-query") {
+        let filter = request.child(CALDAV_NS, "filter");
+        for todo in todos
+            .iter()
+            .filter(|todo| filter.map_or(true, |filter| matches_filter(todo, filter)))
+        {
+            add_response(
+                &mut multistatus,
+                &request,
+                &utils::todo_href(&todo.uuid),
+                todo,
+            );
+        }
+    } else if request.is(CALDAV_NS, "calendar-multiget") {
+        for href in request.children_named(DAV_NS, "href") {
+            let href = href.text.trim();
+            match utils::resource_uuid(href)
+                .and_then(|uuid| todos.iter().find(|todo| todo.uuid == uuid))
+            {
+                Some(todo) => add_response(&mut multistatus, &request, href, todo),
+                None => multistatus.missing(href),
+            }
+        }
+    } else {
+        return Err(ApiError::BadRequest(
+            "The supported reports are `calendar-query` and `calendar-multiget`".to_owned(),
+        ));
+    }
+    Ok(multistatus.finish())
+}
@@ -0,0 +1,156 @@
+use actix_web::{
+    http::header::{self, IfNoneMatch},
+    web, HttpMessage, HttpRequest, HttpResponse,
+};
+use sea_orm::{DatabaseConnection, TransactionTrait};
+
+use crate::{
+    api::{
+        caldav::utils::{self, CALENDAR_CONTENT_TYPE},
+        todo::{ical, utils as todo_utils},
+    },
+    conditional::{self, Validators},
+    errors::{Error as ApiError, ErrorTrait, Result as ApiResult},
+    schemas::todo::TodoSchema,
+};
+
+/// Returns the todo of the resource name, if there is no todo with the name returns an error 404
+async fn find_resource(
+    name: &str,
+    user_id: u32,
+    db: &DatabaseConnection,
+) -> ApiResult<entity::todo::Model> {
+    match utils::resource_uuid(name) {
+        Some(uuid) => utils::find_todo(uuid, user_id, db).await?,
+        None => None,
+    }
+    .not_found_err("There is no todo with the given uuid")
+}
+
+/// The allowed methods of the CalDAV resources, and the WebDAV compliance classes in the `DAV` header
+pub async fn options() -> HttpResponse {
+    HttpResponse::Ok()
+        .insert_header((header::ALLOW, "OPTIONS, GET, PUT, DELETE, PROPFIND, REPORT"))
+        .finish()
+}
+
+/// Get the iCalendar file of a todo, with its entity tag. If the client's copy is fresh returns 304 without a body
+pub async fn get(
+    req: HttpRequest,
+    db: web::Data<DatabaseConnection>,
+    name: web::Path<String>,
+) -> ApiResult<HttpResponse> {
+    let db = db.get_ref();
+    let user = utils::caldav_auth(&req, db).await?;
+    let todo = TodoSchema::from(find_resource(&name, user.id, db).await?);
+
+    let validators = Validators::new(utils::etag(&todo), Some(todo.updated_at));
+    if validators.is_fresh(&req) {
+        let mut response = HttpResponse::NotModified();
+        validators.add_headers(&mut response);
+        return Ok(response.finish());
+    }
+    let mut response = HttpResponse::Ok();
+    validators.add_headers(&mut response);
+    Ok(response
+        .content_type(CALENDAR_CONTENT_TYPE)
+        .body(utils::calendar_data(&todo)))
+}
+
+/// Create or update a todo from an iCalendar file with one VTODO component, the resource name
+/// should be `{uuid}.ics` and the uuid is the uuid of the created todo.
+///
+/// The `If-Match` header is checked against the entity tag of the todo, and `If-None-Match: *`
+/// prevents overwriting an existing todo. If the uuid is used by a todo in the trash or a todo of another
+/// user returns an error 403, without telling which.
+pub async fn put(
+    req: HttpRequest,
+    db: web::Data<DatabaseConnection>,
+    name: web::Path<String>,
+    body: web::Bytes,
+) -> ApiResult<HttpResponse> {
+    let db = db.get_ref();
+    let user = utils::caldav_auth(&req, db).await?;
+    let uuid = utils::resource_uuid(&name)
+        .bad_request_err("The resource name should be a uuid with the `.ics` extension")?;
+    let text =
+        std::str::from_utf8(&body).bad_request_err("The iCalendar file should be UTF-8 text")?;
+    let mut todos = ical::parse_calendar(text)?;
+    if todos.len() != 1 {
+        return Err(ApiError::BadRequest(
+            "The resource should have one VTODO component".to_owned(),
+        ));
+    }
+    let imported = todos.remove(0).map_err(ApiError::BadRequest)?;
+    let actor = todo_utils::Actor::new(user.id, &req);
+
+    let txn = db.begin().await.database_err()?;
+    let (todo, created) = match utils::find_todo(uuid, user.id, &txn).await? {
+        Some(todo) => {
+            let overwrite = !matches!(req.get_header::<IfNoneMatch>(), Some(IfNoneMatch::Any));
+            if !overwrite || !conditional::if_match(&req, &utils::etag(&todo.clone().into())) {
+                return Ok(HttpResponse::PreconditionFailed().finish());
+            }
+            let content = imported.content;
+            let title = (content.title != todo.title).then_some(content.title);
+            let status = (content.status != todo.status).then_some(content.status);
+            let todo = if title.is_some() || status.is_some() {
+                todo_utils::update_todo(todo, title, status, &actor, &txn).await?
+            } else {
+                todo
+            };
+            (todo.into(), false)
+        }
+        None => {
+            if req.headers().contains_key(header::IF_MATCH) {
+                return Ok(HttpResponse::PreconditionFailed().finish());
+            }
+            let todo = todo_utils::create_todo_at(
+                &txn,
+                Some(uuid),
+                imported.content,
+                imported.created_at,
+                imported.completed_at,
+                &actor,
+            )
+            .await
+            .map_err(|err| match err {
+                ApiError::Conflict(_) => ApiError::Forbidden(
+                    "The resource name can't be used, choose another uuid".to_owned(),
+                ),
+                err => err,
+            })?;
+            (todo, true)
+        }
+    };
+    txn.commit().await.database_err()?;
+
+    let mut response = if created {
+        HttpResponse::Created()
+    } else {
+        HttpResponse::NoContent()
+    };
+    Ok(response
+        .insert_header(header::ETag(utils::etag(&todo)))
+        .finish())
+}
+
+/// Delete a todo, the todo is moved to the trash like deleting it in the todos API.
+/// The `If-Match` header is checked against the entity tag of the todo
+pub async fn delete(
+    req: HttpRequest,
+    db: web::Data<DatabaseConnection>,
+    name: web::Path<String>,
+) -> ApiResult<HttpResponse> {
+    let db = db.get_ref();
+    let user = utils::caldav_auth(&req, db).await?;
+    let todo = find_resource(&name, user.id, db).await?;
+    if !conditional::if_match(&req, &utils::etag(&todo.clone().into())) {
+        return Ok(HttpResponse::PreconditionFailed().finish());
+    }
+
+    let txn = db.begin().await.database_err()?;
+    todo_utils::trash_todo(todo, &todo_utils::Actor::new(user.id, &req), &txn).await?;
+    txn.commit().await.database_err()?;
+    Ok(HttpResponse::NoContent().finish())
+}
@@ -0,0 +1,258 @@
+use actix_web::{
+    dev::ServiceResponse,
+    http::header::{self, EntityTag, HeaderValue},
+    middleware::ErrorHandlerResponse,
+    HttpRequest,
+};
+use base64::{engine::general_purpose::STANDARD, Engine};
+use chrono::NaiveDateTime;
+use entity::todo::{Column as TodoColumn, Model as TodoModel};
+use entity::user::Model as UserModel;
+use sea_orm::{ColumnTrait, ConnectionTrait, DatabaseConnection, QueryFilter, QueryOrder};
+use uuid::Uuid;
+
+use crate::{
+    api::{
+        auth::utils::{get_user_by_username_and_password, req_auth},
+        caldav::xml::{self, Element, CALDAV_NS, CALENDARSERVER_NS, DAV_NS},
+        todo::{ical, utils as todo_utils},
+    },
+    conditional::Validators,
+    errors::{Error as ApiError, ErrorTrait, Result as ApiResult},
+    schemas::todo::TodoSchema,
+};
+
+/// The path of the user principal, it's the calendar home too
+pub const HOME_PATH: &str = "/api/caldav/";
+/// The path of the todos calendar collection
+pub const CALENDAR_PATH: &str = "/api/caldav/todos/";
+/// The content type of the todo resources
+pub const CALENDAR_CONTENT_TYPE: &str = "text/calendar; charset=utf-8; component=VTODO";
+/// The authentication challenge of the CalDAV clients
+const CHALLENGE: &str = "Basic realm=\"Oxide Todo\", charset=\"UTF-8\"";
+
+/// A CalDAV resource
+pub enum Resource<'a> {
+    /// The user principal and calendar home
+    Home(&'a UserModel),
+    /// The todos calendar collection, with its ctag
+    Calendar(String),
+    /// A todo resource
+    Todo(&'a TodoSchema),
+}
+
+/// Add the authentication challenge to the `401 Unauthorized` responses,
+/// so the CalDAV clients ask the user for the username and password
+pub fn add_challenge<B>(mut res: ServiceResponse<B>) -> actix_web::Result<ErrorHandlerResponse<B>> {
+    res.response_mut().headers_mut().insert(
+        header::WWW_AUTHENTICATE,
+        HeaderValue::from_static(CHALLENGE),
+    );
+    Ok(ErrorHandlerResponse::Response(res.map_into_left_body()))
+}
+
+/// Returns the user of the request, authenticated by the username and password (HTTP Basic authentication)
+/// or by the Bearer token. If the credentials are missing or incorrect returns an error 401
+pub async fn caldav_auth(req: &HttpRequest, db: &DatabaseConnection) -> ApiResult<UserModel> {
+    let authorization = req
+        .headers()
+        .get(header::AUTHORIZATION)
+        .and_then(|value| value.to_str().ok())
+        .unauthorized_err("The username and password are required")?;
+    let Some(credentials) = authorization.strip_prefix("Basic ") else {
+        return req_auth(req.clone(), db).await;
+    };
+    let credentials = STANDARD
+        .decode(credentials.trim())
+        .ok()
+        .and_then(|credentials| String::from_utf8(credentials).ok())
+        .unauthorized_err("The credentials are invalid")?;
+    let (username, password) = credentials
+        .split_once(':')
+        .unauthorized_err("The credentials are invalid")?;
+    get_user_by_username_and_password(db, username, password)
+        .await
+        .map_err(|_| ApiError::Unauthorized("The username or password is incorrect".to_owned()))
+}
+
+/// Returns the value of the `Depth` header, `0` or `1`. The `infinity` depth is treated as `1`
+pub fn depth(req: &HttpRequest) -> u8 {
+    match req
+        .headers()
+        .get("Depth")
+        .and_then(|value| value.to_str().ok())
+    {
+        Some("0") => 0,
+        _ => 1,
+    }
+}
+
+/// Returns the entity tag of the todo resource, derived from the last modification time.
+/// The version is added, so two changes in the same second have different entity tags
+pub fn etag(todo: &TodoSchema) -> EntityTag {
+    EntityTag::new_strong(format!("{}-{}", todo.updated_at, todo.version))
+}
+
+/// Returns the collection tag, it changes when any todo of the collection is changed, created or deleted
+pub fn ctag(todos: &[TodoSchema]) -> String {
+    let etags = todos
+        .iter()
+        .map(|todo| format!("{}:{}", todo.uuid, etag(todo).tag()))
+        .collect::<Vec<_>>()
+        .join(",");
+    Validators::hashed(etags, None).etag.tag().to_owned()
+}
+
+/// Returns the href of the todo resource
+pub fn todo_href(uuid: &Uuid) -> String {
+    format!("{CALENDAR_PATH}{uuid}.ics")
+}
+
+/// Returns the uuid of the todo resource name or href (`{uuid}.ics`), `None` if it's not a todo resource
+pub fn resource_uuid(href: &str) -> Option<Uuid> {
+    let name = href.trim_end_matches('/').rsplit('/').next()?;
+    Uuid::parse_str(name.strip_suffix(".ics")?).ok()
+}
+
+/// Returns the iCalendar file of the todo resource
+pub fn calendar_data(todo: &TodoSchema) -> String {
+    format!(
+        "{}{}{}",
+        ical::CALENDAR_HEADER,
+        ical::format_todo(todo),
+        ical::CALENDAR_FOOTER
+    )
+}
+
+/// Returns the todos of the user in the calendar collection, in the manual order
+pub async fn calendar_todos(user_id: u32, db: &impl ConnectionTrait) -> ApiResult<Vec<TodoSchema>> {
+    Ok(todo_utils::user_todos(user_id)
+        .order_by_asc(TodoColumn::Position)
+        .order_by_asc(TodoColumn::Id)
+        .all(db)
+        .await
+        .database_err()?
+        .into_iter()
+        .map(TodoSchema::from)
+        .collect())
+}
+
+/// Returns the todo of the user with the given uuid, `None` if the user has no todo with the uuid
+pub async fn find_todo(
+    uuid: Uuid,
+    user_id: u32,
+    db: &impl ConnectionTrait,
+) -> ApiResult<Option<TodoModel>> {
+    todo_utils::user_todos(user_id)
+        .filter(TodoColumn::Uuid.eq(uuid))
+        .one(db)
+        .await
+        .database_err()
+}
+
+/// Returns the properties returned for `allprop` requests (and the requests without a body)
+pub fn all_properties(resource: &Resource) -> Vec<Element> {
+    let names: &[(&str, &str)] = match resource {
+        Resource::Home(_) => &[
+            (DAV_NS, "resourcetype"),
+            (DAV_NS, "displayname"),
+            (DAV_NS, "current-user-principal"),
+        ],
+        Resource::Calendar(_) => &[
+            (DAV_NS, "resourcetype"),
+            (DAV_NS, "displayname"),
+            (DAV_NS, "getetag"),
+            (CALENDARSERVER_NS, "getctag"),
+            (CALDAV_NS, "supported-calendar-component-set"),
+        ],
+        Resource::Todo(_) => &[
+            (DAV_NS, "resourcetype"),
+            (DAV_NS, "getetag"),
+            (DAV_NS, "getcontenttype"),
+            (DAV_NS, "getlastmodified"),
+        ],
+    };
+    names
+        .iter()
+        .map(|(namespace, name)| Element {
+            namespace: (*namespace).to_owned(),
+            name: (*name).to_owned(),
+            ..Default::default()
+        })
+        .collect()
+}
+
+/// Returns the requested properties of the `prop` element, or all the properties
+/// if the request is `allprop` or has no body
+pub fn requested_properties(request: Option<&Element>, resource: &Resource) -> Vec<Element> {
+    match request.and_then(|request| request.child(DAV_NS, "prop")) {
+        Some(prop) => prop.children.clone(),
+        None => all_properties(resource),
+    }
+}
+
+/// Returns the XML value of the resource property, `None` if the resource doesn't have the property
+pub fn property(resource: &Resource, property: &Element) -> Option<String> {
+    let href = |path: &str| format!("<d:href>{path}</d:href>");
+    let privileges = "<d:privilege><d:read/></d:privilege><d:privilege><d:write/></d:privilege>";
+    match (
+        property.namespace.as_str(),
+        property.name.as_str(),
+        resource,
+    ) {
+        (DAV_NS, "current-user-principal" | "principal-URL" | "owner", _) => Some(href(HOME_PATH)),
+        (CALDAV_NS, "calendar-home-set", Resource::Home(_)) => Some(href(HOME_PATH)),
+        (DAV_NS, "current-user-privilege-set", _) => Some(privileges.to_owned()),
+        (DAV_NS, "resourcetype", Resource::Home(_)) => {
+            Some("<d:collection/><d:principal/>".to_owned())
+        }
+        (DAV_NS, "resourcetype", Resource::Calendar(_)) => {
+            Some("<d:collection/><c:calendar/>".to_owned())
+        }
+        (DAV_NS, "resourcetype", Resource::Todo(_)) => Some(String::new()),
+        (DAV_NS, "displayname", Resource::Home(user)) => Some(xml::escape(&user.name)),
+        (DAV_NS, "displayname", Resource::Calendar(_)) => Some("Todos".to_owned()),
+        (DAV_NS, "getetag", Resource::Calendar(ctag)) => Some(xml::escape(
+            &EntityTag::new_strong(ctag.clone()).to_string(),
+        )),
+        (CALENDARSERVER_NS, "getctag", Resource::Calendar(ctag)) => Some(xml::escape(ctag)),
+        (CALDAV_NS, "supported-calendar-component-set", Resource::Calendar(_)) => {
+            Some("<c:comp name=\"VTODO\"/>".to_owned())
+        }
+        (DAV_NS, "supported-report-set", Resource::Calendar(_)) => Some(
+            "<d:supported-report><d:report><c:calendar-query/></d:report></d:supported-report>\
+             <d:supported-report><d:report><c:calendar-multiget/></d:report></d:supported-report>"
+                .to_owned(),
+        ),
+        (DAV_NS, "getetag", Resource::Todo(todo)) => Some(xml::escape(&etag(todo).to_string())),
+        (DAV_NS, "getcontenttype", Resource::Todo(_)) => Some(CALENDAR_CONTENT_TYPE.to_owned()),
+        (DAV_NS, "getlastmodified", Resource::Todo(todo)) => Some(
+            NaiveDateTime::from_timestamp_opt(todo.updated_at, 0)
+                .unwrap_or_default()
+                .format("%a, %d %b %Y %H:%M:%S GMT")
+                .to_string(),
+        ),
+        (CALDAV_NS, "calendar-data", Resource::Todo(todo)) => {
+            Some(xml::escape(&calendar_data(todo)))
+        }
+        _ => None,
+    }
+}
+
+/// Add the response of the resource with the requested properties to the multistatus response
+pub fn add_response(
+    multistatus: &mut xml::Multistatus,
+    href: &str,
+    resource: &Resource,
+    properties: &[Element],
+) {
+    let mut found = Vec::new();
+    let mut not_found = Vec::new();
+    for requested in properties {
+        match property(resource, requested) {
+            Some(value) => found.push((requested, value)),
+            None => not_found.push(requested),
+        }
+    }
+    multistatus.response(href, &found, &not_found);
+}
@@ -0,0 +1,220 @@
+use actix_web::HttpResponse;
+use quick_xml::{events::Event, name::ResolveResult, NsReader};
+
+use crate::errors::{ErrorTrait, Result as ApiResult};
+
+/// The WebDAV namespace
+pub const DAV_NS: &str = "DAV:";
+/// The CalDAV namespace
+pub const CALDAV_NS: &str = "urn:ietf:params:xml:ns:caldav";
+/// The namespace of the calendar server extensions (e.g. `getctag`)
+pub const CALENDARSERVER_NS: &str = "http://calendarserver.org/ns/";
+/// The content type of the XML responses
+pub const XML_CONTENT_TYPE: &str = "application/xml; charset=utf-8";
+
+/// An element of the XML request body, with its namespace
+#[derive(Debug, Clone, Default)]
+pub struct Element {
+    /// The namespace of the element, empty if the element has no namespace
+    pub namespace: String,
+    /// The local name of the element
+    pub name: String,
+    /// The attributes of the element, by their local names
+    pub attributes: Vec<(String, String)>,
+    /// The child elements
+    pub children: Vec<Element>,
+    /// The text content of the element
+    pub text: String,
+}
+
+impl Element {
+    /// Returns `true` if the element has the given namespace and name
+    pub fn is(&self, namespace: &str, name: &str) -> bool {
+        self.namespace == namespace && self.name == name
+    }
+
+    /// Returns the first child element with the given namespace and name
+    pub fn child(&self, namespace: &str, name: &str) -> Option<&Element> {
+        self.children.iter().find(|child| child.is(namespace, name))
+    }
+
+    /// Returns the child elements with the given namespace and name
+    pub fn children_named<'a>(
+        &'a self,
+        namespace: &'a str,
+        name: &'a str,
+    ) -> impl Iterator<Item = &'a Element> {
+        self.children
+            .iter()
+            .filter(move |child| child.is(namespace, name))
+    }
+
+    /// Returns the value of the attribute with the given local name
+    pub fn attribute(&self, name: &str) -> Option<&str> {
+        self.attributes
+            .iter()
+            .find(|(key, _)| key == name)
+            .map(|(_, value)| value.as_str())
+    }
+}
+
+/// Returns the namespace of the resolved name
+fn namespace(resolved: ResolveResult) -> String {
+    match resolved {
+        ResolveResult::Bound(namespace) => String::from_utf8_lossy(namespace.into_inner()).into(),
+        _ => String::new(),
+    }
+}
+
+/// Parse the XML request body, returns `None` if the body is empty.
+/// If the body is not a well-formed XML returns an error 400
+pub fn parse(body: &[u8]) -> ApiResult<Option<Element>> {
+    let body = std::str::from_utf8(body).bad_request_err("The body should be UTF-8 XML")?;
+    let mut reader = NsReader::from_str(body);
+    reader.trim_text(true);
+
+    let mut stack: Vec<Element> = Vec::new();
+    let mut root = None;
+    loop {
+        let (resolved, event) = reader
+            .read_resolved_event()
+            .bad_request_err("The body is not a well-formed XML")?;
+        let (start, empty) = match event {
+            Event::Start(start) => (start, false),
+            Event::Empty(start) => (start, true),
+            Event::End(_) => {
+                let element = stack
+                    .pop()
+                    .bad_request_err("The body is not a well-formed XML")?;
+                match stack.last_mut() {
+                    Some(parent) => parent.children.push(element),
+                    None => root = Some(element),
+                }
+                continue;
+            }
+            Event::Text(text) => {
+                if let Some(element) = stack.last_mut() {
+                    element.text.push_str(
+                        &text
+                            .unescape()
+                            .bad_request_err("The body is not a well-formed XML")?,
+                    );
+                }
+                continue;
+            }
+            Event::Eof => break,
+            _ => continue,
+        };
+        let mut element = Element {
+            namespace: namespace(resolved),
+            name: String::from_utf8_lossy(start.local_name().into_inner()).into(),
+            ..Default::default()
+        };
+        for attribute in start.attributes() {
+            let attribute = attribute.bad_request_err("The body is not a well-formed XML")?;
+            element.attributes.push((
+                String::from_utf8_lossy(attribute.key.local_name().into_inner()).into(),
+                attribute
+                    .unescape_value()
+                    .bad_request_err("The body is not a well-formed XML")?
+                    .into(),
+            ));
+        }
+        match (empty, stack.last_mut()) {
+            (false, _) => stack.push(element),
+            (true, Some(parent)) => parent.children.push(element),
+            (true, None) => root = Some(element),
+        }
+    }
+    if !stack.is_empty() {
+        return Err(crate::errors::Error::BadRequest(
+            "The body is not a well-formed XML".to_owned(),
+        ));
+    }
+    Ok(root)
+}
+
+/// Escape the special characters of a text value, the quotes are not escaped (e.g. in the entity tags)
+pub fn escape(text: &str) -> String {
+    quick_xml::escape::partial_escape(text).into_owned()
+}
+
+/// Returns the qualified name of the element in the multistatus response, with the namespace
+/// declaration if the namespace is not declared in the root element
+fn qualified_name(namespace: &str, name: &str) -> (String, String) {
+    let prefix = match namespace {
+        DAV_NS => "d",
+        CALDAV_NS => "c",
+        CALENDARSERVER_NS => "cs",
+        "" => return (name.to_owned(), String::new()),
+        _ => {
+            return (
+                format!("x:{name}"),
+                format!(" xmlns:x=\"{}\"", quick_xml::escape::escape(namespace)),
+            )
+        }
+    };
+    (format!("{prefix}:{name}"), String::new())
+}
+
+/// A WebDAV multistatus response (See RFC 4918 §13)
+pub struct Multistatus {
+    /// The written XML
+    body: String,
+}
+
+impl Default for Multistatus {
+    fn default() -> Self {
+        Self {
+            body: format!(
+                "<?xml version=\"1.0\" encoding=\"utf-8\"?>\n<d:multistatus xmlns:d=\"{DAV_NS}\" \
+                 xmlns:c=\"{CALDAV_NS}\" xmlns:cs=\"{CALENDARSERVER_NS}\">\n"
+            ),
+        }
+    }
+}
+
+impl Multistatus {
+    /// Add the response of a resource, `found` are the requested properties with their XML values
+    /// and `not_found` are the requested properties that the resource doesn't have
+    pub fn response(&mut self, href: &str, found: &[(&Element, String)], not_found: &[&Element]) {
+        self.body
+            .push_str(&format!("<d:response><d:href>{}</d:href>", escape(href)));
+        if !found.is_empty() {
+            self.body.push_str("<d:propstat><d:prop>");
+            for (property, value) in found {
+                let (name, declaration) = qualified_name(&property.namespace, &property.name);
+                self.body
+                    .push_str(&format!("<{name}{declaration}>{value}</{name}>"));
+            }
+            self.body
+                .push_str("</d:prop><d:status>HTTP/1.1 200 OK</d:status></d:propstat>");
+        }
+        if !not_found.is_empty() {
+            self.body.push_str("<d:propstat><d:prop>");
+            for property in not_found {
+                let (name, declaration) = qualified_name(&property.namespace, &property.name);
+                self.body.push_str(&format!("<{name}{declaration}/>"));
+            }
+            self.body
+                .push_str("</d:prop><d:status>HTTP/1.1 404 Not Found</d:status></d:propstat>");
+        }
+        self.body.push_str("</d:response>\n");
+    }
+
+    /// Add the response of a resource that doesn't exist
+    pub fn missing(&mut self, href: &str) {
+        self.body.push_str(&format!(
+            "<d:response><d:href>{}</d:href><d:status>HTTP/1.1 404 Not Found</d:status></d:response>\n",
+            escape(href)
+        ));
+    }
+
+    /// Returns the `207 Multi-Status` response
+    pub fn finish(mut self) -> HttpResponse {
+        self.body.push_str("</d:multistatus>\n");
+        HttpResponse::MultiStatus()
+            .content_type(XML_CONTENT_TYPE)
+            .body(self.body)
+    }
+}
@@ -0,0 +1,62 @@
+use actix_web::{get, web, HttpResponse};
+use sea_orm::DatabaseConnection;
+use serde::{Deserialize, Serialize};
+use utoipa::IntoParams;
+
+use crate::{
+    api::{
+        calendar::utils,
+        todo::{
+            queries::{TodoFilters, TodoFormat},
+            transfer,
+        },
+    },
+    errors::Result as ApiResult,
+    schemas::message::MessageSchema,
+};
+
+/// The secret token of the calendar feed
+#[derive(IntoParams, Deserialize, Serialize, Debug, Clone, Default)]
+pub struct CalendarFeedQuery {
+    /// The calendar token, create it in `POST /api/calendar/token`
+    #[param(example = "5c1e0d7f9b3a4e2d8f6c1b0a9e8d7c6b5a4f3e2d1c0b9a8f7e6d5c4b3a2f1e0d")]
+    pub token: Option<String>,
+}
+
+/// The todos of the user as an iCalendar feed, a VTODO component per todo.
+///
+/// The feed is authenticated by the calendar token in the `token` parameter instead of the Bearer token,
+/// so the calendar apps can subscribe to it. The todo statuses are mapped to the VTODO statuses:
+/// `pending` is `NEEDS-ACTION`, `progress` is `IN-PROCESS`, `completed` is `COMPLETED` and `cancelled` is `CANCELLED`.
+#[utoipa::path(
+    context_path = "/api",
+    params(CalendarFeedQuery),
+    responses(
+        (
+            status = 200, description = "The iCalendar feed of the todos", body = String, content_type = "text/calendar",
+            example = json!("BEGIN:VCALENDAR\r\nVERSION:2.0\r\nPRODID:-//Oxide Todo//Todos//EN\r\nX-WR-CALNAME:Todos\r\nBEGIN:VTODO\r\nUID:a8bfed8d-4f8b-4150-8ace-3f8916609eba\r\nDTSTAMP:20210503T000000Z\r\nCREATED:20210503T000000Z\r\nLAST-MODIFIED:20210503T000000Z\r\nSEQUENCE:0\r\nSUMMARY:Todo title\r\nSTATUS:NEEDS-ACTION\r\nEND:VTODO\r\nEND:VCALENDAR\r\n")
+        ),
+        (
+            status = 401, description = "The calendar token is invalid", body = MessageSchema,
+            example = json!(MessageSchema::new(401, "The calendar token is invalid"))
+        ),
+    ),
+    tag = "Calendar"
+)]
+#[get("/todos.ics")]
+pub async fn feed(
+    db: web::Data<DatabaseConnection>,
+    query: web::Query<CalendarFeedQuery>,
+) -> ApiResult<HttpResponse> {
+    let user = utils::user_by_calendar_token(query.token.as_deref(), db.get_ref()).await?;
+    let format = TodoFormat::Ics;
+
+    Ok(HttpResponse::Ok()
+        .content_type(format.content_type())
+        .streaming(transfer::export_stream(
+            user.id,
+            format,
+            TodoFilters::default(),
+            db.get_ref().clone(),
+        )))
+}
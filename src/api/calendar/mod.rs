@@ -0,0 +1,14 @@
+use actix_web::web;
+
+pub mod feed;
+pub mod token;
+pub mod utils;
+
+/// Initialize the calendar routes, the feed is `/todos.ics` and the token routes are under `/calendar`
+pub fn init_routes(cfg: &mut web::ServiceConfig) {
+    cfg.service(feed::feed).service(
+        web::scope("/calendar")
+            .service(token::create_token)
+            .service(token::delete_token),
+    );
+}
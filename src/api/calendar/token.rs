@@ -0,0 +1,70 @@
+use actix_web::{delete, post, web, HttpRequest};
+use sea_orm::{ActiveModelTrait, DatabaseConnection, IntoActiveModel, Set};
+
+use crate::{
+    api::{
+        auth::utils::{hash_function, req_auth},
+        webhook::utils::new_secret,
+    },
+    errors::{ErrorTrait, Result as ApiResult},
+    schemas::{calendar::CalendarTokenSchema, message::MessageSchema, traits::OpenApiExample},
+};
+
+/// Create a secret token of the calendar feed, the calendar apps can subscribe to the feed URL without the Bearer token.
+///
+/// The token is returned only once, creating a new token disables the previous one.
+#[utoipa::path(
+    context_path = "/api/calendar",
+    responses(
+        (
+            status = 200, description = "The calendar token and the feed path", body = CalendarTokenSchema,
+            example = json!(CalendarTokenSchema::openapi_example())
+        ),
+    ),
+    tag = "Calendar",
+    security(("Bearer Token" = []))
+)]
+#[post("/token")]
+pub async fn create_token(
+    req: HttpRequest,
+    db: web::Data<DatabaseConnection>,
+) -> ApiResult<CalendarTokenSchema> {
+    let db = db.get_ref();
+    let user = req_auth(req, db).await?;
+
+    // Only the hash of the token is stored, like a password
+    let token = new_secret();
+    let mut user = user.into_active_model();
+    user.calendar_token = Set(Some(hash_function(&token)));
+    user.update(db).await.database_err()?;
+    Ok(CalendarTokenSchema::new(token))
+}
+
+/// Delete the calendar token, the calendar feed is disabled until a new token is created.
+#[utoipa::path(
+    context_path = "/api/calendar",
+    responses(
+        (
+            status = 200, description = "The calendar feed is disabled", body = MessageSchema,
+            example = json!(MessageSchema::new(200, "The calendar token is deleted successfully"))
+        ),
+    ),
+    tag = "Calendar",
+    security(("Bearer Token" = []))
+)]
+#[delete("/token")]
+pub async fn delete_token(
+    req: HttpRequest,
+    db: web::Data<DatabaseConnection>,
+) -> ApiResult<MessageSchema> {
+    let db = db.get_ref();
+    let user = req_auth(req, db).await?;
+
+    let mut user = user.into_active_model();
+    user.calendar_token = Set(None);
+    user.update(db).await.database_err()?;
+    Ok(MessageSchema::new(
+        200,
+        "The calendar token is deleted successfully",
+    ))
+}
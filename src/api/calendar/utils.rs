@@ -0,0 +1,21 @@
+use entity::user::{Column as UserColumn, Entity as UserEntity, Model as UserModel};
+use sea_orm::{ColumnTrait, ConnectionTrait, EntityTrait, QueryFilter};
+
+use crate::{
+    api::auth::utils::hash_function,
+    errors::{ErrorTrait, Result as ApiResult},
+};
+
+/// Returns the user of the calendar token, if the token is invalid returns an error 401
+pub async fn user_by_calendar_token(
+    token: Option<&str>,
+    db: &impl ConnectionTrait,
+) -> ApiResult<UserModel> {
+    let token = token.unauthorized_err("The calendar token is required")?;
+    UserEntity::find()
+        .filter(UserColumn::CalendarToken.eq(hash_function(token)))
+        .one(db)
+        .await
+        .database_err()?
+        .unauthorized_err("The calendar token is invalid")
+}
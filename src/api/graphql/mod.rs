@@ -0,0 +1,154 @@
+use actix_web::{get, http::header, post, web, HttpRequest, HttpResponse};
+use async_graphql::{http::GraphiQLSource, Data, Schema};
+use async_graphql_actix_web::{GraphQLRequest, GraphQLResponse, GraphQLSubscription};
+use sea_orm::DatabaseConnection;
+
+use crate::{
+    api::{
+        auth::utils::{get_user_by_token, req_auth},
+        todo::utils::Actor,
+    },
+    errors::{ErrorTrait, Result as ApiResult},
+    schemas::message::MessageSchema,
+};
+
+pub mod mutation;
+pub mod query;
+pub mod subscription;
+pub mod types;
+
+use mutation::MutationRoot;
+use query::QueryRoot;
+use subscription::SubscriptionRoot;
+
+/// The schema of the GraphQL API
+pub type OxideSchema = Schema<QueryRoot, MutationRoot, SubscriptionRoot>;
+
+/// The maximum depth of the GraphQL queries
+const MAX_QUERY_DEPTH: usize = 10;
+
+/// Build the schema of the GraphQL API
+pub fn schema() -> OxideSchema {
+    Schema::build(QueryRoot, MutationRoot, SubscriptionRoot)
+        .limit_depth(MAX_QUERY_DEPTH)
+        .finish()
+}
+
+/// Execute a GraphQL query or mutation, the schema is in `/api/graphql/schema.graphql`.
+///
+/// The errors of the operations have the status code of the REST endpoints in the `status` extension
+/// (e.g. `404` if there is no todo with the given uuid, `412` if the todo version is outdated).
+#[utoipa::path(
+    context_path = "/api/graphql",
+    request_body = GraphQLRequestSchema,
+    responses(
+        (status = 200, description = "The result of the operation, with its errors"),
+        (
+            status = 401, description = "The token is missing or invalid", body = MessageSchema,
+            example = json!(MessageSchema::new(401, "The token is invalid"))
+        ),
+    ),
+    tag = "GraphQL",
+    security(("Bearer Token" = []))
+)]
+#[post("")]
+pub async fn execute(
+    req: HttpRequest,
+    request: GraphQLRequest,
+    schema: web::Data<OxideSchema>,
+    db: web::Data<DatabaseConnection>,
+) -> ApiResult<GraphQLResponse> {
+    let user = req_auth(req.clone(), db.get_ref()).await?;
+    let actor = Actor::new(user.id, &req);
+    let request = request
+        .into_inner()
+        .data(user)
+        .data(db.get_ref().clone())
+        .data(actor);
+    Ok(schema.execute(request).await.into())
+}
+
+/// Open a WebSocket connection to run the GraphQL subscriptions, with the `graphql-transport-ws`
+/// or the `graphql-ws` protocol.
+///
+/// The connection is authenticated by the Bearer token of the upgrade request, or by the `token`
+/// field of the `connection_init` payload (e.g. `{"token": "..."}`).
+#[utoipa::path(
+    context_path = "/api/graphql",
+    responses(
+        (status = 101, description = "The connection is upgraded to WebSocket"),
+        (
+            status = 400, description = "The request is not a GraphQL WebSocket upgrade", body = MessageSchema,
+            example = json!(MessageSchema::new(400, "The request is not a GraphQL WebSocket upgrade"))
+        ),
+    ),
+    tag = "GraphQL",
+    security((), ("Bearer Token" = []))
+)]
+#[get("")]
+pub async fn subscribe(
+    req: HttpRequest,
+    payload: web::Payload,
+    schema: web::Data<OxideSchema>,
+    db: web::Data<DatabaseConnection>,
+) -> ApiResult<HttpResponse> {
+    let db = db.get_ref().clone();
+    let subscription = GraphQLSubscription::new(schema.get_ref().clone());
+    let subscription = if req.headers().contains_key(header::AUTHORIZATION) {
+        let user = req_auth(req.clone(), &db).await?;
+        let mut data = Data::default();
+        data.insert(user);
+        data.insert(db);
+        subscription.with_data(data).start(&req, payload)
+    } else {
+        subscription
+            .on_connection_init(|payload| async move {
+                let token = payload
+                    .get("token")
+                    .and_then(|token| token.as_str())
+                    .ok_or_else(|| async_graphql::Error::new("The token is required"))?;
+                let user = get_user_by_token(&db, token)
+                    .await
+                    .map_err(types::graphql_error)?;
+                let mut data = Data::default();
+                data.insert(user);
+                data.insert(db);
+                Ok(data)
+            })
+            .start(&req, payload)
+    };
+    subscription.bad_request_err("The request is not a GraphQL WebSocket upgrade")
+}
+
+/// Returns the schema of the GraphQL API in the GraphQL schema language
+#[get("/schema.graphql")]
+pub async fn sdl(schema: web::Data<OxideSchema>) -> HttpResponse {
+    HttpResponse::Ok()
+        .content_type("text/plain; charset=utf-8")
+        .body(schema.sdl())
+}
+
+/// The GraphiQL playground of the GraphQL API
+#[get("/playground")]
+pub async fn playground() -> HttpResponse {
+    HttpResponse::Ok()
+        .content_type("text/html; charset=utf-8")
+        .body(
+            GraphiQLSource::build()
+                .endpoint("/api/graphql")
+                .subscription_endpoint("/api/graphql")
+                .finish(),
+        )
+}
+
+/// Initialize the GraphQL routes, all the routes are under `/graphql`
+pub fn init_routes(cfg: &mut web::ServiceConfig) {
+    cfg.service(
+        web::scope("/graphql")
+            .app_data(web::Data::new(schema()))
+            .service(execute)
+            .service(subscribe)
+            .service(sdl)
+            .service(playground),
+    );
+}
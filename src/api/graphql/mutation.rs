@@ -0,0 +1,88 @@
+use async_graphql::{Context, Object};
+use sea_orm::TransactionTrait;
+use uuid::Uuid;
+
+use crate::{
+    api::{
+        graphql::types::{self, graphql_error, GraphQLTodoStatus},
+        todo::utils::{self, Actor},
+    },
+    errors::ErrorTrait,
+    schemas::todo::{TodoContentSchema, TodoSchema},
+};
+
+/// The mutations of the GraphQL API, they are validated like the REST endpoints
+pub struct MutationRoot;
+
+#[Object]
+impl MutationRoot {
+    /// Create a new todo, the title should be unique
+    async fn create_todo(
+        &self,
+        ctx: &Context<'_>,
+        title: String,
+        #[graphql(default_with = "GraphQLTodoStatus::Pending")] status: GraphQLTodoStatus,
+    ) -> async_graphql::Result<TodoSchema> {
+        let (_, db) = types::context(ctx)?;
+        let actor = ctx.data::<Actor>()?;
+        let txn = db.begin().await.database_err().map_err(graphql_error)?;
+        let todo = utils::create_todo(
+            &txn,
+            TodoContentSchema {
+                title,
+                status: status.into(),
+            },
+            actor,
+        )
+        .await
+        .map_err(graphql_error)?;
+        txn.commit().await.database_err().map_err(graphql_error)?;
+        Ok(todo)
+    }
+
+    /// Update the title or the status of a todo. To prevent overwriting others changes, send the todo `version`
+    async fn update_todo(
+        &self,
+        ctx: &Context<'_>,
+        uuid: Uuid,
+        title: Option<String>,
+        status: Option<GraphQLTodoStatus>,
+        version: Option<u32>,
+    ) -> async_graphql::Result<TodoSchema> {
+        let (user, db) = types::context(ctx)?;
+        let actor = ctx.data::<Actor>()?;
+        let txn = db.begin().await.database_err().map_err(graphql_error)?;
+        let todo = utils::find_todo_by_uuid(uuid, user.id, &txn)
+            .await
+            .map_err(graphql_error)?;
+        utils::check_todo_version(&todo, version).map_err(graphql_error)?;
+        // The title is not changed if it's the same title
+        let title = title.filter(|title| title != &todo.title);
+        let todo = utils::update_todo(todo, title, status.map(Into::into), actor, &txn)
+            .await
+            .map_err(graphql_error)?;
+        txn.commit().await.database_err().map_err(graphql_error)?;
+        Ok(todo.into())
+    }
+
+    /// Delete a todo, the todo is moved to the trash. To prevent deleting others changes, send the todo `version`
+    async fn delete_todo(
+        &self,
+        ctx: &Context<'_>,
+        uuid: Uuid,
+        version: Option<u32>,
+    ) -> async_graphql::Result<TodoSchema> {
+        let (user, db) = types::context(ctx)?;
+        let actor = ctx.data::<Actor>()?;
+        let txn = db.begin().await.database_err().map_err(graphql_error)?;
+        let todo = utils::find_todo_by_uuid(uuid, user.id, &txn)
+            .await
+            .map_err(graphql_error)?;
+        utils::check_todo_version(&todo, version).map_err(graphql_error)?;
+        let todo = utils::trash_todo(todo, actor, &txn)
+            .await
+            .map_err(graphql_error)?;
+        txn.commit().await.database_err().map_err(graphql_error)?;
+        Ok(todo.into())
+    }
+}
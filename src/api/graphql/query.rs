@@ -0,0 +1,70 @@
+use async_graphql::{Context, Object};
+use uuid::Uuid;
+
+use crate::{
+    api::{
+        graphql::types::{self, TodoConnection, TodoFilter, User},
+        todo::{
+            queries::{TodoFilters, TodoOrder, TodoOrderBy},
+            utils,
+        },
+    },
+    schemas::todo::TodoSchema,
+};
+
+/// The queries of the GraphQL API
+pub struct QueryRoot;
+
+#[Object]
+impl QueryRoot {
+    /// The authenticated user
+    async fn me(&self) -> User {
+        User
+    }
+
+    /// The todos of the user, filterable by status and title like the todos list.
+    /// The todos are paginated with the connection arguments, 10 todos by default and 100 at most
+    #[allow(clippy::too_many_arguments)]
+    async fn todos(
+        &self,
+        ctx: &Context<'_>,
+        filter: Option<TodoFilter>,
+        #[graphql(desc = "Order the todos by (default: `CREATED_AT`)")] order_by: Option<
+            TodoOrderBy,
+        >,
+        #[graphql(
+            desc = "Order the todos (default: `NEWER`, or `OLDER` when ordering by `POSITION`)"
+        )]
+        order: Option<TodoOrder>,
+        after: Option<String>,
+        before: Option<String>,
+        first: Option<i32>,
+        last: Option<i32>,
+    ) -> async_graphql::Result<TodoConnection> {
+        types::todos_connection(
+            ctx,
+            filter.unwrap_or_default(),
+            TodoFilters {
+                order_by,
+                order,
+                ..Default::default()
+            },
+            (after, before, first, last),
+        )
+        .await
+    }
+
+    /// A single todo by uuid, `null` if there is no todo with the given uuid
+    async fn todo(
+        &self,
+        ctx: &Context<'_>,
+        uuid: Uuid,
+    ) -> async_graphql::Result<Option<TodoSchema>> {
+        let (user, db) = types::context(ctx)?;
+        match utils::find_todo_by_uuid(uuid, user.id, db).await {
+            Ok(todo) => Ok(Some(todo.into())),
+            Err(crate::errors::Error::NotFound(_)) => Ok(None),
+            Err(err) => Err(types::graphql_error(err)),
+        }
+    }
+}
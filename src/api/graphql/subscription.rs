@@ -0,0 +1,42 @@
+use async_graphql::{Context, Subscription};
+use futures_util::{stream, Stream, StreamExt};
+
+use crate::{
+    api::{
+        graphql::types::{self, graphql_error},
+        sync,
+        todo::events,
+    },
+    schemas::todo::TodoEventSchema,
+};
+
+/// The subscriptions of the GraphQL API
+pub struct SubscriptionRoot;
+
+#[Subscription]
+impl SubscriptionRoot {
+    /// The change events of the user todos, the same events of the events stream.
+    /// The events after the given event id are sent first, the default is the last event
+    async fn todo_events(
+        &self,
+        ctx: &Context<'_>,
+        after: Option<u32>,
+    ) -> async_graphql::Result<impl Stream<Item = async_graphql::Result<TodoEventSchema>>> {
+        let (user, db) = types::context(ctx)?;
+        let after = match after {
+            Some(after) => after,
+            None => sync::last_change(user.id, db)
+                .await
+                .map_err(graphql_error)?,
+        };
+        Ok(
+            events::event_stream(user.id, after, db.clone()).flat_map(|batch| {
+                let events = match batch {
+                    Ok(batch) => batch.into_iter().map(Ok).collect(),
+                    Err(err) => vec![Err(graphql_error(err))],
+                };
+                stream::iter(events)
+            }),
+        )
+    }
+}
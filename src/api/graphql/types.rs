@@ -0,0 +1,245 @@
+use async_graphql::{
+    connection::{self, Connection, Edge},
+    Context, Enum, ErrorExtensions, InputObject, Object, SimpleObject,
+};
+use entity::todo::{Column as TodoColumn, Status as TodoStatus};
+use entity::user::Model as UserModel;
+use sea_orm::{DatabaseConnection, PaginatorTrait, QueryOrder, QuerySelect};
+use uuid::Uuid;
+
+use crate::{
+    api::todo::{
+        queries::{TodoFilters, TodoOrder, TodoOrderBy},
+        utils,
+    },
+    errors::{Error as ApiError, ErrorTrait},
+    schemas::todo::{TodoEventSchema, TodoEventType, TodoSchema},
+};
+
+/// The default number of todos in a page, if `first` and `last` are not given
+const DEFAULT_PAGE_SIZE: usize = 10;
+/// The maximum number of todos in a page
+const MAX_PAGE_SIZE: usize = 100;
+
+/// The status of a todo
+#[derive(Enum, Debug, Clone, Copy, PartialEq, Eq)]
+#[graphql(name = "TodoStatus", remote = "TodoStatus")]
+pub enum GraphQLTodoStatus {
+    /// The todo is completed
+    Completed,
+    /// The todo is pending
+    Pending,
+    /// The todo is in progress
+    Progress,
+    /// The todo is cancelled
+    Cancelled,
+}
+
+/// The type of a todo event
+#[derive(Enum, Debug, Clone, Copy, PartialEq, Eq)]
+#[graphql(name = "TodoEventType", remote = "TodoEventType")]
+pub enum GraphQLTodoEventType {
+    /// The todo is created
+    Created,
+    /// The todo is updated or restored from the trash
+    Updated,
+    /// The todo is moved to the trash
+    Deleted,
+}
+
+/// Convert the API error to a GraphQL error, the status code of the error is in the `status` extension
+pub fn graphql_error(err: ApiError) -> async_graphql::Error {
+    let status = actix_web::ResponseError::status_code(&err).as_u16();
+    (&err).extend_with(|_, extensions| extensions.set("status", status))
+}
+
+/// Returns the authenticated user and the database connection of the request
+pub fn context<'a>(
+    ctx: &'a Context<'_>,
+) -> async_graphql::Result<(&'a UserModel, &'a DatabaseConnection)> {
+    Ok((ctx.data::<UserModel>()?, ctx.data::<DatabaseConnection>()?))
+}
+
+/// The filters of the todos, like the filters of the todos list
+#[derive(InputObject, Debug, Clone, Default)]
+pub struct TodoFilter {
+    /// Filter by status (default: all)
+    pub status: Option<GraphQLTodoStatus>,
+    /// Filter by title (default: all)
+    pub title: Option<String>,
+}
+
+/// The additional fields of the todos connection
+#[derive(SimpleObject, Debug, Clone)]
+pub struct TodoConnectionFields {
+    /// The total number of the todos that match the filter
+    pub total_count: u64,
+}
+
+/// The connection of the todos, the cursors are the offsets of the todos
+pub type TodoConnection = Connection<usize, TodoSchema, TodoConnectionFields>;
+
+#[Object(name = "Todo")]
+impl TodoSchema {
+    /// The id of the todo
+    async fn uuid(&self) -> Uuid {
+        self.uuid
+    }
+
+    /// The title of the todo
+    async fn title(&self) -> &str {
+        &self.title
+    }
+
+    /// The status of the todo
+    async fn status(&self) -> GraphQLTodoStatus {
+        self.status.clone().into()
+    }
+
+    /// The created time of the todo (Unix timestamp)
+    async fn created_at(&self) -> i64 {
+        self.created_at
+    }
+
+    /// The updated time of the todo (Unix timestamp)
+    async fn updated_at(&self) -> i64 {
+        self.updated_at
+    }
+
+    /// The completed time of the todo (Unix timestamp), `null` if the todo is not completed
+    async fn completed_at(&self) -> Option<i64> {
+        self.completed_at
+    }
+
+    /// The version of the todo, incremented on every update
+    async fn version(&self) -> u32 {
+        self.version
+    }
+
+    /// The rank of the todo in the manual order of the todos
+    async fn position(&self) -> &str {
+        &self.position
+    }
+}
+
+#[Object(name = "TodoEvent")]
+impl TodoEventSchema {
+    /// The id of the event, the events are ordered by it
+    async fn id(&self) -> u32 {
+        self.id
+    }
+
+    /// The type of the event
+    async fn event(&self) -> GraphQLTodoEventType {
+        self.event.into()
+    }
+
+    /// The todo after the change
+    async fn todo(&self) -> &TodoSchema {
+        &self.todo
+    }
+}
+
+/// The authenticated user
+pub struct User;
+
+#[Object]
+impl User {
+    /// The name of the user
+    async fn name<'a>(&self, ctx: &'a Context<'_>) -> async_graphql::Result<&'a str> {
+        Ok(&context(ctx)?.0.name)
+    }
+
+    /// The todos of the user, check `Query.todos`
+    #[allow(clippy::too_many_arguments)]
+    async fn todos(
+        &self,
+        ctx: &Context<'_>,
+        filter: Option<TodoFilter>,
+        order_by: Option<TodoOrderBy>,
+        order: Option<TodoOrder>,
+        after: Option<String>,
+        before: Option<String>,
+        first: Option<i32>,
+        last: Option<i32>,
+    ) -> async_graphql::Result<TodoConnection> {
+        todos_connection(
+            ctx,
+            filter.unwrap_or_default(),
+            TodoFilters {
+                order_by,
+                order,
+                ..Default::default()
+            },
+            (after, before, first, last),
+        )
+        .await
+    }
+}
+
+/// Returns a page of the user todos that match the filter, in the order of the filters.
+/// The page is selected by the connection arguments (`after`, `before`, `first` and `last`)
+pub async fn todos_connection(
+    ctx: &Context<'_>,
+    filter: TodoFilter,
+    order: TodoFilters,
+    (after, before, first, last): (Option<String>, Option<String>, Option<i32>, Option<i32>),
+) -> async_graphql::Result<TodoConnection> {
+    let (user, db) = context(ctx)?;
+    let filters = TodoFilters {
+        status: filter.status.map(Into::into),
+        title: filter.title,
+        ..order
+    };
+    let query = filters.filter(utils::user_todos(user.id));
+
+    connection::query(
+        after,
+        before,
+        first,
+        last,
+        |after: Option<usize>, before: Option<usize>, first, last| async move {
+            let total = query
+                .clone()
+                .count(db)
+                .await
+                .database_err()
+                .map_err(graphql_error)?;
+            let count = total as usize;
+            let mut start = after.map_or(0, |after| after.saturating_add(1)).min(count);
+            let mut end = before.unwrap_or(count).clamp(start, count);
+            if first.is_none() && last.is_none() {
+                end = end.min(start + DEFAULT_PAGE_SIZE);
+            }
+            if let Some(first) = first {
+                end = end.min(start + first.min(MAX_PAGE_SIZE));
+            }
+            if let Some(last) = last {
+                start = start.max(end.saturating_sub(last.min(MAX_PAGE_SIZE)));
+            }
+
+            let todos = query
+                .order_by(TodoColumn::from(filters.order_by()), filters.order().into())
+                .order_by_asc(TodoColumn::Id)
+                .offset(start as u64)
+                .limit((end - start) as u64)
+                .all(db)
+                .await
+                .database_err()
+                .map_err(graphql_error)?;
+            let mut connection = Connection::with_additional_fields(
+                start > 0,
+                end < count,
+                TodoConnectionFields { total_count: total },
+            );
+            connection.edges.extend(
+                todos
+                    .into_iter()
+                    .enumerate()
+                    .map(|(idx, todo)| Edge::new(start + idx, TodoSchema::from(todo))),
+            );
+            Ok::<_, async_graphql::Error>(connection)
+        },
+    )
+    .await
+}
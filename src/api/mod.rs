@@ -1,15 +1,36 @@
 use actix_web::web;
 
+use crate::idempotency::Idempotency;
+
 pub mod auth;
+pub mod caldav;
+pub mod calendar;
+pub mod graphql;
+pub mod notification;
 pub mod server_metadata;
+pub mod sync;
 pub mod todo;
+pub mod undo;
+pub mod webhook;
+pub mod ws;
 
 /// Initialize the api routes, all the routes are under `/api`
 pub fn init_routes(cfg: &mut web::ServiceConfig) {
     cfg.service(
         web::scope("/api")
+            // Replay the responses of the requests with `Idempotency-Key` header
+            .wrap(Idempotency)
             .service(server_metadata::get_server_metadata)
             .configure(auth::init_routes)
-            .configure(todo::init_routes),
+            .configure(todo::init_routes)
+            .configure(webhook::init_routes)
+            .configure(notification::init_routes)
+            .configure(calendar::init_routes)
+            .configure(caldav::init_routes)
+            .configure(graphql::init_routes)
+            .service(undo::undo)
+            .service(sync::sync)
+            .service(sync::push)
+            .service(ws::ws),
     );
 }
@@ -0,0 +1,134 @@
+use actix_web::{get, post, web, HttpRequest};
+use chrono::Utc;
+use entity::inbox_message::{
+    ActiveModel as NewMessage, Column as MessageColumn, Entity as MessageEntity,
+};
+use sea_orm::{
+    ActiveModelTrait, ColumnTrait, DatabaseConnection, EntityTrait, PaginatorTrait, QueryFilter,
+    QueryOrder, QuerySelect, Set,
+};
+use serde::{Deserialize, Serialize};
+use utoipa::IntoParams;
+use uuid::Uuid;
+
+use crate::{
+    api::auth::utils::req_auth,
+    errors::{ErrorTrait, Result as ApiResult},
+    schemas::{
+        message::MessageSchema,
+        notification::{InboxMessageSchema, InboxSchema},
+        traits::OpenApiExample,
+    },
+};
+
+/// The maximum number of the returned messages
+const RECENT_MESSAGES: u64 = 50;
+
+/// The filter of the inbox messages
+#[derive(IntoParams, Deserialize, Serialize, Debug, Clone, Default)]
+pub struct InboxQuery {
+    /// Return only the unread messages
+    #[param(example = true)]
+    #[serde(default)]
+    pub unread: bool,
+}
+
+/// Get the inbox of the user, the newest messages first (up to 50 messages), with the number of the unread messages.
+///
+/// The messages are delivered by the `inbox` notification channel.
+#[utoipa::path(
+    context_path = "/api/notifications",
+    params(InboxQuery),
+    responses(
+        (
+            status = 200, description = "The inbox of the user", body = InboxSchema,
+            example = json!(InboxSchema::openapi_example())
+        ),
+    ),
+    tag = "Notification",
+    security(("Bearer Token" = []))
+)]
+#[get("/inbox")]
+pub async fn inbox(
+    req: HttpRequest,
+    db: web::Data<DatabaseConnection>,
+    query: web::Query<InboxQuery>,
+) -> ApiResult<InboxSchema> {
+    let db = db.get_ref();
+    let user = req_auth(req, db).await?;
+
+    let unread_select = MessageEntity::find()
+        .filter(MessageColumn::UserId.eq(user.id))
+        .filter(MessageColumn::ReadAt.is_null());
+    let select = if query.unread {
+        unread_select.clone()
+    } else {
+        MessageEntity::find().filter(MessageColumn::UserId.eq(user.id))
+    };
+    Ok(InboxSchema {
+        unread: unread_select.count(db).await.database_err()?,
+        data: select
+            .order_by_desc(MessageColumn::Id)
+            .limit(RECENT_MESSAGES)
+            .all(db)
+            .await
+            .database_err()?
+            .into_iter()
+            .map(InboxMessageSchema::from)
+            .collect(),
+    })
+}
+
+/// Mark an inbox message as read, the read messages keep their first read time.
+#[utoipa::path(
+    context_path = "/api/notifications",
+    params(
+        (
+            "uuid", description = "The uuid of the message",
+            example = "1e2d3c4b-5a69-4788-9a0b-c1d2e3f4a5b6"
+        )
+    ),
+    responses(
+        (
+            status = 200, description = "The read message", body = InboxMessageSchema,
+            example = json!(InboxMessageSchema {
+                read_at: Some(1620000100),
+                ..Default::default()
+            })
+        ),
+        (
+            status = 404, description = "There is no message with the given uuid", body = MessageSchema,
+            example = json!(MessageSchema::new(404, "There is no message with the given uuid"))
+        ),
+    ),
+    tag = "Notification",
+    security(("Bearer Token" = []))
+)]
+#[post("/inbox/{uuid}/read")]
+pub async fn read_message(
+    req: HttpRequest,
+    db: web::Data<DatabaseConnection>,
+    uuid: web::Path<Uuid>,
+) -> ApiResult<InboxMessageSchema> {
+    let db = db.get_ref();
+    let user = req_auth(req, db).await?;
+    let message = MessageEntity::find()
+        .filter(MessageColumn::Uuid.eq(uuid.into_inner()))
+        .filter(MessageColumn::UserId.eq(user.id))
+        .one(db)
+        .await
+        .database_err()?
+        .not_found_err("There is no message with the given uuid")?;
+    if message.read_at.is_some() {
+        return Ok(message.into());
+    }
+
+    Ok(NewMessage {
+        read_at: Set(Some(Utc::now().naive_utc().timestamp())),
+        ..message.into()
+    }
+    .update(db)
+    .await
+    .database_err()?
+    .into())
+}
@@ -0,0 +1,66 @@
+use actix_web::{get, web, HttpRequest};
+use entity::notification::{
+    Column as NotificationColumn, Entity as NotificationEntity, Status as NotificationStatus,
+};
+use sea_orm::{ColumnTrait, DatabaseConnection, EntityTrait, QueryFilter, QueryOrder, QuerySelect};
+use serde::{Deserialize, Serialize};
+use utoipa::IntoParams;
+
+use crate::{
+    api::auth::utils::req_auth,
+    errors::{ErrorTrait, Result as ApiResult},
+    schemas::{
+        notification::{NotificationListSchema, NotificationSchema},
+        traits::OpenApiExample,
+    },
+};
+
+/// The maximum number of the returned notifications
+const RECENT_NOTIFICATIONS: u64 = 50;
+
+/// The status filter of the notifications
+#[derive(IntoParams, Deserialize, Serialize, Debug, Clone, Default)]
+pub struct NotificationsQuery {
+    /// Filter the notifications by status (`pending`, `sent` or `failed`)
+    #[param(value_type = Option<String>, example = "failed")]
+    pub status: Option<NotificationStatus>,
+}
+
+/// Get the recent notifications of the user on all the channels, the newest first (up to 50 notifications).
+#[utoipa::path(
+    context_path = "/api/notifications",
+    params(NotificationsQuery),
+    responses(
+        (
+            status = 200, description = "The recent notifications of the user", body = NotificationListSchema,
+            example = json!(NotificationListSchema::openapi_example())
+        ),
+    ),
+    tag = "Notification",
+    security(("Bearer Token" = []))
+)]
+#[get("")]
+pub async fn list(
+    req: HttpRequest,
+    db: web::Data<DatabaseConnection>,
+    query: web::Query<NotificationsQuery>,
+) -> ApiResult<NotificationListSchema> {
+    let db = db.get_ref();
+    let user = req_auth(req, db).await?;
+
+    let mut select = NotificationEntity::find().filter(NotificationColumn::UserId.eq(user.id));
+    if let Some(status) = query.status {
+        select = select.filter(NotificationColumn::Status.eq(status));
+    }
+    Ok(NotificationListSchema {
+        data: select
+            .order_by_desc(NotificationColumn::Id)
+            .limit(RECENT_NOTIFICATIONS)
+            .all(db)
+            .await
+            .database_err()?
+            .into_iter()
+            .map(NotificationSchema::from)
+            .collect(),
+    })
+}
@@ -0,0 +1,17 @@
+use actix_web::web;
+
+pub mod inbox;
+pub mod list;
+pub mod preferences;
+
+pub fn init_routes(cfg: &mut web::ServiceConfig) {
+    cfg.service(
+        web::scope("/notifications")
+            .service(list::list)
+            .service(preferences::get_preferences)
+            .service(preferences::update_preferences)
+            .service(preferences::verify_email)
+            .service(inbox::inbox)
+            .service(inbox::read_message),
+    );
+}
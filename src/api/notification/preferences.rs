@@ -0,0 +1,263 @@
+use std::collections::HashSet;
+
+use actix_web::{get, post, put, web, HttpRequest};
+use chrono::Utc;
+use entity::notification_preference::{
+    ActiveModel as NewPreference, Channel, Column as PreferenceColumn, Entity as PreferenceEntity,
+    Model as PreferenceModel,
+};
+use lettre::Address;
+use sea_orm::{
+    ActiveEnum, ActiveModelTrait, ColumnTrait, DatabaseConnection, EntityTrait, IntoActiveModel,
+    QueryFilter, QueryOrder, Set, TransactionTrait,
+};
+
+use crate::{
+    api::{auth::utils::req_auth, webhook::utils as webhook_utils},
+    errors::{Error as ApiError, ErrorTrait, Result as ApiResult},
+    notifications::{self, email},
+    schemas::{
+        message::MessageSchema,
+        notification::{
+            NotificationPreferenceSchema, NotificationPreferencesSchema, VerifyEmailSchema,
+        },
+        todo::TodoEventType,
+        traits::OpenApiExample,
+    },
+};
+
+/// Check the preference of a channel, the target should match the channel and the events should not be empty
+async fn check_preference(preference: &NotificationPreferenceSchema) -> ApiResult<()> {
+    if preference.events.is_empty() {
+        return Err(ApiError::BadRequest(format!(
+            "The `{}` channel should have at least one event",
+            preference.channel.to_value()
+        )));
+    }
+    match (preference.channel, preference.target.as_deref()) {
+        (Channel::Email, _) if !email::is_enabled() => Err(ApiError::BadRequest(
+            "The email notifications are not enabled on this server".to_owned(),
+        )),
+        (Channel::Email, Some(target)) => target
+            .parse::<Address>()
+            .map(|_| ())
+            .bad_request_err("The email address is invalid"),
+        (Channel::Email, None) => Err(ApiError::BadRequest(
+            "The `email` channel should have a target email address".to_owned(),
+        )),
+        (Channel::Webhook, Some(target)) => webhook_utils::check_url(target).await,
+        (Channel::Webhook, None) => Err(ApiError::BadRequest(
+            "The `webhook` channel should have a target URL".to_owned(),
+        )),
+        (Channel::Inbox, Some(_)) => Err(ApiError::BadRequest(
+            "The `inbox` channel doesn't have a target".to_owned(),
+        )),
+        (Channel::Inbox, None) => Ok(()),
+    }
+}
+
+/// Get the notification preferences of the user, the channels that are not listed are disabled.
+#[utoipa::path(
+    context_path = "/api/notifications",
+    responses(
+        (
+            status = 200, description = "The notification preferences of the user", body = NotificationPreferencesSchema,
+            example = json!(NotificationPreferencesSchema::openapi_example())
+        ),
+    ),
+    tag = "Notification",
+    security(("Bearer Token" = []))
+)]
+#[get("/preferences")]
+pub async fn get_preferences(
+    req: HttpRequest,
+    db: web::Data<DatabaseConnection>,
+) -> ApiResult<NotificationPreferencesSchema> {
+    let db = db.get_ref();
+    let user = req_auth(req, db).await?;
+
+    Ok(NotificationPreferencesSchema {
+        data: PreferenceEntity::find()
+            .filter(PreferenceColumn::UserId.eq(user.id))
+            .order_by_asc(PreferenceColumn::Id)
+            .all(db)
+            .await
+            .database_err()?
+            .into_iter()
+            .map(NotificationPreferenceSchema::from)
+            .collect(),
+    })
+}
+
+/// Replace the notification preferences of the user, choose which events are sent to which channel.
+///
+/// The `email` channel sends the notifications to the target email address (only if the server has an SMTP server)
+/// after it's verified, a new address gets an email with the token to verify it (sent in the background like the notifications),
+/// set the channel again to resend it.
+/// The `webhook` channel posts the events to the target URL signed with the `secret` of the response, the secret is
+/// returned only when the URL is set. The `inbox` channel stores them in the user inbox.
+/// Send an empty list to disable the notifications. The notifications are dispatched in the background,
+/// so they may arrive a few seconds after the change.
+#[utoipa::path(
+    context_path = "/api/notifications",
+    request_body = NotificationPreferencesSchema,
+    responses(
+        (
+            status = 200, description = "The new notification preferences", body = NotificationPreferencesSchema,
+            example = json!(NotificationPreferencesSchema::openapi_example())
+        ),
+        (
+            status = 400, description = "A channel is repeated", body = MessageSchema,
+            example = json!(MessageSchema::new(400, "The `email` channel is repeated"))
+        ),
+        (
+            status = 400, description = "The target doesn't match the channel", body = MessageSchema,
+            example = json!(MessageSchema::new(400, "The email address is invalid"))
+        ),
+        (
+            status = 400, description = "The events are empty", body = MessageSchema,
+            example = json!(MessageSchema::new(400, "The `email` channel should have at least one event"))
+        ),
+        (
+            status = 400, description = "The email notifications are disabled", body = MessageSchema,
+            example = json!(MessageSchema::new(400, "The email notifications are not enabled on this server"))
+        ),
+    ),
+    tag = "Notification",
+    security(("Bearer Token" = []))
+)]
+#[put("/preferences")]
+pub async fn update_preferences(
+    req: HttpRequest,
+    db: web::Data<DatabaseConnection>,
+    payload: web::Json<NotificationPreferencesSchema>,
+) -> ApiResult<NotificationPreferencesSchema> {
+    let db = db.get_ref();
+    let user = req_auth(req, db).await?;
+    let preferences = payload.into_inner().data;
+
+    let mut channels = HashSet::new();
+    for preference in &preferences {
+        if !channels.insert(preference.channel) {
+            return Err(ApiError::BadRequest(format!(
+                "The `{}` channel is repeated",
+                preference.channel.to_value()
+            )));
+        }
+        check_preference(preference).await?;
+    }
+
+    let txn = db.begin().await.database_err()?;
+    let current = PreferenceEntity::find()
+        .filter(PreferenceColumn::UserId.eq(user.id))
+        .all(&txn)
+        .await
+        .database_err()?;
+    // The verified email address and the webhook secret are kept if the target is unchanged
+    let unchanged = |channel: Channel, target: &Option<String>| -> Option<&PreferenceModel> {
+        current
+            .iter()
+            .find(|preference| preference.channel == channel && &preference.target == target)
+    };
+    notifications::remove_pending_verification(user.id, &txn).await?;
+    PreferenceEntity::delete_many()
+        .filter(PreferenceColumn::UserId.eq(user.id))
+        .exec(&txn)
+        .await
+        .database_err()?;
+    let now = Utc::now().naive_utc().timestamp();
+    let mut data = Vec::with_capacity(preferences.len());
+    for preference in preferences {
+        let current = unchanged(preference.channel, &preference.target);
+        let (secret, verification_token, verified_at) = match preference.channel {
+            Channel::Email => match current.and_then(|current| current.verified_at) {
+                Some(verified_at) => (None, None, Some(verified_at)),
+                None => (None, Some(webhook_utils::new_secret()), None),
+            },
+            Channel::Webhook => (
+                Some(
+                    current
+                        .and_then(|current| current.secret.clone())
+                        .unwrap_or_else(webhook_utils::new_secret),
+                ),
+                None,
+                None,
+            ),
+            Channel::Inbox => (None, None, None),
+        };
+        let with_secret = preference.channel == Channel::Webhook && current.is_none();
+        let preference = NewPreference {
+            user_id: Set(user.id),
+            channel: Set(preference.channel),
+            target: Set(preference.target),
+            events: Set(TodoEventType::ALL
+                .iter()
+                .filter(|event| preference.events.contains(event))
+                .map(TodoEventType::as_str)
+                .collect::<Vec<_>>()
+                .join(",")),
+            secret: Set(secret),
+            verification_token: Set(verification_token),
+            verified_at: Set(verified_at),
+            created_at: Set(now),
+            ..Default::default()
+        }
+        .insert(&txn)
+        .await
+        .database_err()?;
+        if let (Some(target), Some(token)) = (&preference.target, &preference.verification_token) {
+            notifications::queue_verification(user.id, target, token, &txn).await?;
+        }
+        data.push(NotificationPreferenceSchema::new(preference, with_secret));
+    }
+    txn.commit().await.database_err()?;
+    log::info!("Updated the notification preferences of `{}`", user.name);
+
+    Ok(NotificationPreferencesSchema { data })
+}
+
+/// Verify the email address of the `email` channel with the token of the verification email,
+/// the email notifications are sent only after the address is verified.
+#[utoipa::path(
+    context_path = "/api/notifications",
+    request_body = VerifyEmailSchema,
+    responses(
+        (
+            status = 200, description = "The verified preference of the `email` channel", body = NotificationPreferenceSchema,
+            example = json!(NotificationPreferenceSchema {
+                verified: true,
+                ..NotificationPreferenceSchema::default()
+            })
+        ),
+        (
+            status = 400, description = "The token is invalid", body = MessageSchema,
+            example = json!(MessageSchema::new(400, "The verification token is invalid"))
+        ),
+    ),
+    tag = "Notification",
+    security(("Bearer Token" = []))
+)]
+#[post("/preferences/verify")]
+pub async fn verify_email(
+    req: HttpRequest,
+    db: web::Data<DatabaseConnection>,
+    payload: web::Json<VerifyEmailSchema>,
+) -> ApiResult<NotificationPreferenceSchema> {
+    let db = db.get_ref();
+    let user = req_auth(req, db).await?;
+
+    let mut preference = PreferenceEntity::find()
+        .filter(PreferenceColumn::UserId.eq(user.id))
+        .filter(PreferenceColumn::Channel.eq(Channel::Email))
+        .filter(PreferenceColumn::VerificationToken.eq(payload.into_inner().token))
+        .one(db)
+        .await
+        .database_err()?
+        .ok_or_else(|| ApiError::BadRequest("The verification token is invalid".to_owned()))?
+        .into_active_model();
+    preference.verification_token = Set(None);
+    preference.verified_at = Set(Some(Utc::now().naive_utc().timestamp()));
+    let preference = preference.update(db).await.database_err()?;
+    log::info!("Verified the notification email of `{}`", user.name);
+    Ok(preference.into())
+}
@@ -0,0 +1,284 @@
+use std::collections::HashMap;
+
+use actix_web::{get, post, web, HttpRequest};
+use entity::todo::{Column as TodoColumn, Entity as TodoEntity, Status as TodoStatus};
+use entity::todo_history::{Column as HistoryColumn, Entity as HistoryEntity};
+use sea_orm::{
+    sea_query::Query, ColumnTrait, ConnectionTrait, DatabaseConnection, EntityTrait, QueryFilter,
+    QueryOrder, TransactionTrait,
+};
+
+use crate::{
+    api::auth::utils::req_auth,
+    api::todo::{queries::SyncQuery, utils},
+    errors::{Error as ApiError, ErrorTrait, Result as ApiResult},
+    schemas::{
+        message::MessageSchema,
+        sync::{
+            SyncChangeResultSchema, SyncChangeSchema, SyncChangeStatus, SyncOperation,
+            SyncPushResultSchema, SyncPushSchema, SyncSchema, TodoTombstoneSchema,
+        },
+        todo::{TodoContentSchema, TodoSchema},
+        traits::OpenApiExample,
+    },
+};
+
+/// The maximum number of changes in one push
+const MAX_PUSH_CHANGES: usize = 100;
+
+/// Returns the id of the last change of the user todos, `0` if there is no change
+pub async fn last_change(user_id: u32, db: &impl ConnectionTrait) -> ApiResult<u32> {
+    Ok(HistoryEntity::find()
+        .filter(HistoryColumn::UserId.eq(user_id))
+        .order_by_desc(HistoryColumn::Id)
+        .one(db)
+        .await
+        .database_err()?
+        .map_or(0, |change| change.id))
+}
+
+/// Returns the todos that are changed after the given change, with the tombstones of the deleted todos
+async fn changes_since(
+    user_id: u32,
+    since: u32,
+    token: String,
+    db: &impl ConnectionTrait,
+) -> ApiResult<SyncSchema> {
+    let changed = || {
+        Query::select()
+            .column(HistoryColumn::TodoUuid)
+            .from(HistoryEntity)
+            .and_where(HistoryColumn::UserId.eq(user_id))
+            .and_where(HistoryColumn::Id.gt(since))
+            .to_owned()
+    };
+    let todos = TodoEntity::find()
+        .filter(TodoColumn::UserId.eq(user_id))
+        .filter(TodoColumn::Uuid.in_subquery(changed()))
+        .order_by_asc(TodoColumn::Position)
+        .all(db)
+        .await
+        .database_err()?;
+    // The time of the last change of each todo, it's the deletion time of the permanently deleted todos
+    let mut changed_at: HashMap<_, i64> = HashMap::new();
+    for change in HistoryEntity::find()
+        .filter(HistoryColumn::UserId.eq(user_id))
+        .filter(HistoryColumn::Id.gt(since))
+        .all(db)
+        .await
+        .database_err()?
+    {
+        let time = changed_at.entry(change.todo_uuid).or_default();
+        *time = (*time).max(change.created_at);
+    }
+
+    let (active, trashed): (Vec<_>, Vec<_>) = todos
+        .into_iter()
+        .partition(|todo| todo.deleted_at.is_none());
+    for todo in active.iter().chain(&trashed) {
+        changed_at.remove(&todo.uuid);
+    }
+    let mut deleted: Vec<_> = trashed
+        .into_iter()
+        .filter_map(|todo| Some(TodoTombstoneSchema::new(todo.uuid, todo.deleted_at?)))
+        .chain(
+            changed_at
+                .into_iter()
+                .map(|(uuid, deleted_at)| TodoTombstoneSchema::new(uuid, deleted_at)),
+        )
+        .collect();
+    deleted.sort_by_key(|tombstone| tombstone.deleted_at);
+    Ok(SyncSchema {
+        token,
+        todos: active.into_iter().map(From::from).collect(),
+        deleted,
+    })
+}
+
+/// Get the changes of the user todos since the last sync, for the offline clients.
+///
+/// Without `since` returns all the todos, otherwise returns the todos that are created or updated after the `since` token
+/// with their current state, and the tombstones of the todos that are deleted after it (moved to the trash or permanently deleted).
+/// Send the returned `token` in the `since` parameter of the next sync.
+#[utoipa::path(
+    context_path = "/api",
+    params(SyncQuery),
+    responses(
+        (
+            status = 200, description = "The changes since the last sync", body = SyncSchema,
+            example = json!(SyncSchema::openapi_example())
+        ),
+        (
+            status = 400, description = "The sync token is invalid", body = MessageSchema,
+            example = json!(MessageSchema::new(400, "The sync token is invalid, sync without `since` to get all the todos"))
+        ),
+    ),
+    tag = "Todo",
+    security(("Bearer Token" = []))
+)]
+#[get("/sync")]
+pub async fn sync(
+    req: HttpRequest,
+    db: web::Data<DatabaseConnection>,
+    params: web::Query<SyncQuery>,
+) -> ApiResult<SyncSchema> {
+    let db = db.get_ref();
+    let user = req_auth(req.clone(), db).await?;
+    let since = params.since()?;
+
+    // Read the changes and the token from the same snapshot
+    let txn = db.begin().await.database_err()?;
+    let last_change = last_change(user.id, &txn).await?;
+    let token = last_change.to_string();
+    let sync = match since {
+        None => SyncSchema {
+            token,
+            todos: utils::user_todos(user.id)
+                .order_by_asc(TodoColumn::Position)
+                .all(&txn)
+                .await
+                .database_err()?
+                .into_iter()
+                .map(From::from)
+                .collect(),
+            deleted: Vec::new(),
+        },
+        Some(since) if since > last_change => {
+            return Err(ApiError::BadRequest(
+                "The sync token is invalid, sync without `since` to get all the todos".to_owned(),
+            ))
+        }
+        Some(since) => changes_since(user.id, since, token, &txn).await?,
+    };
+    txn.commit().await.database_err()?;
+    Ok(sync)
+}
+
+/// Apply a single change, returns the todo after the change.
+/// If the given version is not the current version, returns an error 412 with the current todo
+pub async fn apply_change(
+    change: &SyncChangeSchema,
+    actor: &utils::Actor,
+    db: &impl ConnectionTrait,
+) -> ApiResult<TodoSchema> {
+    if change.op == SyncOperation::Create {
+        let title = change
+            .title
+            .clone()
+            .bad_request_err("The `title` is required to create a todo")?;
+        let status = change.status.clone().unwrap_or(TodoStatus::Pending);
+        return utils::create_todo(db, TodoContentSchema { title, status }, actor).await;
+    }
+    let uuid = change
+        .uuid
+        .bad_request_err("The `uuid` is required to update or delete a todo")?;
+    let todo = utils::find_todo_by_uuid(uuid, actor.user_id, db).await?;
+    utils::check_todo_version(&todo, change.version)?;
+    if change.op == SyncOperation::Update {
+        // If the title is not changed, then set it to None
+        let title = change.title.clone().filter(|title| title != &todo.title);
+        utils::update_todo(todo, title, change.status.clone(), actor, db).await
+    } else {
+        utils::trash_todo(todo, actor, db).await
+    }
+    .map(From::from)
+}
+
+/// Apply a single pushed change, the version of the todo is required for `update` and `delete`
+async fn push_change(
+    change: &SyncChangeSchema,
+    actor: &utils::Actor,
+    db: &impl ConnectionTrait,
+) -> ApiResult<TodoSchema> {
+    if change.op != SyncOperation::Create && change.version.is_none() {
+        return Err(ApiError::BadRequest(
+            "The `version` is required to update or delete a todo".to_owned(),
+        ));
+    }
+    apply_change(change, actor, db).await
+}
+
+/// Returns the result of a single pushed change, the errors of the change are reported as rejected except the server errors
+async fn change_result(
+    change: &SyncChangeSchema,
+    actor: &utils::Actor,
+    db: &impl ConnectionTrait,
+) -> ApiResult<SyncChangeResultSchema> {
+    match push_change(change, actor, db).await {
+        Ok(todo) => Ok(SyncChangeResultSchema::new(
+            Some(todo.uuid),
+            SyncChangeStatus::Applied,
+            Some(todo),
+            None,
+        )),
+        Err(ApiError::PreconditionFailed(todo)) => Ok(SyncChangeResultSchema::new(
+            Some(todo.uuid),
+            SyncChangeStatus::Conflict,
+            Some(*todo),
+            None,
+        )),
+        Err(err @ ApiError::InternalServer(_)) => Err(err),
+        Err(err) => Ok(SyncChangeResultSchema::new(
+            change.uuid,
+            SyncChangeStatus::Rejected,
+            None,
+            Some(err.into()),
+        )),
+    }
+}
+
+/// Push the changes made by the client while offline, each change has the last version of the todo that the client knows.
+///
+/// The changes are applied in order in a single transaction, the result of each change is reported:
+/// - `applied`: The change is applied, returns the todo after the change.
+/// - `conflict`: The todo is changed after the client's version, returns the server copy of the todo.
+/// - `rejected`: The change is invalid (e.g. duplicate title), returns the error.
+///
+/// The conflicted and rejected changes are not applied, and don't affect the other changes.
+#[utoipa::path(
+    context_path = "/api",
+    request_body = SyncPushSchema,
+    responses(
+        (
+            status = 200, description = "The result of each change", body = SyncPushResultSchema,
+            example = json!(SyncPushResultSchema::openapi_example())
+        ),
+        (
+            status = 400, description = "Too many changes", body = MessageSchema,
+            example = json!(MessageSchema::new(400, "The maximum number of changes in one push is 100"))
+        ),
+    ),
+    tag = "Todo",
+    security(("Bearer Token" = []))
+)]
+#[post("/sync")]
+pub async fn push(
+    req: HttpRequest,
+    db: web::Data<DatabaseConnection>,
+    payload: web::Json<SyncPushSchema>,
+) -> ApiResult<SyncPushResultSchema> {
+    let db = db.get_ref();
+    let user = req_auth(req.clone(), db).await?;
+    let actor = utils::Actor::new(user.id, &req);
+    if payload.changes.len() > MAX_PUSH_CHANGES {
+        return Err(ApiError::BadRequest(format!(
+            "The maximum number of changes in one push is {MAX_PUSH_CHANGES}"
+        )));
+    }
+
+    let txn = db.begin().await.database_err()?;
+    let mut results = Vec::new();
+    for change in &payload.changes {
+        // Each change has its own savepoint, so a partially applied change is rolled back alone
+        let savepoint = txn.begin().await.database_err()?;
+        let result = change_result(change, &actor, &savepoint).await?;
+        if result.status == SyncChangeStatus::Applied {
+            savepoint.commit().await.database_err()?;
+        } else {
+            savepoint.rollback().await.database_err()?;
+        }
+        results.push(result);
+    }
+    txn.commit().await.database_err()?;
+    Ok(SyncPushResultSchema::new(results))
+}
@@ -0,0 +1,193 @@
+use std::collections::{HashMap, HashSet};
+
+use actix_web::{post, web, HttpRequest};
+use entity::todo::{Column as TodoColumn, Model as TodoModel};
+use sea_orm::{
+    ColumnTrait, ConnectionTrait, DatabaseConnection, QueryFilter, QueryOrder, QuerySelect,
+    TransactionTrait,
+};
+use uuid::Uuid;
+
+use crate::{
+    api::auth::utils::req_auth,
+    api::todo::utils,
+    errors::{Error as ApiError, ErrorTrait, Result as ApiResult},
+    schemas::{
+        message::MessageSchema,
+        todo::{
+            BulkAction, BulkItemStatus, BulkTodoItemSchema, BulkTodoResultSchema, BulkTodoSchema,
+        },
+        traits::OpenApiExample,
+    },
+};
+
+/// The maximum number of todos in one bulk operation
+const MAX_BULK_TODOS: usize = 100;
+
+/// Returns the error of selecting more than the maximum number of todos
+fn too_many_todos() -> ApiError {
+    ApiError::BadRequest(format!(
+        "The maximum number of todos in one bulk operation is {MAX_BULK_TODOS}"
+    ))
+}
+
+/// Returns the selected todos of the bulk operation, the todo is `None` if there is no todo with the uuid
+async fn select_todos(
+    payload: &BulkTodoSchema,
+    user_id: u32,
+    db: &impl ConnectionTrait,
+) -> ApiResult<Vec<(Uuid, Option<TodoModel>)>> {
+    let query = utils::user_todos(user_id);
+    match (&payload.uuids, &payload.filter) {
+        (Some(uuids), None) if uuids.len() > MAX_BULK_TODOS => Err(too_many_todos()),
+        (Some(uuids), None) => {
+            let mut uuids = uuids.clone();
+            // Remove the duplicates and keep the order
+            let mut seen = HashSet::new();
+            uuids.retain(|uuid| seen.insert(*uuid));
+            let mut todos = query
+                .filter(TodoColumn::Uuid.is_in(uuids.clone()))
+                .all(db)
+                .await
+                .database_err()?
+                .into_iter()
+                .map(|todo| (todo.uuid, todo))
+                .collect::<HashMap<_, _>>();
+            Ok(uuids
+                .into_iter()
+                .map(|uuid| (uuid, todos.remove(&uuid)))
+                .collect())
+        }
+        (None, Some(filter)) => {
+            let mut query = filter
+                .filter(query)
+                .order_by(TodoColumn::from(filter.order_by()), filter.order().into());
+            query = match filter.limit {
+                Some(limit) if limit > MAX_BULK_TODOS as u64 => return Err(too_many_todos()),
+                Some(limit) => query.limit(limit),
+                // Select one more todo than the maximum, to know if the filter selects too many todos
+                None => query.limit(MAX_BULK_TODOS as u64 + 1),
+            };
+            if let Some(offset) = filter.offset {
+                query = query.offset(offset);
+            }
+            let todos = query.all(db).await.database_err()?;
+            if todos.len() > MAX_BULK_TODOS {
+                return Err(too_many_todos());
+            }
+            Ok(todos
+                .into_iter()
+                .map(|todo| (todo.uuid, Some(todo)))
+                .collect())
+        }
+        (Some(_), Some(_)) => Err(ApiError::BadRequest(
+            "The `uuids` and `filter` can't be used together".to_owned(),
+        )),
+        (None, None) => Err(ApiError::BadRequest(
+            "The todos should be selected by `uuids` or `filter`".to_owned(),
+        )),
+    }
+}
+
+/// Apply the bulk action on a single todo, the bad request errors are reported as rejected
+async fn apply_action(
+    payload: &BulkTodoSchema,
+    todo: TodoModel,
+    actor: &utils::Actor,
+    db: &impl ConnectionTrait,
+) -> ApiResult<BulkTodoItemSchema> {
+    let uuid = todo.uuid;
+    let result = match payload.action {
+        BulkAction::SetStatus => {
+            let status = payload
+                .status
+                .clone()
+                .bad_request_err("The `status` is required for `set_status` action")?;
+            if todo.status == status {
+                return Ok(BulkTodoItemSchema::new(
+                    uuid,
+                    BulkItemStatus::Unchanged,
+                    Some(todo.into()),
+                    None,
+                ));
+            }
+            utils::update_todo(todo, None, Some(status), actor, db).await
+        }
+        BulkAction::Delete => utils::trash_todo(todo, actor, db).await,
+    };
+    match result {
+        Ok(todo) => Ok(BulkTodoItemSchema::new(
+            uuid,
+            BulkItemStatus::Applied,
+            Some(todo.into()),
+            None,
+        )),
+        Err(ApiError::BadRequest(message)) => Ok(BulkTodoItemSchema::new(
+            uuid,
+            BulkItemStatus::Rejected,
+            None,
+            Some(message),
+        )),
+        Err(err) => Err(err),
+    }
+}
+
+/// Apply one action on multiple todos, selected by uuids or by the list filters.
+///
+/// All the changes are applied in a single transaction, and the result of each todo is reported.
+/// At most 100 todos can be selected in one operation.
+/// Set `dry_run` to `true` to report what would change without applying it.
+#[utoipa::path(
+    context_path = "/api/todos",
+    request_body = BulkTodoSchema,
+    responses(
+        (
+            status = 200, description = "The result of each selected todo", body = BulkTodoResultSchema,
+            example = json!(BulkTodoResultSchema::openapi_example())
+        ),
+        (
+            status = 400, description = "The todos are not selected", body = MessageSchema,
+            example = json!(MessageSchema::new(400, "The todos should be selected by `uuids` or `filter`"))
+        ),
+        (
+            status = 400, description = "Too many todos are selected", body = MessageSchema,
+            example = json!(MessageSchema::new(400, "The maximum number of todos in one bulk operation is 100"))
+        ),
+        (
+            status = 400, description = "The status is missing", body = MessageSchema,
+            example = json!(MessageSchema::new(400, "The `status` is required for `set_status` action"))
+        ),
+    ),
+    tag = "Todo",
+    security(("Bearer Token" = []))
+)]
+#[post("/bulk")]
+pub async fn bulk(
+    req: HttpRequest,
+    db: web::Data<DatabaseConnection>,
+    payload: web::Json<BulkTodoSchema>,
+) -> ApiResult<BulkTodoResultSchema> {
+    let db = db.get_ref();
+    let user = req_auth(req.clone(), db).await?;
+    let actor = utils::Actor::new(user.id, &req);
+    if payload.action == BulkAction::SetStatus && payload.status.is_none() {
+        return Err(ApiError::BadRequest(
+            "The `status` is required for `set_status` action".to_owned(),
+        ));
+    }
+
+    let txn = db.begin().await.database_err()?;
+    let mut results = Vec::new();
+    for (uuid, todo) in select_todos(&payload, user.id, &txn).await? {
+        results.push(match todo {
+            Some(todo) => apply_action(&payload, todo, &actor, &txn).await?,
+            None => BulkTodoItemSchema::new(uuid, BulkItemStatus::NotFound, None, None),
+        });
+    }
+    if payload.dry_run {
+        txn.rollback().await.database_err()?;
+    } else {
+        txn.commit().await.database_err()?;
+    }
+    Ok(BulkTodoResultSchema::new(payload.dry_run, results))
+}
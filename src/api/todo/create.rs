@@ -1,11 +1,11 @@
 use crate::api::auth::utils as auth_utils;
 use crate::api::todo::utils;
-use crate::errors::Result as ApiResult;
+use crate::errors::{ErrorTrait, Result as ApiResult};
 use crate::schemas::todo::TodoSchema;
 use crate::schemas::traits::OpenApiExample;
 use crate::schemas::{message::MessageSchema, todo::TodoContentSchema};
 use actix_web::{post, web, HttpRequest};
-use sea_orm::DatabaseConnection;
+use sea_orm::{DatabaseConnection, TransactionTrait};
 
 /// Create a new todo.
 /// Note: Check the `TodoContentSchema` schema (It's the request body)
@@ -41,10 +41,12 @@ pub async fn create(
 ) -> ApiResult<TodoSchema> {
     log::info!("Creating a new todo: {}", payload.title);
     let db = db.get_ref();
-    let user = auth_utils::req_auth(req, db).await?;
+    let user = auth_utils::req_auth(req.clone(), db).await?;
     let payload = payload.into_inner();
 
-    utils::create_todo(db, payload, user.id)
-        .await
-        .map(From::from)
+    // The todo and its history entry are saved together
+    let txn = db.begin().await.database_err()?;
+    let todo = utils::create_todo(&txn, payload, &utils::Actor::new(user.id, &req)).await?;
+    txn.commit().await.database_err()?;
+    Ok(todo)
 }
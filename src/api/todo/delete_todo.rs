@@ -3,24 +3,27 @@ use actix_web::{
     web::{self, Path},
     HttpRequest,
 };
-use sea_orm::{entity::ModelTrait, DatabaseConnection};
+use sea_orm::{DatabaseConnection, TransactionTrait};
 use uuid::Uuid;
 
 use crate::{
     api::auth::utils::req_auth,
-    api::todo::utils,
+    api::todo::{queries::VersionQuery, utils},
     errors::{ErrorTrait, Result as ApiResult},
     schemas::{message::MessageSchema, todo::TodoSchema, traits::OpenApiExample},
 };
 
-/// Delete a single todo by uuid.
+/// Delete a single todo by uuid, the todo is moved to the trash and can be restored until it's purged.
+///
+/// To prevent deleting others changes, send the todo `ETag` in `If-Match` header or its version in `version` parameter.
 #[utoipa::path(
     context_path = "/api/todos",
     params(
         (
             "uuid", description = "The uuid of the todo",
             example = "b5a5d4e4-7d4e-4f4a-9f3d-3f3f3f3f3f3f"
-        )
+        ),
+        VersionQuery
     ),
     responses(
         (
@@ -30,6 +33,10 @@ use crate::{
         (
             status = 404, description = "There is no todo with the given uuid", body = MessageSchema,
             example = json!(MessageSchema::new(404, "There is no todo with the given uuid"))
+        ),
+        (
+            status = 412, description = "The todo has been modified, returns the current todo", body = TodoSchema,
+            example = json!(TodoSchema::openapi_example())
         )
     ),
     tag = "Todo",
@@ -40,11 +47,15 @@ pub async fn delete_todo(
     req: HttpRequest,
     db: web::Data<DatabaseConnection>,
     uuid: Path<Uuid>,
+    version: web::Query<VersionQuery>,
 ) -> ApiResult<TodoSchema> {
     let db = db.get_ref();
     let uuid = uuid.into_inner();
-    let user = req_auth(req, db).await?;
+    let user = req_auth(req.clone(), db).await?;
     let todo = utils::find_todo_by_uuid(uuid, user.id, db).await?;
-    todo.clone().delete(db).await.database_err()?;
+    utils::check_version(&req, &todo, version.version)?;
+    let txn = db.begin().await.database_err()?;
+    let todo = utils::trash_todo(todo, &utils::Actor::new(user.id, &req), &txn).await?;
+    txn.commit().await.database_err()?;
     Ok(todo.into())
 }
@@ -1,20 +1,20 @@
 use actix_web::{delete, web, HttpRequest};
-use entity::todo::{Column as TodoColumn, Entity as TodoEntity};
-use sea_orm::{ColumnTrait, DatabaseConnection, EntityTrait, QueryFilter};
+use sea_orm::{DatabaseConnection, TransactionTrait};
 
 use crate::{
     api::auth::utils::req_auth,
+    api::todo::utils,
     errors::{ErrorTrait, Result as ApiResult},
     schemas::message::MessageSchema,
 };
 
-/// Delete all todos.
+/// Delete all todos, the todos are moved to the trash and can be restored until they are purged.
 #[utoipa::path(
     context_path = "/api/todos",
     responses(
         (
             status = 200, description = "Delete a single todo by uuid", body = MessageSchema,
-            example = json!{MessageSchema::new(200, "All todos deleted successfully")}
+            example = json!{MessageSchema::new(200, "All todos moved to the trash successfully")}
         )
     ),
     tag = "Todo",
@@ -26,12 +26,17 @@ pub async fn delete_todos(
     db: web::Data<DatabaseConnection>,
 ) -> ApiResult<MessageSchema> {
     let db = db.get_ref();
-    let user = req_auth(req, db).await?;
+    let user = req_auth(req.clone(), db).await?;
+    let actor = utils::Actor::new(user.id, &req);
 
-    TodoEntity::delete_many()
-        .filter(TodoColumn::UserId.eq(user.id))
-        .exec(db)
-        .await
-        .database_err()?;
-    Ok(MessageSchema::new(200, "All todos deleted successfully"))
+    // The todos are trashed one by one, so each one has its own history entry
+    let txn = db.begin().await.database_err()?;
+    for todo in utils::user_todos(user.id).all(&txn).await.database_err()? {
+        utils::trash_todo(todo, &actor, &txn).await?;
+    }
+    txn.commit().await.database_err()?;
+    Ok(MessageSchema::new(
+        200,
+        "All todos moved to the trash successfully",
+    ))
 }
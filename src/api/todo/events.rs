@@ -0,0 +1,146 @@
+use std::time::Duration;
+
+use actix_web::{
+    get,
+    http::header::{CacheControl, CacheDirective},
+    rt::time,
+    web, HttpRequest, HttpResponse,
+};
+use entity::todo_history::{Column as HistoryColumn, Entity as HistoryEntity};
+use futures_util::{stream, Stream, StreamExt};
+use sea_orm::{
+    ColumnTrait, ConnectionTrait, DatabaseConnection, EntityTrait, QueryFilter, QueryOrder,
+    QuerySelect,
+};
+
+use crate::{
+    api::{auth::utils::req_auth, sync},
+    errors::{Error as ApiError, ErrorTrait, Result as ApiResult},
+    schemas::{message::MessageSchema, todo::TodoEventSchema},
+};
+
+/// The interval of checking the new events
+const POLL_INTERVAL: Duration = Duration::from_secs(1);
+/// The interval of the keep-alive messages when there are no events, the closed connections are detected by them
+const KEEP_ALIVE_INTERVAL: Duration = Duration::from_secs(15);
+/// The maximum number of events that are read at once
+const EVENTS_BATCH_SIZE: u64 = 100;
+
+/// Returns the events of the user todos after the given event id, the oldest first
+pub async fn events_after(
+    user_id: u32,
+    after: u32,
+    db: &impl ConnectionTrait,
+) -> ApiResult<Vec<TodoEventSchema>> {
+    HistoryEntity::find()
+        .filter(HistoryColumn::UserId.eq(user_id))
+        .filter(HistoryColumn::Id.gt(after))
+        .order_by_asc(HistoryColumn::Id)
+        .limit(EVENTS_BATCH_SIZE)
+        .all(db)
+        .await
+        .database_err()?
+        .iter()
+        .map(TodoEventSchema::new)
+        .collect()
+}
+
+/// Returns a stream of the events of the user todos after the given event id.
+///
+/// The events are read from the todo history, so only the committed changes are streamed.
+/// An empty batch is returned when there are no events for a while, to keep the connection alive
+pub fn event_stream(
+    user_id: u32,
+    after: u32,
+    db: DatabaseConnection,
+) -> impl Stream<Item = ApiResult<Vec<TodoEventSchema>>> {
+    stream::unfold(Some(after), move |after| {
+        let db = db.clone();
+        async move {
+            let mut after = after?;
+            let mut waited = Duration::ZERO;
+            loop {
+                match events_after(user_id, after, &db).await {
+                    Ok(batch) if batch.is_empty() && waited < KEEP_ALIVE_INTERVAL => {
+                        time::sleep(POLL_INTERVAL).await;
+                        waited += POLL_INTERVAL;
+                    }
+                    Ok(batch) => {
+                        after = batch.last().map_or(after, |event| event.id);
+                        return Some((Ok(batch), Some(after)));
+                    }
+                    // End the stream after the error
+                    Err(err) => return Some((Err(err), None)),
+                }
+            }
+        }
+    })
+}
+
+/// Returns the event in the Server-Sent Events format
+fn sse_message(event: &TodoEventSchema) -> ApiResult<String> {
+    let data = serde_json::to_string(&event.todo).server_err("Failed to serialize the todo")?;
+    Ok(format!(
+        "id: {}\nevent: {}\ndata: {data}\n\n",
+        event.id,
+        event.event.as_str()
+    ))
+}
+
+/// Stream the changes of the user todos as Server-Sent Events.
+///
+/// The events are `todo.created`, `todo.updated` (updated or restored from the trash) and `todo.deleted` (moved to the trash),
+/// the data of the event is the todo after the change. Only the new events are streamed, send the `Last-Event-ID` header
+/// to get the events after it first (the browsers send it when they reconnect).
+#[utoipa::path(
+    context_path = "/api/todos",
+    params(
+        (
+            "Last-Event-ID" = Option<u32>, Header, description = "The id of the last received event, to resume the stream after it",
+            example = "1"
+        )
+    ),
+    responses(
+        (
+            status = 200, description = "The stream of the todo events", content_type = "text/event-stream", body = String,
+            example = json!("id: 1\nevent: todo.updated\ndata: {\"uuid\": \"a8bfed8d-4f8b-4150-8ace-3f8916609eba\", ...}\n\n")
+        ),
+        (
+            status = 400, description = "The `Last-Event-ID` header is invalid", body = MessageSchema,
+            example = json!(MessageSchema::new(400, "The `Last-Event-ID` header must be an event id"))
+        ),
+    ),
+    tag = "Todo",
+    security(("Bearer Token" = []))
+)]
+#[get("/events")]
+pub async fn events(
+    req: HttpRequest,
+    db: web::Data<DatabaseConnection>,
+) -> ApiResult<HttpResponse> {
+    let user = req_auth(req.clone(), db.get_ref()).await?;
+    let after = match req.headers().get("Last-Event-ID") {
+        Some(id) => id
+            .to_str()
+            .ok()
+            .and_then(|id| id.trim().parse().ok())
+            .bad_request_err("The `Last-Event-ID` header must be an event id")?,
+        None => sync::last_change(user.id, db.get_ref()).await?,
+    };
+
+    let messages = event_stream(user.id, after, db.get_ref().clone()).map(|batch| {
+        let batch = batch?;
+        if batch.is_empty() {
+            return Ok::<_, ApiError>(web::Bytes::from_static(b": keep-alive\n\n"));
+        }
+        batch
+            .iter()
+            .map(sse_message)
+            .collect::<ApiResult<String>>()
+            .map(web::Bytes::from)
+    });
+    Ok(HttpResponse::Ok()
+        .content_type("text/event-stream")
+        .insert_header(CacheControl(vec![CacheDirective::NoCache]))
+        .streaming(messages))
+}
@@ -0,0 +1,85 @@
+use actix_web::{
+    get,
+    http::header::{ContentDisposition, DispositionParam, DispositionType},
+    web, HttpRequest, HttpResponse,
+};
+use sea_orm::DatabaseConnection;
+
+use crate::{
+    api::auth::utils::req_auth,
+    api::todo::{
+        markdown,
+        queries::{ExportQuery, TodoFormat},
+        transfer,
+    },
+    errors::{Error as ApiError, Result as ApiResult},
+    schemas::{message::MessageSchema, todo::TodoSchema, traits::OpenApiExample},
+};
+
+/// Export all the todos of the user as a file, in the manual order. The todos in the trash are not exported,
+/// and the todos can be filtered by `status` and `title` like the todos list.
+///
+/// The `json` format is an array of todos and the `csv` format has a header row with the todo fields as columns.
+/// The `todotxt` format is a todo.txt file (check the import endpoint for how the todos are mapped),
+/// and the `ics` format is an iCalendar file with a VTODO component per todo.
+/// The `markdown` format is a GitHub-flavored Markdown task list (`- [ ]` and `- [x]`), it can be grouped by
+/// `status` or `project` (the first `+project` tag of the title) with the `group_by` parameter, a heading per group.
+/// The file can be imported in the `/api/todos/import` endpoint.
+#[utoipa::path(
+    context_path = "/api/todos",
+    params(ExportQuery),
+    responses(
+        (
+            status = 200, description = "The todos file", body = [TodoSchema],
+            example = json!([TodoSchema::openapi_example()])
+        ),
+        (
+            status = 400, description = "The format is invalid", body = MessageSchema,
+            example = json!(MessageSchema::new(400, "unknown variant `xml`, expected one of `json`, `csv`, `todotxt`, `ics`, `markdown`"))
+        ),
+        (
+            status = 400, description = "The todos are grouped in a format other than `markdown`", body = MessageSchema,
+            example = json!(MessageSchema::new(400, "The todos can be grouped in the `markdown` format only"))
+        ),
+    ),
+    tag = "Todo",
+    security(("Bearer Token" = []))
+)]
+#[get("/export")]
+pub async fn export(
+    req: HttpRequest,
+    db: web::Data<DatabaseConnection>,
+    query: web::Query<ExportQuery>,
+) -> ApiResult<HttpResponse> {
+    let user = req_auth(req, db.get_ref()).await?;
+    let format = query.format.unwrap_or_default();
+    if query.group_by.is_some() && format != TodoFormat::Markdown {
+        return Err(ApiError::BadRequest(
+            "The todos can be grouped in the `markdown` format only".to_owned(),
+        ));
+    }
+
+    let mut response = HttpResponse::Ok();
+    response
+        .content_type(format.content_type())
+        .insert_header(ContentDisposition {
+            disposition: DispositionType::Attachment,
+            parameters: vec![DispositionParam::Filename(format!(
+                "todos.{}",
+                format.extension()
+            ))],
+        });
+    match query.group_by {
+        // The groups need all the todos, so the grouped task list is not streamed
+        Some(group_by) => {
+            let todos = transfer::export_todos(user.id, &query.filters(), db.get_ref()).await?;
+            Ok(response.body(markdown::format_checklist(&todos, group_by)))
+        }
+        None => Ok(response.streaming(transfer::export_stream(
+            user.id,
+            format,
+            query.filters(),
+            db.get_ref().clone(),
+        ))),
+    }
+}
@@ -1,35 +1,47 @@
 use actix_web::{
     get,
     web::{self, Path},
-    HttpRequest,
+    HttpRequest, HttpResponse,
 };
 use sea_orm::DatabaseConnection;
 use uuid::Uuid;
 
 use crate::{
     api::auth::utils::req_auth,
-    api::todo::utils,
+    api::todo::{queries::TodoFields, utils},
+    conditional::Validators,
     errors::Result as ApiResult,
     schemas::{message::MessageSchema, todo::TodoSchema, traits::OpenApiExample},
 };
 
 /// Get a single todo by uuid.
+///
+/// The returned fields can be trimmed by the `fields` parameter, and the related data can be embedded by the `include` parameter.
+///
+/// The response has `ETag` and `Last-Modified` headers, send them back in `If-None-Match` or `If-Modified-Since`
+/// headers to get `304 Not Modified` if the todo is not changed.
 #[utoipa::path(
     context_path = "/api/todos",
     params(
         (
             "uuid", description = "The uuid of the todo",
             example = "b5a5d4e4-7d4e-4f4a-9f3d-3f3f3f3f3f3f"
-        )
+        ),
+        TodoFields
     ),
     responses(
         (
             status = 200, description = "Get a single todo by uuid", body = TodoScheam,
             example = json!(TodoSchema::openapi_example())
         ),
+        (status = 304, description = "The todo is not modified since the given `ETag` or `Last-Modified`"),
         (
             status = 404, description = "There is no todo with the given uuid", body = MessageSchema,
             example = json!(MessageSchema::new(404, "There is no todo with the given uuid"))
+        ),
+        (
+            status = 400, description = "Invalid field", body = MessageSchema,
+            example = json!(MessageSchema::new(400, "The field `{field}` is invalid, the valid fields are: uuid, title, status, created_at, updated_at, completed_at, version, deleted_at, position"))
         )
     ),
     tag = "Todo",
@@ -40,12 +52,17 @@ pub async fn get_todo(
     req: HttpRequest,
     db: web::Data<DatabaseConnection>,
     uuid: Path<Uuid>,
-) -> ApiResult<TodoSchema> {
+    fields: web::Query<TodoFields>,
+) -> ApiResult<HttpResponse> {
     let db = db.get_ref();
     let uuid = uuid.into_inner();
-    let user = req_auth(req, db).await?;
+    let user = req_auth(req.clone(), db).await?;
+    fields.validate()?;
 
-    utils::find_todo_by_uuid(uuid, user.id, db)
-        .await
-        .map(From::from)
+    let todo = utils::find_todo_by_uuid(uuid, user.id, db).await?;
+    let todo = TodoSchema::from(todo);
+    // The representation depends on the selected fields, so they are part of the entity tag
+    Validators::new(todo.etag(), Some(todo.updated_at))
+        .with_variant(req.query_string())
+        .respond(&req, &fields.apply(&todo, &user)?)
 }
@@ -0,0 +1,72 @@
+use actix_web::{get, web, HttpRequest};
+use entity::todo_history::{Column as HistoryColumn, Entity as HistoryEntity};
+use entity::user::Entity as UserEntity;
+use sea_orm::{ColumnTrait, DatabaseConnection, EntityTrait, QueryFilter, QueryOrder};
+use uuid::Uuid;
+
+use crate::{
+    api::auth::utils::req_auth,
+    errors::{Error as ApiError, ErrorTrait, Result as ApiResult},
+    schemas::{
+        message::MessageSchema,
+        todo::{TodoHistoryListSchema, TodoHistorySchema},
+        traits::OpenApiExample,
+    },
+};
+
+/// Get the change history of a single todo by uuid, the oldest change first.
+///
+/// Each change has the changed fields with their old and new values, who made it, when and the request id.
+/// The history of the todos in the trash is available too.
+#[utoipa::path(
+    context_path = "/api/todos",
+    params(
+        (
+            "uuid", description = "The uuid of the todo",
+            example = "b5a5d4e4-7d4e-4f4a-9f3d-3f3f3f3f3f3f"
+        )
+    ),
+    responses(
+        (
+            status = 200, description = "The history of the todo", body = TodoHistoryListSchema,
+            example = json!(TodoHistoryListSchema::openapi_example())
+        ),
+        (
+            status = 404, description = "There is no todo with the given uuid", body = MessageSchema,
+            example = json!(MessageSchema::new(404, "There is no todo with the given uuid"))
+        )
+    ),
+    tag = "Todo",
+    security(("Bearer Token" = []))
+)]
+#[get("/{uuid}/history")]
+pub async fn history(
+    req: HttpRequest,
+    db: web::Data<DatabaseConnection>,
+    uuid: web::Path<Uuid>,
+) -> ApiResult<TodoHistoryListSchema> {
+    let db = db.get_ref();
+    let user = req_auth(req, db).await?;
+
+    let history = HistoryEntity::find()
+        .filter(HistoryColumn::TodoUuid.eq(*uuid))
+        .filter(HistoryColumn::UserId.eq(user.id))
+        .order_by_asc(HistoryColumn::Id)
+        .find_also_related(UserEntity)
+        .all(db)
+        .await
+        .database_err()?;
+    if history.is_empty() {
+        return Err(ApiError::NotFound(
+            "There is no todo with the given uuid".to_owned(),
+        ));
+    }
+    Ok(TodoHistoryListSchema {
+        data: history
+            .into_iter()
+            .map(|(entry, actor)| {
+                TodoHistorySchema::new(entry, actor.map(|actor| actor.name).unwrap_or_default())
+            })
+            .collect(),
+    })
+}
@@ -0,0 +1,216 @@
+use std::collections::HashMap;
+
+use chrono::{NaiveDate, NaiveDateTime};
+use entity::todo::Status as TodoStatus;
+
+use crate::{
+    api::todo::transfer::ImportedTodo,
+    errors::{Error as ApiError, Result as ApiResult},
+    schemas::todo::{TodoContentSchema, TodoSchema},
+};
+
+/// The start of the exported calendars
+pub const CALENDAR_HEADER: &str =
+    "BEGIN:VCALENDAR\r\nVERSION:2.0\r\nPRODID:-//Oxide Todo//Todos//EN\r\nX-WR-CALNAME:Todos\r\n";
+/// The end of the exported calendars
+pub const CALENDAR_FOOTER: &str = "END:VCALENDAR\r\n";
+/// The maximum length of a content line in octets, the longer lines are folded
+const MAX_LINE_LENGTH: usize = 75;
+/// The format of the UTC date-time values
+const DATE_TIME_FORMAT: &str = "%Y%m%dT%H%M%SZ";
+
+/// Returns the iCalendar status of the todo status
+pub fn ical_status(status: &TodoStatus) -> &'static str {
+    match status {
+        TodoStatus::Pending => "NEEDS-ACTION",
+        TodoStatus::Progress => "IN-PROCESS",
+        TodoStatus::Completed => "COMPLETED",
+        TodoStatus::Cancelled => "CANCELLED",
+    }
+}
+
+/// Returns the todo status of the iCalendar status, `None` if the status is not a VTODO status
+fn todo_status(status: &str) -> Option<TodoStatus> {
+    match status.to_uppercase().as_str() {
+        "NEEDS-ACTION" => Some(TodoStatus::Pending),
+        "IN-PROCESS" => Some(TodoStatus::Progress),
+        "COMPLETED" => Some(TodoStatus::Completed),
+        "CANCELLED" => Some(TodoStatus::Cancelled),
+        _ => None,
+    }
+}
+
+/// Escape the special characters of a text value
+fn escape(text: &str) -> String {
+    text.replace('\\', "\\\\")
+        .replace(';', "\\;")
+        .replace(',', "\\,")
+        .replace('\n', "\\n")
+}
+
+/// Unescape the special characters of a text value
+fn unescape(text: &str) -> String {
+    let mut unescaped = String::with_capacity(text.len());
+    let mut chars = text.chars();
+    while let Some(char) = chars.next() {
+        if char != '\\' {
+            unescaped.push(char);
+            continue;
+        }
+        match chars.next() {
+            Some('n' | 'N') => unescaped.push('\n'),
+            Some(escaped) => unescaped.push(escaped),
+            None => unescaped.push('\\'),
+        }
+    }
+    unescaped
+}
+
+/// Returns the UTC date-time value of the Unix timestamp
+pub fn format_time(timestamp: i64) -> String {
+    NaiveDateTime::from_timestamp_opt(timestamp, 0)
+        .unwrap_or_default()
+        .format(DATE_TIME_FORMAT)
+        .to_string()
+}
+
+/// Returns the Unix timestamp of a date-time or a date value, the floating times are considered UTC
+fn parse_time(value: &str) -> Option<i64> {
+    let value = value.trim_end_matches('Z');
+    NaiveDateTime::parse_from_str(value, "%Y%m%dT%H%M%S")
+        .ok()
+        .or_else(|| {
+            NaiveDate::parse_from_str(value, "%Y%m%d")
+                .ok()?
+                .and_hms_opt(0, 0, 0)
+        })
+        .map(|time| time.timestamp())
+}
+
+/// Fold the content line to lines of 75 octets at most, ends with a line break
+fn fold(line: &str) -> String {
+    let mut folded = String::with_capacity(line.len() + 2);
+    let mut length = 0;
+    for char in line.chars() {
+        if length + char.len_utf8() > MAX_LINE_LENGTH {
+            folded.push_str("\r\n ");
+            length = 1;
+        }
+        folded.push(char);
+        length += char.len_utf8();
+    }
+    folded.push_str("\r\n");
+    folded
+}
+
+/// Returns the VTODO component of the todo
+pub fn format_todo(todo: &TodoSchema) -> String {
+    let mut lines = vec![
+        "BEGIN:VTODO".to_owned(),
+        format!("UID:{}", todo.uuid),
+        format!("DTSTAMP:{}", format_time(todo.updated_at)),
+        format!("CREATED:{}", format_time(todo.created_at)),
+        format!("LAST-MODIFIED:{}", format_time(todo.updated_at)),
+        format!("SEQUENCE:{}", todo.version.saturating_sub(1)),
+        format!("SUMMARY:{}", escape(&todo.title)),
+        format!("STATUS:{}", ical_status(&todo.status)),
+    ];
+    if let Some(completed_at) = todo.completed_at {
+        lines.push(format!("COMPLETED:{}", format_time(completed_at)));
+    }
+    lines.push("END:VTODO".to_owned());
+    lines.iter().map(|line| fold(line)).collect()
+}
+
+/// Returns the content lines of the iCalendar text, the folded lines are unfolded
+fn unfold(text: &str) -> Vec<String> {
+    let mut lines: Vec<String> = Vec::new();
+    for line in text.lines() {
+        match (line.strip_prefix([' ', '\t']), lines.last_mut()) {
+            (Some(rest), Some(last)) => last.push_str(rest),
+            _ if line.is_empty() => {}
+            _ => lines.push(line.to_owned()),
+        }
+    }
+    lines
+}
+
+/// Split the content line to its uppercase name and its value, the parameters are ignored
+fn split_line(line: &str) -> Option<(String, &str)> {
+    // The value starts after the first colon that is not in a quoted parameter value
+    let mut quoted = false;
+    let colon = line.char_indices().find_map(|(idx, char)| match char {
+        '"' => {
+            quoted = !quoted;
+            None
+        }
+        ':' if !quoted => Some(idx),
+        _ => None,
+    })?;
+    let name = line[..colon].split(';').next().unwrap_or_default();
+    Some((name.to_uppercase(), &line[colon + 1..]))
+}
+
+/// Returns the todo of the VTODO properties, or why it can't be a todo
+fn todo_from_properties(properties: &HashMap<String, String>) -> Result<ImportedTodo, String> {
+    let title = properties
+        .get("SUMMARY")
+        .map(|summary| unescape(summary))
+        .ok_or_else(|| "The todo has no `SUMMARY`".to_owned())?;
+    let completed_at = properties
+        .get("COMPLETED")
+        .and_then(|time| parse_time(time));
+    let status = match properties.get("STATUS") {
+        Some(status) => todo_status(status)
+            .ok_or_else(|| format!("The status `{status}` is not a todo status"))?,
+        None if completed_at.is_some() => TodoStatus::Completed,
+        None => TodoStatus::Pending,
+    };
+    Ok(ImportedTodo {
+        content: TodoContentSchema { title, status },
+        created_at: properties
+            .get("CREATED")
+            .or_else(|| properties.get("DTSTAMP"))
+            .and_then(|time| parse_time(time)),
+        completed_at,
+    })
+}
+
+/// Parse an iCalendar file, returns the todo of each VTODO component or why it can't be a todo.
+/// The other components are ignored, if the file is not an iCalendar file returns an error 400
+pub fn parse_calendar(text: &str) -> ApiResult<Vec<Result<ImportedTodo, String>>> {
+    let lines = unfold(text);
+    if !lines
+        .first()
+        .map_or(false, |line| line.eq_ignore_ascii_case("BEGIN:VCALENDAR"))
+    {
+        return Err(ApiError::BadRequest(
+            "The file should be an iCalendar file".to_owned(),
+        ));
+    }
+
+    let mut todos = Vec::new();
+    // The properties of the current VTODO, and the depth of the components nested in it (e.g. VALARM)
+    let mut current: Option<(HashMap<String, String>, usize)> = None;
+    for line in &lines {
+        let Some((name, value)) = split_line(line) else {
+            continue;
+        };
+        match (name.as_str(), current.as_mut()) {
+            ("BEGIN", None) if value.eq_ignore_ascii_case("VTODO") => {
+                current = Some((HashMap::new(), 0))
+            }
+            ("BEGIN", Some((_, depth))) => *depth += 1,
+            ("END", Some((_, depth))) if *depth > 0 => *depth -= 1,
+            ("END", Some((properties, _))) => {
+                todos.push(todo_from_properties(properties));
+                current = None;
+            }
+            (_, Some((properties, 0))) => {
+                properties.entry(name).or_insert_with(|| value.to_owned());
+            }
+            _ => {}
+        }
+    }
+    Ok(todos)
+}
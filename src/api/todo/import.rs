@@ -0,0 +1,118 @@
+use actix_web::{post, web, HttpRequest};
+use sea_orm::{DatabaseConnection, TransactionTrait};
+
+use crate::{
+    api::auth::utils::req_auth,
+    api::todo::{
+        queries::{ImportMode, ImportQuery},
+        transfer, utils,
+    },
+    errors::{Error as ApiError, ErrorTrait, Result as ApiResult},
+    schemas::{
+        message::MessageSchema,
+        todo::{ImportErrorSchema, ImportResultSchema},
+        traits::OpenApiExample,
+    },
+};
+
+/// Import todos from a file, the file is the request body in the given format (like the exported files).
+///
+/// Each row is created like `POST /api/todos` (the `title` and `status` are used, and the `created_at` and `completed_at`
+/// times in the `json` and `csv` formats), the rows that can't be created are reported with their errors. In `replace` mode the current todos are moved to the trash first.
+///
+/// In the `todotxt` format each line is a todo, the completion marker (`x`) is the `completed` status and the
+/// completion and creation dates are kept. The `status:progress` and `status:cancelled` tags are the other statuses,
+/// the rest of the line (with the priority, `+project`, `@context` and the other tags) is the title.
+///
+/// In the `ics` format each VTODO component is a todo, its `SUMMARY` is the title and its `STATUS` is mapped to the todo
+/// status (`NEEDS-ACTION`, `IN-PROCESS`, `COMPLETED` or `CANCELLED`). The `CREATED` and `COMPLETED` times are kept.
+///
+/// In the `markdown` format each task item (`- [ ]` or `- [x]`) is a todo and the other lines are ignored. The checked items
+/// are `completed`, or `cancelled` if they are struck through (`~~title~~`) or under a `## Cancelled` heading. The unchecked items
+/// under a `## Progress` heading are `progress`, so the task list grouped by status keeps the statuses.
+#[utoipa::path(
+    context_path = "/api/todos",
+    params(ImportQuery),
+    request_body(content = String, description = "The todos file", content_type = "text/csv"),
+    responses(
+        (
+            status = 200, description = "The import result", body = ImportResultSchema,
+            example = json!(ImportResultSchema::openapi_example())
+        ),
+        (
+            status = 400, description = "The file is invalid", body = MessageSchema,
+            example = json!(MessageSchema::new(400, "The CSV file should have `title` and `status` columns"))
+        ),
+        (
+            status = 400, description = "The file has too many todos", body = MessageSchema,
+            example = json!(MessageSchema::new(400, "The file has more than 500 todos"))
+        ),
+    ),
+    tag = "Todo",
+    security(("Bearer Token" = []))
+)]
+#[post("/import")]
+pub async fn import(
+    req: HttpRequest,
+    db: web::Data<DatabaseConnection>,
+    query: web::Query<ImportQuery>,
+    body: web::Bytes,
+) -> ApiResult<ImportResultSchema> {
+    let db = db.get_ref();
+    let user = req_auth(req.clone(), db).await?;
+    let actor = utils::Actor::new(user.id, &req);
+    let rows = transfer::decode_todos(query.format.unwrap_or_default(), &body)?;
+
+    let mut result = ImportResultSchema {
+        imported: 0,
+        trashed: 0,
+        errors: Vec::new(),
+    };
+    let txn = db.begin().await.database_err()?;
+    if query.mode.unwrap_or_default() == ImportMode::Replace {
+        for todo in utils::user_todos(user.id).all(&txn).await.database_err()? {
+            utils::trash_todo(todo, &actor, &txn).await?;
+            result.trashed += 1;
+        }
+    }
+    for (row, todo) in rows {
+        let todo = match todo {
+            Ok(todo) => todo,
+            Err(err) => {
+                result
+                    .errors
+                    .push(ImportErrorSchema::new(row, MessageSchema::new(400, err)));
+                continue;
+            }
+        };
+        // Each row has its own savepoint, so a failed row doesn't leave partial changes
+        let savepoint = txn.begin().await.database_err()?;
+        let created = utils::create_todo_at(
+            &savepoint,
+            None,
+            todo.content,
+            todo.created_at,
+            todo.completed_at,
+            &actor,
+        );
+        match created.await {
+            Ok(_) => {
+                savepoint.commit().await.database_err()?;
+                result.imported += 1;
+            }
+            Err(err @ ApiError::InternalServer(_)) => return Err(err),
+            Err(err) => {
+                savepoint.rollback().await.database_err()?;
+                result.errors.push(ImportErrorSchema::new(row, err.into()));
+            }
+        }
+    }
+    txn.commit().await.database_err()?;
+    log::info!(
+        "Imported {} todos for `{}`, {} rows are failed",
+        result.imported,
+        user.name,
+        result.errors.len()
+    );
+    Ok(result)
+}
@@ -1,22 +1,31 @@
 use crate::api::auth::utils as auth_utils;
-use crate::api::todo::queries::TodoFilters;
+use crate::api::todo::queries::{TodoFields, TodoFilters};
+use crate::api::todo::utils;
+use crate::conditional::Validators;
 use crate::errors::{ErrorTrait, Result as ApiResult};
-use crate::schemas::{todo::TodoListSchema, traits::OpenApiExample};
-use actix_web::{get, web, HttpRequest};
-use entity::todo::{Column as TodoColumn, Entity as TodoEntity};
-use sea_orm::{
-    ColumnTrait, DatabaseConnection, EntityTrait, PaginatorTrait, QueryFilter, QueryOrder,
-    QuerySelect,
-};
+use crate::schemas::{message::MessageSchema, todo::TodoListSchema, traits::OpenApiExample};
+use actix_web::{get, web, HttpRequest, HttpResponse};
+use entity::todo::Column as TodoColumn;
+use sea_orm::{DatabaseConnection, PaginatorTrait, QueryOrder, QuerySelect};
 
 /// list todos, filterable by status, title, limit, offset, order, and order_by.
+///
+/// The returned fields of the todos can be trimmed by the `fields` parameter, and the related data can be embedded by the `include` parameter.
+///
+/// The response has `ETag` and `Last-Modified` headers, send them back in `If-None-Match` or `If-Modified-Since`
+/// headers to get `304 Not Modified` if the list is not changed.
 #[utoipa::path(
     context_path = "/api/todos",
-    params(TodoFilters),
+    params(TodoFilters, TodoFields),
     responses(
         (
             status = 200, description = "List todos", body = TodoListSchema,
             example = json!(TodoListSchema::openapi_example())
+        ),
+        (status = 304, description = "The list is not modified since the given `ETag` or `Last-Modified`"),
+        (
+            status = 400, description = "Invalid field", body = MessageSchema,
+            example = json!(MessageSchema::new(400, "The field `{field}` is invalid, the valid fields are: uuid, title, status, created_at, updated_at, completed_at, version, deleted_at, position"))
         )
     ),
     tag = "Todo",
@@ -27,32 +36,26 @@ pub async fn list(
     req: HttpRequest,
     db: web::Data<DatabaseConnection>,
     params: web::Query<TodoFilters>,
-) -> ApiResult<TodoListSchema> {
+    fields: web::Query<TodoFields>,
+) -> ApiResult<HttpResponse> {
     let db = db.get_ref();
-    let user = auth_utils::req_auth(req, db).await?;
-    let mut query = TodoEntity::find().filter(TodoColumn::UserId.eq(user.id));
-
-    if let Some(title) = &params.title {
-        query = query.filter(
-            TodoColumn::Title
-                .contains(title)
-                .or(TodoColumn::Title.eq(title.as_str()))
-                .or(TodoColumn::Title.like(title)),
-        );
-    }
-    if let Some(status) = &params.status {
-        query = query.filter(TodoColumn::Status.eq(status.clone()));
-    }
+    let user = auth_utils::req_auth(req.clone(), db).await?;
+    fields.validate()?;
+    let query = params.filter(utils::user_todos(user.id));
 
     let total = query.clone().count(db).await.database_err()?;
-    query
+    let todos = query
         .order_by(TodoColumn::from(params.order_by()), params.order().into())
         .limit(params.limit())
         .offset(params.offset())
         .all(db)
         .await
-        .map(|todos| {
-            TodoListSchema::new(todos.into_iter().map(From::from).collect(), &params, total)
-        })
-        .database_err()
+        .database_err()?;
+    // The whole todos of the user, the list changes when a todo leaves it too
+    let last_modified = utils::todos_last_modified(user.id, db).await?;
+    let list = TodoListSchema::new(todos.into_iter().map(From::from).collect(), &params, total);
+    let body = serde_json::to_vec(&fields.apply_list(&list, &user)?)
+        .server_err("Failed to serialize the todos")?;
+    // The entity tag is a hash of the whole result set
+    Ok(Validators::hashed(&body, last_modified).respond_bytes(&req, body))
 }
@@ -0,0 +1,155 @@
+use std::collections::BTreeMap;
+
+use entity::todo::Status as TodoStatus;
+
+use crate::{
+    api::todo::{queries::ChecklistGroup, transfer::ImportedTodo},
+    schemas::todo::{TodoContentSchema, TodoSchema},
+};
+
+/// The order of the status groups in the task list
+const STATUS_GROUPS: [TodoStatus; 4] = [
+    TodoStatus::Pending,
+    TodoStatus::Progress,
+    TodoStatus::Completed,
+    TodoStatus::Cancelled,
+];
+/// The heading of the todos without a project, when the task list is grouped by project
+const NO_PROJECT_HEADING: &str = "No project";
+
+/// Returns the heading of the status group, e.g. `Pending`
+fn status_heading(status: &TodoStatus) -> String {
+    let status = status.as_str();
+    status[..1].to_uppercase() + &status[1..]
+}
+
+/// Returns the project of the todo, the first `+project` tag of the title
+pub fn project(title: &str) -> Option<&str> {
+    title
+        .split(' ')
+        .find_map(|word| word.strip_prefix('+').filter(|project| !project.is_empty()))
+}
+
+/// Returns the task item of the todo, the completed and cancelled todos are checked
+/// and the cancelled todos are struck through
+pub fn format_item(todo: &TodoSchema) -> String {
+    let title = todo.title.replace(['\r', '\n'], " ");
+    match todo.status {
+        TodoStatus::Completed => format!("- [x] {title}"),
+        TodoStatus::Cancelled => format!("- [x] ~~{title}~~"),
+        TodoStatus::Pending | TodoStatus::Progress => format!("- [ ] {title}"),
+    }
+}
+
+/// Returns the task list of the todos grouped by status or project, a heading per group.
+/// The todos keep their order in each group
+pub fn format_checklist(todos: &[TodoSchema], group_by: ChecklistGroup) -> String {
+    let groups: Vec<(String, Vec<&TodoSchema>)> = match group_by {
+        ChecklistGroup::Status => STATUS_GROUPS
+            .iter()
+            .map(|status| {
+                let todos = todos.iter().filter(|todo| &todo.status == status);
+                (status_heading(status), todos.collect())
+            })
+            .collect(),
+        ChecklistGroup::Project => {
+            let mut projects: BTreeMap<&str, Vec<&TodoSchema>> = BTreeMap::new();
+            let mut no_project = Vec::new();
+            for todo in todos {
+                match project(&todo.title) {
+                    Some(project) => projects.entry(project).or_default().push(todo),
+                    None => no_project.push(todo),
+                }
+            }
+            projects
+                .into_iter()
+                .map(|(project, todos)| (project.to_owned(), todos))
+                .chain([(NO_PROJECT_HEADING.to_owned(), no_project)])
+                .collect()
+        }
+    };
+    groups
+        .into_iter()
+        .filter(|(_, todos)| !todos.is_empty())
+        .map(|(heading, todos)| {
+            let items = todos
+                .into_iter()
+                .map(|todo| format_item(todo) + "\n")
+                .collect::<String>();
+            format!("## {heading}\n\n{items}")
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Split the task item of the line, returns if it's checked and its text.
+/// The item can be a bullet (`-`, `*` or `+`) or an ordered (`1.` or `1)`) list item
+fn parse_item(line: &str) -> Option<(bool, &str)> {
+    let line = line.trim_start();
+    let rest = match line.strip_prefix(['-', '*', '+']) {
+        Some(rest) => rest,
+        None => line
+            .trim_start_matches(|c: char| c.is_ascii_digit())
+            .strip_prefix(['.', ')'])
+            .filter(|_| line.starts_with(|c: char| c.is_ascii_digit()))?,
+    };
+    let rest = rest.strip_prefix(' ')?.trim_start();
+    let (checked, text) = match rest.get(..3)? {
+        "[ ]" => (false, &rest[3..]),
+        "[x]" | "[X]" => (true, &rest[3..]),
+        _ => return None,
+    };
+    // The checkbox should be followed by a space or the end of the line
+    (text.is_empty() || text.starts_with([' ', '\t'])).then(|| (checked, text.trim()))
+}
+
+/// Returns the todo of the task item, `section` is the status of the heading that the item is under.
+///
+/// The checked items are `completed`, or `cancelled` if they are struck through (`~~title~~`) or under a
+/// `Cancelled` heading. The unchecked items are `pending`, or `progress` if they are under a `Progress` heading
+fn item_todo(
+    checked: bool,
+    text: &str,
+    section: Option<&TodoStatus>,
+) -> Result<ImportedTodo, String> {
+    let struck = text
+        .strip_prefix("~~")
+        .and_then(|text| text.strip_suffix("~~"))
+        .filter(|title| !title.is_empty());
+    let (title, status) = match (checked, struck, section) {
+        (true, Some(title), _) => (title, TodoStatus::Cancelled),
+        (true, None, Some(TodoStatus::Cancelled)) => (text, TodoStatus::Cancelled),
+        (true, None, _) => (text, TodoStatus::Completed),
+        (false, _, Some(TodoStatus::Progress)) => (text, TodoStatus::Progress),
+        (false, _, _) => (text, TodoStatus::Pending),
+    };
+    if title.is_empty() {
+        return Err("The task item has no title".to_owned());
+    }
+    Ok(ImportedTodo {
+        content: TodoContentSchema {
+            title: title.to_owned(),
+            status,
+        },
+        created_at: None,
+        completed_at: None,
+    })
+}
+
+/// Parse a Markdown task list, returns the line number of each task item with its todo or why it's invalid.
+/// The other lines (e.g. the headings, the paragraphs and the list items without a checkbox) are ignored,
+/// but a status heading (e.g. `## Progress`) sets the status of the items under it, the opposite of `format_checklist`
+pub fn parse_checklist(text: &str) -> Vec<(u64, Result<ImportedTodo, String>)> {
+    let mut section = None;
+    let mut rows = Vec::new();
+    for (row, line) in (1..).zip(text.lines()) {
+        if let Some(heading) = line.trim_start().strip_prefix('#') {
+            section = heading.trim_start_matches('#').trim().parse().ok();
+            continue;
+        }
+        if let Some((checked, text)) = parse_item(line) {
+            rows.push((row, item_todo(checked, text, section.as_ref())));
+        }
+    }
+    rows
+}
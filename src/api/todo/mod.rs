@@ -1,11 +1,25 @@
 use actix_web::web;
 
+pub mod bulk;
 pub mod create;
 pub mod delete_todo;
 pub mod delete_todos;
+pub mod events;
+pub mod export;
 pub mod get_todo;
+pub mod history;
+pub mod ical;
+pub mod import;
 pub mod list;
+pub mod markdown;
+pub mod move_todo;
+pub mod patch;
 pub mod queries;
+pub mod rank;
+pub mod stats;
+pub mod todotxt;
+pub mod transfer;
+pub mod trash;
 pub mod update;
 pub mod utils;
 
@@ -13,8 +27,19 @@ pub fn init_routes(cfg: &mut web::ServiceConfig) {
     cfg.service(
         web::scope("/todos")
             .service(create::create)
+            .service(bulk::bulk)
+            .service(import::import)
+            // Should be before `get_todo`, otherwise `stats`, `events`, `export` and `trash` will be parsed as a uuid
+            .service(stats::stats)
+            .service(events::events)
+            .service(export::export)
+            .service(trash::list_trash)
+            .service(trash::restore)
+            .service(history::history)
+            .service(move_todo::move_todo)
             .service(get_todo::get_todo)
             .service(update::update_todo)
+            .service(patch::patch_todo)
             .service(delete_todo::delete_todo)
             .service(list::list)
             .service(delete_todos::delete_todos),
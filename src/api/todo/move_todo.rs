@@ -0,0 +1,65 @@
+use crate::api::auth::utils::req_auth;
+use crate::api::todo::utils;
+use crate::errors::{ErrorTrait, Result as ApiResult};
+use crate::schemas::todo::{MoveTodoSchema, TodoSchema};
+use crate::schemas::{message::MessageSchema, traits::OpenApiExample};
+use actix_web::{post, web, HttpRequest};
+use sea_orm::{DatabaseConnection, TransactionTrait};
+use uuid::Uuid;
+
+/// Move a single todo by uuid in the manual order of the todos, list them with `order_by=position` to get this order.
+///
+/// The todo is placed right after the `after` todo and right before the `before` todo, one of them is enough.
+/// Only the position of the moved todo is changed, the other todos keep theirs.
+#[utoipa::path(
+    context_path = "/api/todos",
+    request_body = MoveTodoSchema,
+    params(
+        (
+            "uuid", description = "The uuid of the todo",
+            example = "b5a5d4e4-7d4e-4f4a-9f3d-3f3f3f3f3f3f"
+        )
+    ),
+    responses(
+        (
+            status = 200, description = "The moved todo", body = TodoSchema,
+            example = json!(TodoSchema::openapi_example())
+        ),
+        (
+            status = 400, description = "There is no `before` or `after` todo", body = MessageSchema,
+            example = json!(MessageSchema::new(400, "The todo should be moved `before` or `after` another todo"))
+        ),
+        (
+            status = 400, description = "The `after` todo is ordered after the `before` todo", body = MessageSchema,
+            example = json!(MessageSchema::new(400, "The `after` todo should be ordered before the `before` todo"))
+        ),
+        (
+            status = 404, description = "There is no todo with the given uuid", body = MessageSchema,
+            example = json!(MessageSchema::new(404, "There is no todo with the given uuid"))
+        ),
+        (
+            status = 412, description = "The todo has been modified, returns the current todo", body = TodoSchema,
+            example = json!(TodoSchema::openapi_example())
+        )
+    ),
+    tag = "Todo",
+    security(("Bearer Token" = []))
+)]
+#[post("/{uuid}/move")]
+pub async fn move_todo(
+    req: HttpRequest,
+    payload: web::Json<MoveTodoSchema>,
+    uuid: web::Path<Uuid>,
+    db: web::Data<DatabaseConnection>,
+) -> ApiResult<TodoSchema> {
+    let payload = payload.into_inner();
+    let db = db.as_ref();
+    let user = req_auth(req.clone(), db).await?;
+    let todo = utils::find_todo_by_uuid(*uuid, user.id, db).await?;
+    utils::check_version(&req, &todo, payload.version)?;
+    let actor = utils::Actor::new(user.id, &req);
+    let txn = db.begin().await.database_err()?;
+    let todo = utils::move_todo(todo, payload.after, payload.before, &actor, &txn).await?;
+    txn.commit().await.database_err()?;
+    Ok(todo.into())
+}
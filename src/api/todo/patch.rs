@@ -0,0 +1,153 @@
+use actix_web::{patch, web, HttpRequest};
+use entity::todo::Status as TodoStatus;
+use sea_orm::{DatabaseConnection, TransactionTrait};
+use serde_json::Value;
+use uuid::Uuid;
+
+use crate::{
+    api::auth::utils::req_auth,
+    api::todo::utils,
+    errors::{Error as ApiError, ErrorTrait, Result as ApiResult},
+    schemas::{
+        message::MessageSchema,
+        todo::{TodoPatch, TodoSchema},
+        traits::OpenApiExample,
+    },
+};
+
+/// The todo fields that can be changed by a patch
+const WRITABLE_FIELDS: [&str; 2] = ["title", "status"];
+
+/// Returns the changed title and status of the patched todo,
+/// if a read-only field is changed or a field is invalid returns an error 400
+fn patched_fields(
+    todo: &TodoSchema,
+    current: &Value,
+    patched: &Value,
+) -> ApiResult<(Option<String>, Option<TodoStatus>)> {
+    let (Some(current), Some(patched)) = (current.as_object(), patched.as_object()) else {
+        return Err(ApiError::BadRequest(
+            "The patched todo must be an object".to_owned(),
+        ));
+    };
+    // The version is the version that the patch is based on, it's checked before
+    if let Some(field) = current
+        .keys()
+        .chain(patched.keys())
+        .filter(|field| !WRITABLE_FIELDS.contains(&field.as_str()) && *field != "version")
+        .find(|field| current.get(*field) != patched.get(*field))
+    {
+        return Err(ApiError::BadRequest(format!(
+            "The field `{field}` can't be changed, the writable fields are: {}",
+            WRITABLE_FIELDS.join(", ")
+        )));
+    }
+    let title = patched
+        .get("title")
+        .and_then(Value::as_str)
+        .bad_request_err("The todo title must be a string")?;
+    let status = patched
+        .get("status")
+        .and_then(Value::as_str)
+        .bad_request_err("The todo status must be a string")?
+        .parse::<TodoStatus>()
+        .map_err(ApiError::BadRequest)?;
+    Ok((
+        (title != todo.title).then(|| title.to_owned()),
+        (status != todo.status).then_some(status),
+    ))
+}
+
+/// Patch a single todo by uuid, only the title and status can be changed.
+///
+/// The patch format is chosen by the `Content-Type` header:
+/// - `application/merge-patch+json`: JSON Merge Patch (RFC 7396), e.g. `{"status": "completed"}`
+/// - `application/json-patch+json`: JSON Patch (RFC 6902), e.g. `[{"op": "replace", "path": "/status", "value": "completed"}]`
+///
+/// To prevent overwriting others changes, send the todo `ETag` in `If-Match` header, or the version in the `version` field (Merge Patch)
+/// or in a `test` operation on `/version` (JSON Patch). If the todo is modified, returns 412 with the current todo.
+#[utoipa::path(
+    context_path = "/api/todos",
+    request_body(
+        content = Object, content_type = "application/merge-patch+json",
+        description = "JSON Merge Patch, or JSON Patch with `application/json-patch+json` content type",
+        example = json!({"status": "completed"})
+    ),
+    params(
+        (
+            "uuid", description = "The uuid of the todo",
+            example = "b5a5d4e4-7d4e-4f4a-9f3d-3f3f3f3f3f3f"
+        )
+    ),
+    responses(
+        (
+            status = 200, description = "The patched todo", body = TodoSchema,
+            example = json!(TodoSchema::openapi_example())
+        ),
+        (
+            status = 400, description = "A read-only field is changed", body = MessageSchema,
+            example = json!(MessageSchema::new(400, "The field `{field}` can't be changed, the writable fields are: title, status"))
+        ),
+        (
+            status = 404, description = "There is no todo with the given uuid", body = MessageSchema,
+            example = json!(MessageSchema::new(404, "There is no todo with the given uuid"))
+        ),
+        (
+            status = 409, description = "A JSON Patch `test` operation on a field other than `/version` failed", body = MessageSchema,
+            example = json!(MessageSchema::new(409, "The JSON Patch `test` operation failed"))
+        ),
+        (
+            status = 412, description = "The todo has been modified, returns the current todo", body = TodoSchema,
+            example = json!(TodoSchema::openapi_example())
+        ),
+        (
+            status = 415, description = "The content type is not supported", body = MessageSchema,
+            example = json!(MessageSchema::new(415, "The content type `application/json` is not supported, the accepted types are: application/merge-patch+json, application/json-patch+json"))
+        )
+    ),
+    tag = "Todo",
+    security(("Bearer Token" = []))
+)]
+#[patch("/{uuid}")]
+pub async fn patch_todo(
+    req: HttpRequest,
+    body: web::Bytes,
+    uuid: web::Path<Uuid>,
+    db: web::Data<DatabaseConnection>,
+) -> ApiResult<TodoSchema> {
+    let db = db.get_ref();
+    let user = req_auth(req.clone(), db).await?;
+    let patch = TodoPatch::from_request(&req, &body)?;
+    let todo = utils::find_todo_by_uuid(*uuid, user.id, db).await?;
+
+    let schema = TodoSchema::from(todo.clone());
+    let current = serde_json::to_value(&schema).server_err("Failed to serialize the todo")?;
+    let mut patched = current.clone();
+    patch.apply(&mut patched).map_err(|err| match err {
+        // A failed `test` of the version is a modified todo, like a stale `If-Match`
+        ApiError::Conflict(_) if patch.tests_other_version(&current) => {
+            ApiError::PreconditionFailed(Box::new(schema.clone()))
+        }
+        err => err,
+    })?;
+    let version = match patched.get("version") {
+        Some(Value::Null) | None => None,
+        Some(version) => Some(
+            version
+                .as_u64()
+                .and_then(|version| u32::try_from(version).ok())
+                .bad_request_err("The version must be a positive number")?,
+        ),
+    };
+    utils::check_version(&req, &todo, version)?;
+
+    let (title, status) = patched_fields(&schema, &current, &patched)?;
+    if title.is_none() && status.is_none() {
+        return Ok(schema);
+    }
+    let actor = utils::Actor::new(user.id, &req);
+    let txn = db.begin().await.database_err()?;
+    let todo = utils::update_todo(todo, title, status, &actor, &txn).await?;
+    txn.commit().await.database_err()?;
+    Ok(todo.into())
+}
@@ -1,11 +1,14 @@
+use async_graphql::Enum;
 use entity::todo::Column as TodoColumn;
+use entity::todo::Entity as TodoEntity;
 use entity::todo::Status as TodoStatus;
 use sea_orm::query::Order;
+use sea_orm::{ColumnTrait, QueryFilter, Select};
 use serde::{Deserialize, Serialize};
 use utoipa::IntoParams;
 
 /// The order_by filter
-#[derive(Default, Deserialize, Serialize, Debug, Clone)]
+#[derive(Default, Deserialize, Serialize, Debug, Clone, Copy, PartialEq, Eq, Enum)]
 #[serde(rename_all = "snake_case")]
 pub enum TodoOrderBy {
     /// Order by created_at
@@ -13,10 +16,12 @@ pub enum TodoOrderBy {
     CreatedAt,
     /// Order by updated_at
     UpdatedAt,
+    /// Order by the manual order of the todos
+    Position,
 }
 
 /// The order filter
-#[derive(Default, Deserialize, Serialize, Debug, Clone)]
+#[derive(Default, Deserialize, Serialize, Debug, Clone, Copy, PartialEq, Eq, Enum)]
 #[serde(rename_all = "lowercase")]
 pub enum TodoOrder {
     /// Ascending order, Older first
@@ -35,10 +40,11 @@ pub struct TodoFilters {
     /// Filter by title (default: all)
     #[param(example = "homework")]
     pub title: Option<String>,
-    /// Order the todos by (`created_at` or `updated_at`) (default: `created_at`
+    /// Order the todos by (`created_at`, `updated_at` or `position`) (default: `created_at`
     #[param(value_type = Option<String>, example = "updated_at")]
     pub order_by: Option<TodoOrderBy>,
-    /// Order the todos (`older` or `newer`) (default: `newer`)
+    /// Order the todos (`older` or `newer`) (default: `newer`, or `older` when ordering by `position`)
+    /// Note: `older` is the manual order when ordering by `position`
     #[param(value_type = Option<String>, example = "newer")]
     pub order: Option<TodoOrder>,
     /// Offset the number of todos (default: `0`)
@@ -53,13 +59,16 @@ impl TodoFilters {
     /// Returns the order_by filter
     /// Note: Will return `CreatedAt` if the filter is not set
     pub fn order_by(&self) -> TodoOrderBy {
-        self.order_by.clone().unwrap_or_default()
+        self.order_by.unwrap_or_default()
     }
 
     /// Returns the order filter
-    /// Note: Will return `Desc` if the filter is not set
+    /// Note: Will return `Desc` if the filter is not set, and `Asc` if the todos are ordered by `position`
     pub fn order(&self) -> TodoOrder {
-        self.order.clone().unwrap_or_default()
+        self.order.unwrap_or_else(|| match self.order_by() {
+            TodoOrderBy::Position => TodoOrder::Older,
+            _ => TodoOrder::default(),
+        })
     }
 
     /// Returns the offset filter
@@ -73,6 +82,22 @@ impl TodoFilters {
     pub fn limit(&self) -> u64 {
         self.limit.unwrap_or(10)
     }
+
+    /// Filter the given query by the status and title filters
+    pub fn filter(&self, mut query: Select<TodoEntity>) -> Select<TodoEntity> {
+        if let Some(title) = &self.title {
+            query = query.filter(
+                TodoColumn::Title
+                    .contains(title)
+                    .or(TodoColumn::Title.eq(title.as_str()))
+                    .or(TodoColumn::Title.like(title)),
+            );
+        }
+        if let Some(status) = &self.status {
+            query = query.filter(TodoColumn::Status.eq(status.clone()));
+        }
+        query
+    }
 }
 
 impl Default for TodoFilters {
@@ -102,6 +127,7 @@ impl From<TodoOrderBy> for TodoColumn {
         match order_by {
             TodoOrderBy::CreatedAt => Self::CreatedAt,
             TodoOrderBy::UpdatedAt => Self::UpdatedAt,
+            TodoOrderBy::Position => Self::Position,
         }
     }
 }
@@ -1,3 +1,15 @@
 mod list_filters;
+mod stats_filters;
+mod sync_query;
+mod todo_fields;
+mod transfer_query;
+mod undo_query;
+mod version_query;
 
 pub use list_filters::*;
+pub use stats_filters::*;
+pub use sync_query::*;
+pub use todo_fields::*;
+pub use transfer_query::*;
+pub use undo_query::*;
+pub use version_query::*;
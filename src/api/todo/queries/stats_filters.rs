@@ -0,0 +1,104 @@
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+use utoipa::IntoParams;
+
+use crate::errors::{Error as ApiError, Result as ApiResult};
+
+/// The maximum number of periods in the statistics trend
+pub const MAX_STATS_PERIODS: i64 = 366;
+/// The earliest timestamp of the range (0001-01-01 00:00:00 UTC)
+const MIN_TIMESTAMP: i64 = -62_135_596_800;
+/// The latest timestamp of the range (9999-12-31 23:59:59 UTC)
+const MAX_TIMESTAMP: i64 = 253_402_300_799;
+
+/// The period of the statistics trend
+#[derive(Default, Deserialize, Serialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum StatsPeriod {
+    /// A day, starts at 00:00 UTC
+    #[default]
+    Day,
+    /// A week, starts on Monday at 00:00 UTC
+    Week,
+}
+
+impl StatsPeriod {
+    /// Returns the length of the period in seconds
+    pub fn seconds(&self) -> i64 {
+        match self {
+            Self::Day => 86_400,
+            Self::Week => 7 * 86_400,
+        }
+    }
+
+    /// Returns the start of the period that contains the given timestamp
+    pub fn start_of(&self, timestamp: i64) -> i64 {
+        // The Unix epoch was on Thursday, so the first Monday is 4 days after it
+        let offset = match self {
+            Self::Day => 0,
+            Self::Week => 4 * 86_400,
+        };
+        timestamp - (timestamp - offset).rem_euclid(self.seconds())
+    }
+}
+
+/// Todo statistics filters
+#[derive(IntoParams, Deserialize, Serialize, Debug, Clone, Default)]
+pub struct StatsFilters {
+    /// The start of the trend range (Unix timestamp) (default: 30 days before `to`)
+    #[param(example = "1620000000")]
+    pub from: Option<i64>,
+    /// The end of the trend range (Unix timestamp) (default: now)
+    #[param(example = "1622592000")]
+    pub to: Option<i64>,
+    /// The period of the trend (`day` or `week`) (default: `day`)
+    #[param(value_type = Option<String>, example = "week")]
+    pub period: Option<StatsPeriod>,
+}
+
+impl StatsFilters {
+    /// Returns the period filter
+    /// Note: Will return `Day` if the filter is not set
+    pub fn period(&self) -> StatsPeriod {
+        self.period.unwrap_or_default()
+    }
+
+    /// Returns the end of the range
+    /// Note: Will return the current time if the filter is not set
+    pub fn to(&self) -> i64 {
+        self.to
+            .unwrap_or_else(|| Utc::now().naive_utc().timestamp())
+    }
+
+    /// Returns the start of the range
+    /// Note: Will return 30 days before the end of the range if the filter is not set
+    pub fn from(&self) -> i64 {
+        self.from
+            .unwrap_or_else(|| self.to().saturating_sub(30 * 86_400))
+    }
+
+    /// Returns the range as `(from, to)`, if the range is invalid returns an error 400
+    pub fn range(&self) -> ApiResult<(i64, i64)> {
+        let (from, to) = (self.from(), self.to());
+        if ![from, to]
+            .iter()
+            .all(|timestamp| (MIN_TIMESTAMP..=MAX_TIMESTAMP).contains(timestamp))
+        {
+            return Err(ApiError::BadRequest(format!(
+                "The `from` and `to` timestamps must be between {MIN_TIMESTAMP} and {MAX_TIMESTAMP}"
+            )));
+        }
+        if from > to {
+            return Err(ApiError::BadRequest(
+                "The `from` timestamp must be before the `to` timestamp".to_owned(),
+            ));
+        }
+        let period = self.period();
+        if (period.start_of(to) - period.start_of(from)) / period.seconds() >= MAX_STATS_PERIODS {
+            return Err(ApiError::BadRequest(format!(
+                "The range is too large, the maximum number of periods is {MAX_STATS_PERIODS}"
+            )));
+        }
+        Ok((from, to))
+    }
+}
@@ -0,0 +1,30 @@
+use serde::{Deserialize, Serialize};
+use utoipa::IntoParams;
+
+use crate::errors::{Error as ApiError, Result as ApiResult};
+
+/// The change token of the last sync
+#[derive(IntoParams, Deserialize, Serialize, Debug, Clone, Default)]
+pub struct SyncQuery {
+    /// The `token` of the last sync, to get the changes made after it (default: get all the todos)
+    #[param(example = "42")]
+    pub since: Option<String>,
+}
+
+impl SyncQuery {
+    /// Returns the last change id in the token, if the token is invalid returns an error 400
+    /// Note: Will return `None` if the token is not set
+    pub fn since(&self) -> ApiResult<Option<u32>> {
+        self.since
+            .as_deref()
+            .map(|token| {
+                token.parse().map_err(|_| {
+                    ApiError::BadRequest(
+                        "The sync token is invalid, sync without `since` to get all the todos"
+                            .to_owned(),
+                    )
+                })
+            })
+            .transpose()
+    }
+}
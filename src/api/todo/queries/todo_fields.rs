@@ -0,0 +1,115 @@
+use entity::user::Model as UserModel;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Map, Value};
+use utoipa::IntoParams;
+
+use crate::errors::{Error as ApiError, ErrorTrait, Result as ApiResult};
+use crate::schemas::todo::{TodoListSchema, TodoSchema};
+
+/// The fields of the todo schema, that can be selected by the `fields` parameter
+pub const TODO_FIELDS: &[&str] = &[
+    "uuid",
+    "title",
+    "status",
+    "created_at",
+    "updated_at",
+    "completed_at",
+    "version",
+    "deleted_at",
+    "position",
+];
+
+/// The related data that can be included by the `include` parameter
+pub const TODO_INCLUDES: &[&str] = &["owner"];
+
+/// Sparse fieldsets and embedded relations of the todo responses
+#[derive(IntoParams, Deserialize, Serialize, Debug, Clone, Default)]
+pub struct TodoFields {
+    /// Comma separated fields to return, (`uuid`, `title`, `status`, `created_at`, `updated_at`, `completed_at`, `version`, `deleted_at`, `position`) (default: all)
+    #[param(example = "uuid,title,status")]
+    pub fields: Option<String>,
+    /// Comma separated related data to include, (`owner`) (default: none)
+    #[param(example = "owner")]
+    pub include: Option<String>,
+}
+
+/// Split the comma separated values, and check if they are valid
+fn split_valid<'a>(values: &'a str, valid: &[&str], name: &str) -> ApiResult<Vec<&'a str>> {
+    values
+        .split(',')
+        .map(str::trim)
+        .filter(|value| !value.is_empty())
+        .map(|value| {
+            valid
+                .contains(&value)
+                .then_some(value)
+                .bad_request_err(&format!(
+                    "The {name} `{value}` is invalid, the valid {name}s are: {}",
+                    valid.join(", ")
+                ))
+        })
+        .collect()
+}
+
+impl TodoFields {
+    /// Returns the selected fields, if there is an invalid field, returns an error 400
+    /// Note: Will return `None` if the parameter is not set
+    pub fn fields(&self) -> ApiResult<Option<Vec<&str>>> {
+        self.fields
+            .as_deref()
+            .map(|fields| split_valid(fields, TODO_FIELDS, "field"))
+            .transpose()
+    }
+
+    /// Returns the included relations, if there is an invalid relation, returns an error 400
+    pub fn include(&self) -> ApiResult<Vec<&str>> {
+        self.include
+            .as_deref()
+            .map(|include| split_valid(include, TODO_INCLUDES, "include"))
+            .unwrap_or_else(|| Ok(Vec::new()))
+    }
+
+    /// Validate the parameters, returns an error 400 if there is an invalid field or relation
+    pub fn validate(&self) -> ApiResult<()> {
+        self.fields().and(self.include()).map(|_| ())
+    }
+
+    /// Trim the given todo to the selected fields, and embed the included relations
+    pub fn apply(&self, todo: &TodoSchema, owner: &UserModel) -> ApiResult<Value> {
+        let value = serde_json::to_value(todo).server_err("Failed to serialize the todo")?;
+        self.apply_value(value, owner)
+    }
+
+    /// Same as [`TodoFields::apply`] but for a serialized todo
+    pub fn apply_value(&self, value: Value, owner: &UserModel) -> ApiResult<Value> {
+        let Value::Object(todo) = value else {
+            return Err(ApiError::InternalServer(
+                "The todo is not an object".to_owned(),
+            ));
+        };
+        let mut todo: Map<String, Value> = match self.fields()? {
+            Some(fields) => todo
+                .into_iter()
+                .filter(|(key, _)| fields.contains(&key.as_str()))
+                .collect(),
+            None => todo,
+        };
+        for relation in self.include()? {
+            if relation == "owner" {
+                todo.insert("owner".to_owned(), json!({ "username": owner.name }));
+            }
+        }
+        Ok(Value::Object(todo))
+    }
+
+    /// Trim the todos of the given list to the selected fields, and embed the included relations
+    pub fn apply_list(&self, list: &TodoListSchema, owner: &UserModel) -> ApiResult<Value> {
+        let mut value = serde_json::to_value(list).server_err("Failed to serialize the todos")?;
+        if let Some(Value::Array(todos)) = value.get_mut("data") {
+            for todo in todos.iter_mut() {
+                *todo = self.apply_value(todo.take(), owner)?;
+            }
+        }
+        Ok(value)
+    }
+}
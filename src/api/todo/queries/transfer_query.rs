@@ -0,0 +1,107 @@
+use entity::todo::Status as TodoStatus;
+use serde::{Deserialize, Serialize};
+use utoipa::IntoParams;
+
+use crate::api::todo::queries::TodoFilters;
+
+/// The file format of the exported and imported todos
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum TodoFormat {
+    /// A JSON array of todos
+    #[default]
+    Json,
+    /// A CSV file with a header row
+    Csv,
+    /// A todo.txt file, a todo per line
+    #[serde(rename = "todotxt")]
+    TodoTxt,
+    /// An iCalendar file, a VTODO component per todo
+    Ics,
+    /// A GitHub-flavored Markdown task list, a task item per todo
+    Markdown,
+}
+
+/// How the todos are grouped in the Markdown task list
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum ChecklistGroup {
+    /// A heading per status
+    Status,
+    /// A heading per project, the first `+project` tag of the title
+    Project,
+}
+
+/// What to do with the current todos when importing
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum ImportMode {
+    /// Keep the current todos and add the imported todos
+    #[default]
+    Merge,
+    /// Move the current todos to the trash, then add the imported todos
+    Replace,
+}
+
+/// The format of the exported todos and their filters
+#[derive(IntoParams, Deserialize, Serialize, Debug, Clone, Default)]
+pub struct ExportQuery {
+    /// The format of the file (`json`, `csv`, `todotxt`, `ics` or `markdown`, default: `json`)
+    #[param(value_type = Option<String>, example = "csv")]
+    pub format: Option<TodoFormat>,
+    /// Export the todos with the status (`completed`, `pending`, `progress`, `cancelled`) (default: all)
+    #[param(value_type = Option<String>, example = "pending")]
+    pub status: Option<TodoStatus>,
+    /// Export the todos with the title, like the todos list filter (default: all)
+    #[param(example = "homework")]
+    pub title: Option<String>,
+    /// Group the `markdown` task list by `status` or `project` (default: not grouped)
+    #[param(value_type = Option<String>, example = "status")]
+    pub group_by: Option<ChecklistGroup>,
+}
+
+impl ExportQuery {
+    /// Returns the filters of the exported todos
+    pub fn filters(&self) -> TodoFilters {
+        TodoFilters {
+            status: self.status.clone(),
+            title: self.title.clone(),
+            ..Default::default()
+        }
+    }
+}
+
+/// The format of the imported todos and the import mode
+#[derive(IntoParams, Deserialize, Serialize, Debug, Clone, Default)]
+pub struct ImportQuery {
+    /// The format of the file (`json`, `csv`, `todotxt`, `ics` or `markdown`, default: `json`)
+    #[param(value_type = Option<String>, example = "csv")]
+    pub format: Option<TodoFormat>,
+    /// Keep the current todos (`merge`) or move them to the trash first (`replace`), default: `merge`
+    #[param(value_type = Option<String>, example = "merge")]
+    pub mode: Option<ImportMode>,
+}
+
+impl TodoFormat {
+    /// Returns the content type of the format
+    pub fn content_type(&self) -> &'static str {
+        match self {
+            Self::Json => "application/json",
+            Self::Csv => "text/csv; charset=utf-8",
+            Self::TodoTxt => "text/plain; charset=utf-8",
+            Self::Ics => "text/calendar; charset=utf-8",
+            Self::Markdown => "text/markdown; charset=utf-8",
+        }
+    }
+
+    /// Returns the file extension of the format
+    pub fn extension(&self) -> &'static str {
+        match self {
+            Self::Json => "json",
+            Self::Csv => "csv",
+            Self::TodoTxt => "txt",
+            Self::Ics => "ics",
+            Self::Markdown => "md",
+        }
+    }
+}
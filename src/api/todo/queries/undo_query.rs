@@ -0,0 +1,28 @@
+use serde::{Deserialize, Serialize};
+use utoipa::IntoParams;
+
+use crate::errors::{Error as ApiError, Result as ApiResult};
+
+/// The maximum number of operations that can be undone at once
+pub const MAX_UNDO_STEPS: u64 = 50;
+
+/// The number of operations to undo
+#[derive(IntoParams, Deserialize, Serialize, Debug, Clone, Default)]
+pub struct UndoQuery {
+    /// The number of the last operations to undo, from 1 to 50 (default: 1)
+    #[param(example = "1")]
+    pub steps: Option<u64>,
+}
+
+impl UndoQuery {
+    /// Returns the number of operations to undo, if it's out of range returns an error 400
+    /// Note: Will return 1 if the steps is not set
+    pub fn steps(&self) -> ApiResult<u64> {
+        match self.steps.unwrap_or(1) {
+            steps @ 1..=MAX_UNDO_STEPS => Ok(steps),
+            _ => Err(ApiError::BadRequest(format!(
+                "The steps must be between 1 and {MAX_UNDO_STEPS}"
+            ))),
+        }
+    }
+}
@@ -0,0 +1,10 @@
+use serde::{Deserialize, Serialize};
+use utoipa::IntoParams;
+
+/// The version of the todo that the request is based on
+#[derive(IntoParams, Deserialize, Serialize, Debug, Clone, Default)]
+pub struct VersionQuery {
+    /// The version of the todo, will return `412 Precondition Failed` if it's not the current version (default: no check)
+    #[param(example = "1")]
+    pub version: Option<u32>,
+}
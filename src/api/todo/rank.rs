@@ -0,0 +1,106 @@
+//! Fractional rank keys, used to order the todos manually.
+//!
+//! A rank is a base-62 fraction without the leading `0.`, e.g. `V` is `0.5`. A rank between any two ranks
+//! can be computed, so moving a todo changes only its own rank. The ranks never end with the `0` digit,
+//! so comparing them as strings gives the same order as comparing them as fractions.
+
+/// The digits of the ranks, in ascending ASCII order
+const DIGITS: &[u8] = b"0123456789ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz";
+
+/// The maximum length of a rank, the ranks are rebalanced when a longer rank is needed
+pub const MAX_RANK_LENGTH: usize = 32;
+
+/// Parse the rank into digit indexes, returns `None` if the rank is invalid
+fn parse(rank: &str) -> Option<Vec<usize>> {
+    rank.bytes()
+        .map(|digit| DIGITS.iter().position(|d| *d == digit))
+        .collect::<Option<Vec<_>>>()
+        .filter(|digits| digits.last() != Some(&0))
+}
+
+/// Returns the midpoint of the two ranks, `high` is `None` for the end of the list
+fn midpoint(low: &[usize], high: Option<&[usize]>) -> Vec<usize> {
+    if let Some(high) = high {
+        // The common prefix, the missing digits of `low` are zeros
+        let prefix = high
+            .iter()
+            .enumerate()
+            .take_while(|(idx, digit)| low.get(*idx).copied().unwrap_or(0) == **digit)
+            .count();
+        if prefix > 0 {
+            let mut rank = high[..prefix].to_vec();
+            rank.extend(midpoint(
+                low.get(prefix..).unwrap_or_default(),
+                Some(&high[prefix..]),
+            ));
+            return rank;
+        }
+    }
+    let low_digit = low.first().copied().unwrap_or(0);
+    let high_digit = high
+        .and_then(|high| high.first().copied())
+        .unwrap_or(DIGITS.len());
+    if high_digit - low_digit > 1 {
+        vec![(low_digit + high_digit) / 2]
+    } else if high.map_or(false, |high| high.len() > 1) {
+        vec![high_digit]
+    } else {
+        let mut rank = vec![low_digit];
+        rank.extend(midpoint(low.get(1..).unwrap_or_default(), None));
+        rank
+    }
+}
+
+/// Returns a rank between the two ranks, `None` for `low` is the start of the list and for `high` is the end of it.
+///
+/// Returns `None` if one of the ranks is invalid, or `low` isn't before `high`
+pub fn rank_between(low: Option<&str>, high: Option<&str>) -> Option<String> {
+    let low = low.map_or(Some(Vec::new()), parse)?;
+    let rank = match high.map(parse) {
+        Some(high) => {
+            let high = high.filter(|high| low < *high)?;
+            midpoint(&low, Some(&high))
+        }
+        // Appending is the common case, so the shortest rank after `low` is used
+        None => match low.iter().position(|digit| *digit < DIGITS.len() - 1) {
+            Some(idx) => low[..idx]
+                .iter()
+                .copied()
+                .chain(Some(low[idx] + 1))
+                .collect(),
+            None => midpoint(&low, None),
+        },
+    };
+    Some(
+        rank.into_iter()
+            .map(|digit| DIGITS[digit] as char)
+            .collect(),
+    )
+}
+
+/// Returns `count` evenly spaced ranks in ascending order, used to rebalance the ranks
+pub fn spread_ranks(count: usize) -> Vec<String> {
+    let base = DIGITS.len() as u128;
+    let slots = count as u128 + 1;
+    // Leave at least a digit of free ranks between each two ranks
+    let (mut length, mut space) = (1, base);
+    while space < slots * base {
+        length += 1;
+        space *= base;
+    }
+    let step = space / slots;
+    (1..slots)
+        .map(|idx| {
+            let mut value = idx * step;
+            let mut rank = vec![DIGITS[0]; length];
+            for digit in rank.iter_mut().rev() {
+                *digit = DIGITS[(value % base) as usize];
+                value /= base;
+            }
+            while rank.last() == Some(&DIGITS[0]) {
+                rank.pop();
+            }
+            String::from_utf8_lossy(&rank).into_owned()
+        })
+        .collect()
+}
@@ -0,0 +1,48 @@
+use actix_web::{get, web, HttpRequest};
+use sea_orm::DatabaseConnection;
+
+use crate::{
+    api::auth::utils::req_auth,
+    api::todo::{queries::StatsFilters, utils},
+    errors::{ErrorTrait, Result as ApiResult},
+    schemas::{message::MessageSchema, todo::TodoStatsSchema, traits::OpenApiExample},
+};
+
+/// Get the statistics of the user todos.
+///
+/// Returns the number of todos for each status, the total compared with the maximum number of todos,
+/// and the created and completed todos per day or week in the given range.
+#[utoipa::path(
+    context_path = "/api/todos",
+    params(StatsFilters),
+    responses(
+        (
+            status = 200, description = "The statistics of the user todos", body = TodoStatsSchema,
+            example = json!(TodoStatsSchema::openapi_example())
+        ),
+        (
+            status = 400, description = "The range is invalid", body = MessageSchema,
+            example = json!(MessageSchema::new(400, "The `from` timestamp must be before the `to` timestamp"))
+        ),
+    ),
+    tag = "Todo",
+    security(("Bearer Token" = []))
+)]
+#[get("/stats")]
+pub async fn stats(
+    req: HttpRequest,
+    db: web::Data<DatabaseConnection>,
+    params: web::Query<StatsFilters>,
+) -> ApiResult<TodoStatsSchema> {
+    let db = db.get_ref();
+    let user = req_auth(req, db).await?;
+    let range = params.range()?;
+
+    let todos = utils::user_todos(user.id).all(db).await.database_err()?;
+    Ok(TodoStatsSchema::new(
+        &todos,
+        &params,
+        range,
+        utils::max_todos_count(),
+    ))
+}
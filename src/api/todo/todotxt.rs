@@ -0,0 +1,141 @@
+use chrono::{NaiveDate, NaiveDateTime};
+use entity::todo::Status as TodoStatus;
+
+use crate::{
+    api::todo::transfer::ImportedTodo,
+    schemas::todo::{TodoContentSchema, TodoSchema},
+};
+
+/// The format of the todo.txt dates
+const DATE_FORMAT: &str = "%Y-%m-%d";
+/// The length of the todo.txt dates, e.g. `2023-05-01`
+const DATE_LENGTH: usize = 10;
+/// The key of the tag that keeps the statuses that todo.txt doesn't have (`progress` and `cancelled`)
+const STATUS_KEY: &str = "status:";
+
+/// Returns the Unix timestamp of the start of the date, if the word is a todo.txt date
+fn parse_date(word: &str) -> Option<i64> {
+    if word.len() != DATE_LENGTH {
+        return None;
+    }
+    NaiveDate::parse_from_str(word, DATE_FORMAT)
+        .ok()?
+        .and_hms_opt(0, 0, 0)
+        .map(|date| date.timestamp())
+}
+
+/// Returns the todo.txt date of the Unix timestamp
+fn format_date(timestamp: i64) -> String {
+    NaiveDateTime::from_timestamp_opt(timestamp, 0)
+        .unwrap_or_default()
+        .format(DATE_FORMAT)
+        .to_string()
+}
+
+/// Split the first word of the text if it's a date, returns the date and the rest of the text
+fn take_date(text: &str) -> (Option<i64>, &str) {
+    let (word, rest) = text.split_once(' ').unwrap_or((text, ""));
+    match parse_date(word) {
+        Some(date) => (Some(date), rest),
+        None => (None, text),
+    }
+}
+
+/// Split the priority of the text, e.g. `(A) `, returns the priority and the rest of the text
+fn take_priority(text: &str) -> Option<(&str, &str)> {
+    let bytes = text.as_bytes();
+    (bytes.len() > 4
+        && bytes[0] == b'('
+        && bytes[1].is_ascii_uppercase()
+        && bytes[2] == b')'
+        && bytes[3] == b' ')
+        .then(|| (&text[..3], &text[4..]))
+}
+
+/// Parse a todo.txt line, returns why the line is invalid if it can't be a todo.
+///
+/// The completion marker (`x`) is mapped to the `completed` status, the completion and creation dates
+/// to the todo times, and the `status:` tag to the other statuses. The rest of the line is the title,
+/// with the priority, the `+project`, `@context` and the other tags as is.
+pub fn parse_line(line: &str) -> Result<ImportedTodo, String> {
+    let (completed, mut text) = match line.strip_prefix("x ") {
+        Some(text) => (true, text),
+        None => (false, line),
+    };
+    let mut priority = None;
+    if !completed {
+        if let Some((found, rest)) = take_priority(text) {
+            priority = Some(found);
+            text = rest;
+        }
+    }
+
+    let (first_date, rest) = take_date(text);
+    text = rest;
+    let (completed_at, created_at) = if completed && first_date.is_some() {
+        // The completion date comes first, then the creation date
+        let (created_at, rest) = take_date(text);
+        text = rest;
+        (first_date, created_at)
+    } else {
+        (None, first_date)
+    };
+
+    let mut status = None;
+    let title = text
+        .split(' ')
+        .filter(|word| match word.strip_prefix(STATUS_KEY).map(str::parse) {
+            Some(Ok(tag)) if status.is_none() => {
+                status = Some(tag);
+                false
+            }
+            _ => true,
+        })
+        .collect::<Vec<_>>()
+        .join(" ");
+    let status = if completed {
+        TodoStatus::Completed
+    } else {
+        match status {
+            Some(TodoStatus::Completed) => {
+                return Err("The completed todos should start with `x `".to_owned())
+            }
+            Some(status) => status,
+            None => TodoStatus::Pending,
+        }
+    };
+
+    Ok(ImportedTodo {
+        content: TodoContentSchema {
+            title: match priority {
+                Some(priority) => format!("{priority} {title}"),
+                None => title,
+            },
+            status,
+        },
+        created_at,
+        completed_at,
+    })
+}
+
+/// Returns the todo.txt line of the todo, the opposite of `parse_line`
+pub fn format_line(todo: &TodoSchema) -> String {
+    let created_at = format_date(todo.created_at);
+    let mut line = match todo.status {
+        TodoStatus::Completed => format!(
+            "x {} {created_at} {}",
+            format_date(todo.completed_at.unwrap_or(todo.updated_at)),
+            todo.title
+        ),
+        _ => match take_priority(&todo.title) {
+            Some((priority, title)) => format!("{priority} {created_at} {title}"),
+            None => format!("{created_at} {}", todo.title),
+        },
+    };
+    if matches!(todo.status, TodoStatus::Progress | TodoStatus::Cancelled) {
+        line.push(' ');
+        line.push_str(STATUS_KEY);
+        line.push_str(todo.status.as_str());
+    }
+    line
+}
@@ -0,0 +1,296 @@
+use actix_web::web::Bytes;
+use futures_util::{stream, Stream};
+use sea_orm::{ColumnTrait, Condition, DatabaseConnection, QueryFilter, QueryOrder, QuerySelect};
+use serde::Deserialize;
+use serde_json::Value;
+
+use crate::{
+    api::todo::{
+        ical, markdown,
+        queries::{TodoFilters, TodoFormat},
+        todotxt, utils,
+    },
+    errors::{Error as ApiError, ErrorTrait, Result as ApiResult},
+    schemas::todo::{TodoContentSchema, TodoSchema},
+};
+use entity::todo::{Column as TodoColumn, Model as TodoModel, Status as TodoStatus};
+
+/// The number of todos fetched and encoded at once while exporting
+const EXPORT_BATCH_SIZE: u64 = 100;
+/// The columns of the exported CSV files, the imported files should have `title` and `status` columns
+const CSV_HEADERS: [&str; 9] = [
+    "uuid",
+    "title",
+    "status",
+    "created_at",
+    "updated_at",
+    "completed_at",
+    "version",
+    "deleted_at",
+    "position",
+];
+
+/// A todo of the imported file, with its times if the file has them
+pub struct ImportedTodo {
+    /// The title and the status of the todo
+    pub content: TodoContentSchema,
+    /// The creation time (Unix timestamp)
+    pub created_at: Option<i64>,
+    /// The completion time (Unix timestamp)
+    pub completed_at: Option<i64>,
+}
+
+/// A todo row of the JSON and CSV files, the exported fields other than the times are ignored
+#[derive(Deserialize)]
+struct TodoRow {
+    title: String,
+    status: TodoStatus,
+    created_at: Option<i64>,
+    completed_at: Option<i64>,
+}
+
+impl From<TodoRow> for ImportedTodo {
+    fn from(row: TodoRow) -> Self {
+        Self {
+            content: TodoContentSchema {
+                title: row.title,
+                status: row.status,
+            },
+            created_at: row.created_at,
+            completed_at: row.completed_at,
+        }
+    }
+}
+
+/// The state of the export stream
+enum ExportState {
+    /// The file header is not sent yet
+    Start,
+    /// The todos after the cursor (position and id) are not sent yet
+    Todos(Option<(String, u32)>),
+    /// The file is ended
+    Done,
+}
+
+/// Returns what is written before the todos in the file
+fn file_header(format: TodoFormat) -> ApiResult<Bytes> {
+    match format {
+        TodoFormat::Json => Ok(Bytes::from_static(b"[")),
+        TodoFormat::Csv => {
+            let mut writer = csv::Writer::from_writer(Vec::new());
+            writer
+                .write_record(CSV_HEADERS)
+                .server_err("Failed to write the CSV header")?;
+            Ok(writer
+                .into_inner()
+                .server_err("Failed to write the CSV header")?
+                .into())
+        }
+        TodoFormat::TodoTxt | TodoFormat::Markdown => Ok(Bytes::new()),
+        TodoFormat::Ics => Ok(Bytes::from_static(ical::CALENDAR_HEADER.as_bytes())),
+    }
+}
+
+/// Returns what is written after the todos in the file
+fn file_footer(format: TodoFormat) -> Bytes {
+    match format {
+        TodoFormat::Json => Bytes::from_static(b"\n]\n"),
+        TodoFormat::Csv | TodoFormat::TodoTxt | TodoFormat::Markdown => Bytes::new(),
+        TodoFormat::Ics => Bytes::from_static(ical::CALENDAR_FOOTER.as_bytes()),
+    }
+}
+
+/// Encode a batch of todos, `first` is `true` if it's the first batch in the file
+fn encode_todos(format: TodoFormat, todos: &[TodoSchema], first: bool) -> ApiResult<Bytes> {
+    match format {
+        TodoFormat::Json => {
+            let mut buf = Vec::new();
+            for (idx, todo) in todos.iter().enumerate() {
+                buf.extend_from_slice(if first && idx == 0 { b"\n" } else { b",\n" });
+                serde_json::to_writer(&mut buf, todo).server_err("Failed to serialize the todo")?;
+            }
+            Ok(buf.into())
+        }
+        TodoFormat::Csv => {
+            let mut writer = csv::WriterBuilder::new()
+                .has_headers(false)
+                .from_writer(Vec::new());
+            for todo in todos {
+                writer
+                    .serialize(todo)
+                    .server_err("Failed to serialize the todo")?;
+            }
+            Ok(writer
+                .into_inner()
+                .server_err("Failed to serialize the todo")?
+                .into())
+        }
+        TodoFormat::TodoTxt => Ok(todos
+            .iter()
+            .map(|todo| todotxt::format_line(todo) + "\n")
+            .collect::<String>()
+            .into()),
+        TodoFormat::Ics => Ok(todos
+            .iter()
+            .map(ical::format_todo)
+            .collect::<String>()
+            .into()),
+        TodoFormat::Markdown => Ok(todos
+            .iter()
+            .map(|todo| markdown::format_item(todo) + "\n")
+            .collect::<String>()
+            .into()),
+    }
+}
+
+/// Returns the todos of the user that match the filters after the cursor (position and id), in the manual order
+async fn todos_after(
+    user_id: u32,
+    filters: &TodoFilters,
+    cursor: Option<(String, u32)>,
+    db: &DatabaseConnection,
+) -> ApiResult<Vec<TodoModel>> {
+    let mut select = filters.filter(utils::user_todos(user_id));
+    if let Some((position, id)) = cursor {
+        select = select.filter(
+            Condition::any()
+                .add(TodoColumn::Position.gt(position.as_str()))
+                .add(
+                    Condition::all()
+                        .add(TodoColumn::Position.eq(position))
+                        .add(TodoColumn::Id.gt(id)),
+                ),
+        );
+    }
+    select
+        .order_by_asc(TodoColumn::Position)
+        .order_by_asc(TodoColumn::Id)
+        .limit(EXPORT_BATCH_SIZE)
+        .all(db)
+        .await
+        .database_err()
+}
+
+/// Returns all the user todos that match the filters, in the manual order
+pub async fn export_todos(
+    user_id: u32,
+    filters: &TodoFilters,
+    db: &DatabaseConnection,
+) -> ApiResult<Vec<TodoSchema>> {
+    let mut todos = Vec::new();
+    let mut cursor = None;
+    loop {
+        let batch = todos_after(user_id, filters, cursor, db).await?;
+        let Some(last) = batch.last() else {
+            return Ok(todos);
+        };
+        cursor = Some((last.position.clone(), last.id));
+        todos.extend(batch.into_iter().map(TodoSchema::from));
+    }
+}
+
+/// Returns a stream of the user todos that match the filters encoded in the given format, in the manual order.
+/// The todos are fetched in batches, so the whole file is never in memory
+pub fn export_stream(
+    user_id: u32,
+    format: TodoFormat,
+    filters: TodoFilters,
+    db: DatabaseConnection,
+) -> impl Stream<Item = ApiResult<Bytes>> {
+    stream::unfold(ExportState::Start, move |state| {
+        let db = db.clone();
+        let filters = filters.clone();
+        async move {
+            match state {
+                ExportState::Start => Some((file_header(format), ExportState::Todos(None))),
+                ExportState::Todos(cursor) => {
+                    let first = cursor.is_none();
+                    let todos = match todos_after(user_id, &filters, cursor, &db).await {
+                        Ok(todos) => todos,
+                        Err(err) => return Some((Err(err), ExportState::Done)),
+                    };
+                    let Some(last) = todos.last() else {
+                        return Some((Ok(file_footer(format)), ExportState::Done));
+                    };
+                    let cursor = Some((last.position.clone(), last.id));
+                    let todos = todos.into_iter().map(TodoSchema::from).collect::<Vec<_>>();
+                    Some((
+                        encode_todos(format, &todos, first),
+                        ExportState::Todos(cursor),
+                    ))
+                }
+                ExportState::Done => None,
+            }
+        }
+    })
+}
+
+/// Decode the imported file, returns the number of each row (starts from 1) with its todo or why it's invalid.
+/// If the file itself is invalid returns an error 400
+pub fn decode_todos(
+    format: TodoFormat,
+    body: &[u8],
+) -> ApiResult<Vec<(u64, Result<ImportedTodo, String>)>> {
+    let rows = match format {
+        TodoFormat::Json => (1..)
+            .zip(
+                serde_json::from_slice::<Vec<Value>>(body)
+                    .bad_request_err("The file should be a JSON array of todos")?,
+            )
+            .map(|(row, todo)| {
+                let todo = serde_json::from_value::<TodoRow>(todo)
+                    .map(ImportedTodo::from)
+                    .map_err(|err| format!("The todo is invalid: {err}"));
+                (row, todo)
+            })
+            .collect::<Vec<_>>(),
+        TodoFormat::Csv => {
+            let mut reader = csv::Reader::from_reader(body);
+            let headers = reader
+                .headers()
+                .bad_request_err("The CSV file should have a header row")?;
+            if !["title", "status"]
+                .iter()
+                .all(|column| headers.iter().any(|header| header == *column))
+            {
+                return Err(ApiError::BadRequest(
+                    "The CSV file should have `title` and `status` columns".to_owned(),
+                ));
+            }
+            (1..)
+                .zip(reader.deserialize::<TodoRow>())
+                .map(|(row, todo)| {
+                    let todo = todo
+                        .map(ImportedTodo::from)
+                        .map_err(|err| format!("The todo is invalid: {err}"));
+                    (row, todo)
+                })
+                .collect()
+        }
+        TodoFormat::TodoTxt => (1..)
+            .zip(
+                std::str::from_utf8(body)
+                    .bad_request_err("The todo.txt file should be UTF-8 text")?
+                    .lines(),
+            )
+            .filter(|(_, line)| !line.trim().is_empty())
+            .map(|(row, line)| (row, todotxt::parse_line(line)))
+            .collect(),
+        TodoFormat::Ics => (1..)
+            .zip(ical::parse_calendar(
+                std::str::from_utf8(body)
+                    .bad_request_err("The iCalendar file should be UTF-8 text")?,
+            )?)
+            .collect(),
+        TodoFormat::Markdown => markdown::parse_checklist(
+            std::str::from_utf8(body).bad_request_err("The Markdown file should be UTF-8 text")?,
+        ),
+    };
+    if rows.len() as u64 > utils::max_todos_count() {
+        return Err(ApiError::BadRequest(format!(
+            "The file has more than {} todos",
+            utils::max_todos_count()
+        )));
+    }
+    Ok(rows)
+}
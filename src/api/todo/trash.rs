@@ -0,0 +1,98 @@
+use actix_web::{get, post, web, HttpRequest};
+use entity::todo::Column as TodoColumn;
+use sea_orm::{DatabaseConnection, PaginatorTrait, QueryOrder, QuerySelect, TransactionTrait};
+use uuid::Uuid;
+
+use crate::{
+    api::auth::utils::req_auth,
+    api::todo::{queries::TodoFilters, utils},
+    errors::{ErrorTrait, Result as ApiResult},
+    schemas::{
+        message::MessageSchema,
+        todo::{TodoListSchema, TodoSchema},
+        traits::OpenApiExample,
+    },
+};
+
+/// list the todos in the trash, filterable by status, title, limit, offset, order, and order_by.
+///
+/// The todos stay in the trash for `TRASH_RETENTION_DAYS` days (default: 30), then they are permanently deleted.
+#[utoipa::path(
+    context_path = "/api/todos",
+    params(TodoFilters),
+    responses(
+        (
+            status = 200, description = "List the todos in the trash", body = TodoListSchema,
+            example = json!(TodoListSchema::openapi_example())
+        )
+    ),
+    tag = "Todo",
+    security(("Bearer Token" = []))
+)]
+#[get("/trash")]
+pub async fn list_trash(
+    req: HttpRequest,
+    db: web::Data<DatabaseConnection>,
+    params: web::Query<TodoFilters>,
+) -> ApiResult<TodoListSchema> {
+    let db = db.get_ref();
+    let user = req_auth(req, db).await?;
+    let query = params.filter(utils::user_trash(user.id));
+
+    let total = query.clone().count(db).await.database_err()?;
+    query
+        .order_by(TodoColumn::from(params.order_by()), params.order().into())
+        .limit(params.limit())
+        .offset(params.offset())
+        .all(db)
+        .await
+        .map(|todos| {
+            TodoListSchema::new(todos.into_iter().map(From::from).collect(), &params, total)
+        })
+        .database_err()
+}
+
+/// Restore a todo from the trash by uuid.
+#[utoipa::path(
+    context_path = "/api/todos",
+    params(
+        (
+            "uuid", description = "The uuid of the todo",
+            example = "b5a5d4e4-7d4e-4f4a-9f3d-3f3f3f3f3f3f"
+        )
+    ),
+    responses(
+        (
+            status = 200, description = "The restored todo", body = TodoSchema,
+            example = json!(TodoSchema::openapi_example())
+        ),
+        (
+            status = 404, description = "There is no todo in the trash with the given uuid", body = MessageSchema,
+            example = json!(MessageSchema::new(404, "There is no todo in the trash with the given uuid"))
+        ),
+        (
+            status = 400, description = "Dubplicate todo", body = MessageSchema,
+            example = json!(MessageSchema::new(400, "The todo `{title}` already exists"))
+        ),
+        (
+            status = 400, description = "The maximum number of todos is reached", body = MessageSchema,
+            example = json!(MessageSchema::new(400, "The maximum number of todos is 500"))
+        ),
+    ),
+    tag = "Todo",
+    security(("Bearer Token" = []))
+)]
+#[post("/{uuid}/restore")]
+pub async fn restore(
+    req: HttpRequest,
+    db: web::Data<DatabaseConnection>,
+    uuid: web::Path<Uuid>,
+) -> ApiResult<TodoSchema> {
+    let db = db.get_ref();
+    let user = req_auth(req.clone(), db).await?;
+    let todo = utils::find_trashed_todo_by_uuid(*uuid, user.id, db).await?;
+    let txn = db.begin().await.database_err()?;
+    let todo = utils::restore_todo(todo, &utils::Actor::new(user.id, &req), &txn).await?;
+    txn.commit().await.database_err()?;
+    Ok(todo.into())
+}
@@ -1,13 +1,15 @@
 use crate::api::auth::utils::req_auth;
 use crate::api::todo::utils;
-use crate::errors::Result as ApiResult;
+use crate::errors::{ErrorTrait, Result as ApiResult};
 use crate::schemas::todo::{TodoSchema, UpdateTodoSchema};
 use crate::schemas::{message::MessageSchema, traits::OpenApiExample};
 use actix_web::{put, web, HttpRequest};
-use sea_orm::DatabaseConnection;
+use sea_orm::{DatabaseConnection, TransactionTrait};
 use uuid::Uuid;
 
 /// Update a single todo by uuid, only the title and status can be updated.
+///
+/// To prevent overwriting others changes, send the todo `ETag` in `If-Match` header or its version in `version` field.
 #[utoipa::path(
     context_path = "/api/todos",
     request_body = UpdateTodoSchema,
@@ -25,6 +27,10 @@ use uuid::Uuid;
         (
             status = 404, description = "There is no todo with the given uuid", body = MessageSchema,
             example = json!(MessageSchema::new(404, "There is no todo with the given uuid"))
+        ),
+        (
+            status = 412, description = "The todo has been modified, returns the current todo", body = TodoSchema,
+            example = json!(TodoSchema::openapi_example())
         )
     ),
     tag = "Todo",
@@ -39,11 +45,14 @@ pub async fn update_todo(
 ) -> ApiResult<TodoSchema> {
     let payload = payload.into_inner();
     let db = db.as_ref();
-    let user = req_auth(req, db).await?;
+    let user = req_auth(req.clone(), db).await?;
     let todo = utils::find_todo_by_uuid(*uuid, user.id, db).await?;
+    utils::check_version(&req, &todo, payload.version)?;
     // If the title is not changed, then set it to None. Otherwise, set it to Some(payload.title)
     let todo_title = payload.title.filter(|title| title != &todo.title);
-    utils::update_todo(todo, todo_title, payload.status, db)
-        .await
-        .map(TodoSchema::from)
+    let actor = utils::Actor::new(user.id, &req);
+    let txn = db.begin().await.database_err()?;
+    let todo = utils::update_todo(todo, todo_title, payload.status, &actor, &txn).await?;
+    txn.commit().await.database_err()?;
+    Ok(todo.into())
 }
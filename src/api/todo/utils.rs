@@ -1,18 +1,29 @@
-use std::env;
+use std::{borrow::Cow, env};
 
 use crate::{
+    api::{todo::rank, webhook::utils as webhook_utils},
+    conditional,
     errors::{Error as ApiError, ErrorTrait, Result as ApiResult},
+    notifications,
     schemas::todo::{TodoContentSchema, TodoSchema},
 };
+use actix_web::HttpRequest;
 use chrono::Utc;
 use entity::todo::{
     ActiveModel as NewTodo, Column as TodoColumn, Entity as TodoEntity, Model as TodoModel,
     Status as TodoStatus,
 };
+use entity::todo_history::{
+    Action as HistoryAction, ActiveModel as NewHistory, Model as HistoryModel,
+};
 use sea_orm::{
-    ActiveModelTrait, ColumnTrait, DatabaseConnection, EntityTrait, PaginatorTrait, QueryFilter,
-    Select, Set,
+    sea_query::{Expr, Query, SimpleExpr, SubQueryStatement},
+    ActiveModelTrait, ColumnTrait, Condition, ConnectionTrait, DbErr, EntityTrait, QueryFilter,
+    QueryOrder, RuntimeErr, Select, Set,
 };
+use serde::de::DeserializeOwned;
+use serde_json::{json, Map, Value};
+use sqlx_core::error::Error as SqlxError;
 use uuid::Uuid;
 
 /// Returns a unique UUID
@@ -25,7 +36,7 @@ use uuid::Uuid;
 pub async fn unique_uuid<E>(
     select: Select<E>,
     column: impl ColumnTrait,
-    db: &DatabaseConnection,
+    db: &impl ConnectionTrait,
 ) -> ApiResult<Uuid>
 where
     E: EntityTrait,
@@ -52,6 +63,112 @@ where
     }
 }
 
+/// The maximum length of the request id given by the client
+const MAX_REQUEST_ID_LENGTH: usize = 64;
+
+/// Who made a change, recorded in the todo history
+#[derive(Debug, Clone)]
+pub struct Actor {
+    /// The id of the user that made the change
+    pub user_id: u32,
+    /// The id of the request that made the change, taken from the `X-Request-Id` header or generated
+    pub request_id: String,
+    /// The request id of the operation that the change undoes, `None` if the change is not an undo
+    pub undo_of: Option<String>,
+}
+
+impl Actor {
+    /// Create a new actor for the given user and request
+    pub fn new(user_id: u32, req: &HttpRequest) -> Self {
+        Self::with_request_id(
+            user_id,
+            req.headers()
+                .get("X-Request-Id")
+                .and_then(|id| id.to_str().ok()),
+        )
+    }
+
+    /// Create a new actor for the given user with the client request id, a new one is generated
+    /// if it's not given, empty or longer than the maximum length
+    pub fn with_request_id(user_id: u32, request_id: Option<&str>) -> Self {
+        let request_id = request_id
+            .filter(|id| !id.is_empty() && id.len() <= MAX_REQUEST_ID_LENGTH)
+            .map(ToOwned::to_owned)
+            .unwrap_or_else(|| Uuid::new_v4().to_string());
+        Self {
+            user_id,
+            request_id,
+            undo_of: None,
+        }
+    }
+
+    /// Returns the same actor, undoing the operation with the given request id
+    pub fn undoing(&self, request_id: &str) -> Self {
+        Self {
+            undo_of: Some(request_id.to_owned()),
+            ..self.clone()
+        }
+    }
+}
+
+/// Returns the field-level changes between two serialized todos, as `{"field": {"old": .., "new": ..}}`.
+/// The `updated_at` and `version` fields are excluded, because they are changed with every change
+pub fn todo_changes(old: &Value, new: &Value) -> Map<String, Value> {
+    let empty = Map::new();
+    let old = old.as_object().unwrap_or(&empty);
+    let new = new.as_object().unwrap_or(&empty);
+    new.keys()
+        .chain(old.keys().filter(|key| !new.contains_key(*key)))
+        .filter(|key| !matches!(key.as_str(), "updated_at" | "version"))
+        .filter_map(|key| {
+            let (old, new) = (
+                old.get(key).unwrap_or(&Value::Null),
+                new.get(key).unwrap_or(&Value::Null),
+            );
+            (old != new).then(|| (key.clone(), json!({ "old": old, "new": new })))
+        })
+        .collect()
+}
+
+/// Record a change of a todo in its history, `old` is `None` if the todo is created
+pub async fn record_history(
+    action: HistoryAction,
+    old: Option<&TodoModel>,
+    new: &TodoModel,
+    actor: &Actor,
+    db: &impl ConnectionTrait,
+) -> ApiResult<HistoryModel> {
+    let serialize = |todo: &TodoModel| {
+        serde_json::to_value(TodoSchema::from(todo.clone()))
+            .server_err("Failed to serialize the todo")
+    };
+    let snapshot = serialize(new)?;
+    let changes = todo_changes(
+        &old.map(serialize).transpose()?.unwrap_or_default(),
+        &snapshot,
+    );
+
+    let history = NewHistory {
+        todo_uuid: Set(new.uuid),
+        user_id: Set(new.user_id),
+        actor_id: Set(actor.user_id),
+        action: Set(action),
+        changes: Set(Value::Object(changes).to_string()),
+        snapshot: Set(snapshot.to_string()),
+        request_id: Set(actor.request_id.clone()),
+        created_at: Set(Utc::now().naive_utc().timestamp()),
+        undo_of: Set(actor.undo_of.clone()),
+        ..Default::default()
+    }
+    .insert(db)
+    .await
+    .database_err()?;
+    // The deliveries are queued with the change, so they are sent only if the change is committed
+    webhook_utils::queue_deliveries(&history, db).await?;
+    notifications::queue_notifications(&history, db).await?;
+    Ok(history)
+}
+
 /// Returns the maximum number of todos that can be created by a user
 pub fn max_todos_count() -> u64 {
     env::var("MAXIMUM_TODO_PER_USER")
@@ -68,18 +185,176 @@ pub fn max_todo_title_length() -> u64 {
         .unwrap_or(100)
 }
 
+/// Returns the number of days that the todos stay in the trash before being purged
+pub fn trash_retention_days() -> u64 {
+    env::var("TRASH_RETENTION_DAYS")
+        .unwrap_or_else(|_| "30".to_string())
+        .parse()
+        .unwrap_or(30)
+}
+
+/// Returns the number of minutes that the operations can be undone after
+pub fn undo_window_minutes() -> u64 {
+    env::var("UNDO_WINDOW_MINUTES")
+        .unwrap_or_else(|_| "60".to_string())
+        .parse()
+        .unwrap_or(60)
+}
+
+/// Returns a query of the user todos, the todos in the trash are excluded
+pub fn user_todos(user_id: u32) -> Select<TodoEntity> {
+    TodoEntity::find()
+        .filter(TodoColumn::UserId.eq(user_id))
+        .filter(TodoColumn::DeletedAt.is_null())
+}
+
+/// Returns the last time that a todo of the user is changed (Unix timestamp), including the todos
+/// that are moved to the trash, so a removed todo changes the time of the list too
+pub async fn todos_last_modified(
+    user_id: u32,
+    db: &impl ConnectionTrait,
+) -> ApiResult<Option<i64>> {
+    let todos = TodoEntity::find().filter(TodoColumn::UserId.eq(user_id));
+    let updated_at = todos
+        .clone()
+        .order_by_desc(TodoColumn::UpdatedAt)
+        .one(db)
+        .await
+        .database_err()?
+        .map(|todo| todo.updated_at);
+    let deleted_at = todos
+        .order_by_desc(TodoColumn::DeletedAt)
+        .one(db)
+        .await
+        .database_err()?
+        .and_then(|todo| todo.deleted_at);
+    Ok(updated_at.max(deleted_at))
+}
+
+/// Returns a query of the user todos that are in the trash
+pub fn user_trash(user_id: u32) -> Select<TodoEntity> {
+    TodoEntity::find()
+        .filter(TodoColumn::UserId.eq(user_id))
+        .filter(TodoColumn::DeletedAt.is_not_null())
+}
+
+/// Returns a condition that is true if the user has less than the maximum number of todos.
+/// It's checked in the same statement of the insert or the update, so the concurrent requests can't exceed the maximum
+fn below_todos_quota(user_id: u32) -> SimpleExpr {
+    let count = Query::select()
+        .expr(Expr::col(TodoColumn::Id).count())
+        .from(TodoEntity)
+        .and_where(TodoColumn::UserId.eq(user_id))
+        .and_where(TodoColumn::DeletedAt.is_null())
+        .to_owned();
+    Expr::expr(SimpleExpr::SubQuery(
+        None,
+        Box::new(SubQueryStatement::SelectStatement(count)),
+    ))
+    .lt(max_todos_count())
+}
+
+/// Returns the error of reaching the maximum number of todos
+fn max_todos_err() -> ApiError {
+    ApiError::BadRequest(format!(
+        "The maximum number of todos is {}",
+        max_todos_count()
+    ))
+}
+
+/// Map the database error of saving a todo, the violation of the unique title index is mapped to an error 400,
+/// and the violation of the unique uuid (a given uuid that is used meanwhile) is mapped to an error 409
+fn save_todo_err(err: DbErr, title: &str) -> ApiError {
+    match err {
+        // SQLITE_CONSTRAINT_UNIQUE, the message names the columns of the violated constraint
+        DbErr::Exec(RuntimeErr::SqlxError(SqlxError::Database(err)))
+        | DbErr::Query(RuntimeErr::SqlxError(SqlxError::Database(err)))
+            if err.code() == Some(Cow::Borrowed("2067")) =>
+        {
+            if err.message().contains("todo.uuid") {
+                ApiError::Conflict("There is already a todo with the given uuid".to_owned())
+            } else {
+                ApiError::BadRequest(format!("The todo `{title}` is already exists"))
+            }
+        }
+        err => {
+            log::error!("Failed to save the todo: {err}");
+            ApiError::InternalServer("Database error ):".to_owned())
+        }
+    }
+}
+
+/// Returns the error of a todo change that is not saved because the todo is modified since it's read,
+/// an error 412 with the current todo, or an error 404 if the todo is purged
+fn modified_todo_err(current: Option<TodoModel>) -> ApiError {
+    match current {
+        Some(current) => ApiError::PreconditionFailed(Box::new(current.into())),
+        None => ApiError::NotFound("There is no todo with the given uuid".to_owned()),
+    }
+}
+
+/// Save the changes of a todo only if its version is not changed since it's read, so the concurrent
+/// changes can't overwrite each other. If the todo is modified meanwhile returns an error 412 with the current todo
+async fn save_todo(
+    todo: &TodoModel,
+    changes: NewTodo,
+    db: &impl ConnectionTrait,
+) -> ApiResult<TodoModel> {
+    let title = changes.title.clone().unwrap();
+    let res = TodoEntity::update_many()
+        .set(changes)
+        .filter(TodoColumn::Id.eq(todo.id))
+        .filter(TodoColumn::Version.eq(todo.version))
+        .exec(db)
+        .await
+        .map_err(|err| save_todo_err(err, &title))?;
+    let saved = TodoEntity::find_by_id(todo.id)
+        .one(db)
+        .await
+        .database_err()?;
+    if res.rows_affected == 0 {
+        return Err(modified_todo_err(saved));
+    }
+    saved.server_err("The saved todo is not found")
+}
+
+/// Save the changes of a todo that is moved out of the trash like `save_todo`,
+/// if the maximum number of todos is reached returns an error 400
+async fn save_restored_todo(
+    todo: &TodoModel,
+    changes: NewTodo,
+    db: &impl ConnectionTrait,
+) -> ApiResult<TodoModel> {
+    let title = changes.title.clone().unwrap();
+    let res = TodoEntity::update_many()
+        .set(changes)
+        .filter(TodoColumn::Id.eq(todo.id))
+        .filter(TodoColumn::Version.eq(todo.version))
+        .filter(below_todos_quota(todo.user_id))
+        .exec(db)
+        .await
+        .map_err(|err| save_todo_err(err, &title))?;
+    let saved = TodoEntity::find_by_id(todo.id)
+        .one(db)
+        .await
+        .database_err()?;
+    if res.rows_affected == 0 {
+        return Err(match saved {
+            Some(current) if current.version == todo.version => max_todos_err(),
+            current => modified_todo_err(current),
+        });
+    }
+    saved.server_err("The restored todo is not found")
+}
+
 /// Returns whether if there is a todo with the given title and user id
 pub async fn is_todo_title_exists(
     title: &str,
     user_id: u32,
-    db: &DatabaseConnection,
+    db: &impl ConnectionTrait,
 ) -> ApiResult<bool> {
-    Ok(TodoEntity::find()
-        .filter(
-            TodoColumn::Title
-                .eq(title)
-                .and(TodoColumn::UserId.eq(user_id)),
-        )
+    Ok(user_todos(user_id)
+        .filter(TodoColumn::Title.eq(title))
         .one(db)
         .await
         .database_err()?
@@ -90,10 +365,9 @@ pub async fn is_todo_title_exists(
 pub async fn find_todo_by_uuid(
     uuid: Uuid,
     user_id: u32,
-    db: &DatabaseConnection,
+    db: &impl ConnectionTrait,
 ) -> ApiResult<TodoModel> {
-    TodoEntity::find()
-        .filter(TodoColumn::UserId.eq(user_id))
+    user_todos(user_id)
         .filter(TodoColumn::Uuid.eq(uuid))
         .one(db)
         .await
@@ -101,12 +375,179 @@ pub async fn find_todo_by_uuid(
         .not_found_err("There is no todo with the given uuid")
 }
 
+/// Returns a todo in the trash by uuid, if the todo is not found, returns an error 404
+pub async fn find_trashed_todo_by_uuid(
+    uuid: Uuid,
+    user_id: u32,
+    db: &impl ConnectionTrait,
+) -> ApiResult<TodoModel> {
+    user_trash(user_id)
+        .filter(TodoColumn::Uuid.eq(uuid))
+        .one(db)
+        .await
+        .database_err()?
+        .not_found_err("There is no todo in the trash with the given uuid")
+}
+
+/// Move a todo to the trash, it will be purged after the trash retention period
+pub async fn trash_todo(
+    todo: TodoModel,
+    actor: &Actor,
+    db: &impl ConnectionTrait,
+) -> ApiResult<TodoModel> {
+    let current_time = Utc::now().naive_utc().timestamp();
+    let changes = NewTodo {
+        deleted_at: Set(Some(current_time)),
+        updated_at: Set(current_time),
+        version: Set(todo.version + 1),
+        ..todo.clone().into()
+    };
+    let trashed = save_todo(&todo, changes, db).await?;
+    record_history(HistoryAction::Deleted, Some(&todo), &trashed, actor, db).await?;
+    Ok(trashed)
+}
+
+/// Restore a todo from the trash, if the todo with the same title already exists
+/// or the maximum number of todos is reached, returns an error 400
+pub async fn restore_todo(
+    todo: TodoModel,
+    actor: &Actor,
+    db: &impl ConnectionTrait,
+) -> ApiResult<TodoModel> {
+    if is_todo_title_exists(&todo.title, todo.user_id, db).await? {
+        return Err(ApiError::BadRequest(format!(
+            "The todo `{}` is already exists",
+            todo.title
+        )));
+    }
+    let changes = NewTodo {
+        deleted_at: Set(None),
+        updated_at: Set(Utc::now().naive_utc().timestamp()),
+        version: Set(todo.version + 1),
+        ..todo.clone().into()
+    };
+    let restored = save_restored_todo(&todo, changes, db).await?;
+    record_history(HistoryAction::Restored, Some(&todo), &restored, actor, db).await?;
+    Ok(restored)
+}
+
+/// Returns the old value of the field in the changes, or the current value if the field is not changed
+fn old_value<T: DeserializeOwned>(
+    changes: &Map<String, Value>,
+    field: &str,
+    current: T,
+) -> ApiResult<T> {
+    match changes.get(field) {
+        Some(change) => {
+            serde_json::from_value(change["old"].clone()).server_err("The todo history is invalid")
+        }
+        None => Ok(current),
+    }
+}
+
+/// Revert a change of a todo, the changed fields are set back to their old values.
+/// Reverting the creation of a todo moves it to the trash.
+/// If the reverted todo conflicts with the other todos, returns an error 409
+pub async fn revert_todo(
+    todo: TodoModel,
+    change: &HistoryModel,
+    actor: &Actor,
+    db: &impl ConnectionTrait,
+) -> ApiResult<TodoModel> {
+    if change.action == HistoryAction::Created {
+        return trash_todo(todo, actor, db).await;
+    }
+    let changes: Map<String, Value> =
+        serde_json::from_str(&change.changes).server_err("The todo history is invalid")?;
+    let title = old_value(&changes, "title", todo.title.clone())?;
+    let deleted_at = old_value(&changes, "deleted_at", todo.deleted_at)?;
+
+    if deleted_at.is_none()
+        && (todo.deleted_at.is_some() || title != todo.title)
+        && is_todo_title_exists(&title, todo.user_id, db).await?
+    {
+        return Err(ApiError::Conflict(format!(
+            "Can't undo, the todo `{title}` is already exists"
+        )));
+    }
+    let action = match (todo.deleted_at, deleted_at) {
+        (None, Some(_)) => HistoryAction::Deleted,
+        (Some(_), None) => HistoryAction::Restored,
+        _ => HistoryAction::Updated,
+    };
+    let changes = NewTodo {
+        title: Set(title.clone()),
+        status: Set(old_value(&changes, "status", todo.status.clone())?),
+        completed_at: Set(old_value(&changes, "completed_at", todo.completed_at)?),
+        position: Set(old_value(&changes, "position", todo.position.clone())?),
+        deleted_at: Set(deleted_at),
+        updated_at: Set(Utc::now().naive_utc().timestamp()),
+        version: Set(todo.version + 1),
+        ..todo.clone().into()
+    };
+    let reverted = if action == HistoryAction::Restored {
+        save_restored_todo(&todo, changes, db).await
+    } else {
+        save_todo(&todo, changes, db).await
+    }
+    // The conflicts with the other todos are reported as undo conflicts
+    .map_err(|err| match err {
+        ApiError::BadRequest(message) => ApiError::Conflict(format!("Can't undo, {message}")),
+        err => err,
+    })?;
+    record_history(action, Some(&todo), &reverted, actor, db).await?;
+    Ok(reverted)
+}
+
+/// Permanently delete the todos that moved to the trash before the given time (Unix timestamp),
+/// returns the number of the purged todos
+pub async fn purge_trash(before: i64, db: &impl ConnectionTrait) -> ApiResult<u64> {
+    TodoEntity::delete_many()
+        .filter(TodoColumn::DeletedAt.lt(before))
+        .exec(db)
+        .await
+        .database_err()
+        .map(|res| res.rows_affected)
+}
+
+/// Check the todo version against the given version, if it doesn't match returns an error 412 with the current todo.
+/// The GraphQL and gRPC APIs map the error to their own precondition errors
+pub fn check_todo_version(todo: &TodoModel, version: Option<u32>) -> ApiResult<()> {
+    match version {
+        Some(version) if version != todo.version => {
+            Err(ApiError::PreconditionFailed(Box::new(todo.clone().into())))
+        }
+        _ => Ok(()),
+    }
+}
+
+/// Check the todo version against the `If-Match` header and the given version,
+/// if one of them doesn't match returns an error 412 with the current todo
+pub fn check_version(req: &HttpRequest, todo: &TodoModel, version: Option<u32>) -> ApiResult<()> {
+    let current = TodoSchema::from(todo.clone());
+    if !conditional::if_match(req, &current.etag()) {
+        return Err(ApiError::PreconditionFailed(Box::new(current)));
+    }
+    check_todo_version(todo, version)
+}
+
+/// Returns the completion time of the todo after changing its status to the given status.
+/// The completion time is kept if the todo is still completed, and removed if it's not
+pub fn completed_at(todo: &TodoModel, status: &TodoStatus, current_time: i64) -> Option<i64> {
+    match (&todo.status, status) {
+        (TodoStatus::Completed, TodoStatus::Completed) => todo.completed_at,
+        (_, TodoStatus::Completed) => Some(current_time),
+        _ => None,
+    }
+}
+
 /// Update a todo, if the title is empty or the todo with the same title already exists, returns an error 400
 pub async fn update_todo(
     todo: TodoModel,
     title: Option<String>,
     status: Option<TodoStatus>,
-    db: &DatabaseConnection,
+    actor: &Actor,
+    db: &impl ConnectionTrait,
 ) -> ApiResult<TodoModel> {
     if let Some(title) = &title {
         if title.is_empty() {
@@ -123,23 +564,214 @@ pub async fn update_todo(
             )));
         }
     }
-    NewTodo {
-        updated_at: Set(Utc::now().naive_utc().timestamp()),
-        title: Set(title.unwrap_or_else(|| todo.title.clone())),
-        status: Set(status.unwrap_or_else(|| todo.status.clone())),
-        ..todo.into()
+    let current_time = Utc::now().naive_utc().timestamp();
+    let status = status.unwrap_or_else(|| todo.status.clone());
+    let title = title.unwrap_or_else(|| todo.title.clone());
+    let changes = NewTodo {
+        updated_at: Set(current_time),
+        title: Set(title),
+        completed_at: Set(completed_at(&todo, &status, current_time)),
+        status: Set(status),
+        version: Set(todo.version + 1),
+        ..todo.clone().into()
+    };
+    let updated = save_todo(&todo, changes, db).await?;
+    record_history(HistoryAction::Updated, Some(&todo), &updated, actor, db).await?;
+    Ok(updated)
+}
+
+/// Returns the position after the last todo of the user, the todos in the trash are included
+/// so the restored todos keep their positions
+async fn next_position(user_id: u32, db: &impl ConnectionTrait) -> ApiResult<String> {
+    let last = TodoEntity::find()
+        .filter(TodoColumn::UserId.eq(user_id))
+        .order_by_desc(TodoColumn::Position)
+        .one(db)
+        .await
+        .database_err()?;
+    rank::rank_between(last.as_ref().map(|todo| todo.position.as_str()), None)
+        .server_err("Failed to compute the todo position")
+}
+
+/// Returns the query of the user todos that are ordered before (`after` is `false`)
+/// or after (`after` is `true`) the given todo, the todos with the same position are ordered by id
+fn ordered_around(todo: &TodoModel, after: bool) -> Select<TodoEntity> {
+    let (position, id) = if after {
+        (
+            TodoColumn::Position.gt(todo.position.as_str()),
+            TodoColumn::Id.gt(todo.id),
+        )
+    } else {
+        (
+            TodoColumn::Position.lt(todo.position.as_str()),
+            TodoColumn::Id.lt(todo.id),
+        )
+    };
+    user_todos(todo.user_id).filter(
+        Condition::any().add(position).add(
+            Condition::all()
+                .add(TodoColumn::Position.eq(todo.position.as_str()))
+                .add(id),
+        ),
+    )
+}
+
+/// Returns the positions of the todos that the todo will be moved between, `None` is the start or the end of the list.
+///
+/// `after` is the todo that will be right before the moved todo, and `before` is the todo that will be right after it
+async fn move_neighbors(
+    todo: &TodoModel,
+    after: Option<Uuid>,
+    before: Option<Uuid>,
+    db: &impl ConnectionTrait,
+) -> ApiResult<(Option<String>, Option<String>)> {
+    let neighbor = |uuid: Uuid| async move {
+        user_todos(todo.user_id)
+            .filter(TodoColumn::Uuid.eq(uuid))
+            .one(db)
+            .await
+            .database_err()?
+            .not_found_err("There is no todo with the given `before` or `after` uuid")
+    };
+    let adjacent = |neighbor: TodoModel, after: bool| async move {
+        let query = ordered_around(&neighbor, after).filter(TodoColumn::Id.ne(todo.id));
+        if after {
+            query
+                .order_by_asc(TodoColumn::Position)
+                .order_by_asc(TodoColumn::Id)
+        } else {
+            query
+                .order_by_desc(TodoColumn::Position)
+                .order_by_desc(TodoColumn::Id)
+        }
+        .one(db)
+        .await
+        .database_err()
+    };
+
+    if after == Some(todo.uuid) || before == Some(todo.uuid) {
+        return Err(ApiError::BadRequest(
+            "The todo can't be moved before or after itself".to_owned(),
+        ));
+    }
+    match (after, before) {
+        (None, None) => Err(ApiError::BadRequest(
+            "The todo should be moved `before` or `after` another todo".to_owned(),
+        )),
+        (Some(after), Some(before)) => {
+            let (low, high) = (neighbor(after).await?, neighbor(before).await?);
+            if (&low.position, low.id) >= (&high.position, high.id) {
+                return Err(ApiError::BadRequest(
+                    "The `after` todo should be ordered before the `before` todo".to_owned(),
+                ));
+            }
+            Ok((Some(low.position), Some(high.position)))
+        }
+        (Some(after), None) => {
+            let low = neighbor(after).await?;
+            let high = adjacent(low.clone(), true).await?;
+            Ok((Some(low.position), high.map(|todo| todo.position)))
+        }
+        (None, Some(before)) => {
+            let high = neighbor(before).await?;
+            let low = adjacent(high.clone(), false).await?;
+            Ok((low.map(|todo| todo.position), Some(high.position)))
+        }
     }
-    .update(db)
-    .await
-    .database_err()
 }
 
-/// Createing a new todo, if the title is empty or the todo with the same title already exists, returns an error 400
+/// Give the todos of the user evenly spaced positions, keeping their order. The moved todo is skipped,
+/// it gets a new position after the rebalance so its version is still checked when it's saved.
+/// The todos in the trash are skipped too, they aren't changed until they are restored.
+/// The position changes are recorded as updates, so the clients that follow the changes get the new positions
+async fn rebalance_positions(
+    moved: &TodoModel,
+    actor: &Actor,
+    db: &impl ConnectionTrait,
+) -> ApiResult<()> {
+    let todos = user_todos(moved.user_id)
+        .filter(TodoColumn::Id.ne(moved.id))
+        .order_by_asc(TodoColumn::Position)
+        .order_by_asc(TodoColumn::Id)
+        .all(db)
+        .await
+        .database_err()?;
+    let current_time = Utc::now().naive_utc().timestamp();
+    let positions = rank::spread_ranks(todos.len());
+    for (todo, position) in todos.into_iter().zip(positions) {
+        if todo.position == position {
+            continue;
+        }
+        let changes = NewTodo {
+            position: Set(position),
+            updated_at: Set(current_time),
+            version: Set(todo.version + 1),
+            ..todo.clone().into()
+        };
+        let rebalanced = save_todo(&todo, changes, db).await?;
+        record_history(HistoryAction::Updated, Some(&todo), &rebalanced, actor, db).await?;
+    }
+    Ok(())
+}
+
+/// Move a todo between two todos, only the position of the moved todo is changed.
+/// If the new position is too long, the positions of the user todos are rebalanced first.
+///
+/// `after` is the todo that will be right before the moved todo, and `before` is the todo that will be right after it
+pub async fn move_todo(
+    todo: TodoModel,
+    after: Option<Uuid>,
+    before: Option<Uuid>,
+    actor: &Actor,
+    db: &impl ConnectionTrait,
+) -> ApiResult<TodoModel> {
+    let (low, high) = move_neighbors(&todo, after, before, db).await?;
+    let mut position = rank::rank_between(low.as_deref(), high.as_deref());
+    if position
+        .as_ref()
+        .map_or(true, |position| position.len() > rank::MAX_RANK_LENGTH)
+    {
+        rebalance_positions(&todo, actor, db).await?;
+        let (low, high) = move_neighbors(&todo, after, before, db).await?;
+        position = rank::rank_between(low.as_deref(), high.as_deref());
+    }
+
+    let changes = NewTodo {
+        position: Set(position.server_err("Failed to compute the todo position")?),
+        updated_at: Set(Utc::now().naive_utc().timestamp()),
+        version: Set(todo.version + 1),
+        ..todo.clone().into()
+    };
+    let moved = save_todo(&todo, changes, db).await?;
+    record_history(HistoryAction::Updated, Some(&todo), &moved, actor, db).await?;
+    Ok(moved)
+}
+
+/// Createing a new todo, if the title is empty or the todo with the same title already exists, returns an error 400.
+///
+/// The title uniqueness and the maximum number of todos are enforced by the database too,
+/// so the concurrent requests can't create duplicate titles or exceed the maximum
 pub async fn create_todo(
-    db: &DatabaseConnection,
+    db: &impl ConnectionTrait,
     todo_content: TodoContentSchema,
-    user_id: u32,
+    actor: &Actor,
+) -> ApiResult<TodoSchema> {
+    create_todo_at(db, None, todo_content, None, None, actor).await
+}
+
+/// Create a new todo like `create_todo`, with the given uuid and creation and completion times (Unix timestamps),
+/// used to import the todos. A unique uuid is generated if it's not given, and the current time is used for
+/// the times that are not given. If the given uuid is already used returns an error 409.
+/// Note: The completion time is ignored if the todo is not completed
+pub async fn create_todo_at(
+    db: &impl ConnectionTrait,
+    uuid: Option<Uuid>,
+    todo_content: TodoContentSchema,
+    created_at: Option<i64>,
+    completed_at: Option<i64>,
+    actor: &Actor,
 ) -> ApiResult<TodoSchema> {
+    let user_id = actor.user_id;
     if todo_content.title.is_empty() {
         return Err(ApiError::BadRequest("The todo title is empty".to_string()));
     } else if is_todo_title_exists(&todo_content.title, user_id, db).await? {
@@ -152,33 +784,76 @@ pub async fn create_todo(
             "The todo title length must be less than {}",
             max_todo_title_length()
         )));
-    } else if TodoEntity::find()
-        .filter(TodoColumn::UserId.eq(user_id))
-        .count(db)
-        .await
-        .database_err()?
-        > max_todos_count()
-    {
-        return Err(ApiError::BadRequest(format!(
-            "The maximum number of todos is {}",
-            max_todos_count()
-        )));
     }
 
     let current_time = Utc::now().naive_utc().timestamp();
-    let uuid = unique_uuid(TodoEntity::find(), TodoColumn::Uuid, db).await?;
+    let uuid = match uuid {
+        Some(uuid) => {
+            let used = TodoEntity::find()
+                .filter(TodoColumn::Uuid.eq(uuid))
+                .one(db)
+                .await
+                .database_err()?;
+            if used.is_some() {
+                return Err(ApiError::Conflict(
+                    "There is already a todo with the given uuid".to_owned(),
+                ));
+            }
+            uuid
+        }
+        None => unique_uuid(TodoEntity::find(), TodoColumn::Uuid, db).await?,
+    };
+    let created_at = created_at.unwrap_or(current_time);
+    let completed_at = (todo_content.status == TodoStatus::Completed)
+        .then(|| completed_at.unwrap_or(current_time));
+    let updated_at = completed_at.unwrap_or(created_at).max(created_at);
+    let position = next_position(user_id, db).await?;
 
-    NewTodo {
-        uuid: Set(uuid),
-        title: Set(todo_content.title.clone()),
-        status: Set(todo_content.status),
-        created_at: Set(current_time),
-        updated_at: Set(current_time),
-        user_id: Set(user_id),
-        ..Default::default()
+    // Insert the todo only if the maximum number of todos is not reached, in one statement
+    let insert = Query::insert()
+        .into_table(TodoEntity)
+        .columns([
+            TodoColumn::Uuid,
+            TodoColumn::UserId,
+            TodoColumn::Title,
+            TodoColumn::Status,
+            TodoColumn::CreatedAt,
+            TodoColumn::UpdatedAt,
+            TodoColumn::CompletedAt,
+            TodoColumn::Version,
+            TodoColumn::Position,
+        ])
+        .select_from(
+            Query::select()
+                .exprs([
+                    Expr::val(uuid),
+                    Expr::val(user_id),
+                    Expr::val(todo_content.title.as_str()),
+                    Expr::val(todo_content.status.as_str()),
+                    Expr::val(created_at),
+                    Expr::val(updated_at),
+                    Expr::val(completed_at),
+                    Expr::val(1u32),
+                    Expr::val(position.as_str()),
+                ])
+                .and_where(below_todos_quota(user_id))
+                .to_owned(),
+        )
+        .server_err("Failed to build the todo insert statement")?
+        .to_owned();
+    let res = db
+        .execute(db.get_database_backend().build(&insert))
+        .await
+        .map_err(|err| save_todo_err(err, &todo_content.title))?;
+    if res.rows_affected() == 0 {
+        return Err(max_todos_err());
     }
-    .save(db)
-    .await
-    .map(From::from)
-    .database_err()
+    let todo = TodoEntity::find()
+        .filter(TodoColumn::Uuid.eq(uuid))
+        .one(db)
+        .await
+        .database_err()?
+        .server_err("The created todo is not found")?;
+    record_history(HistoryAction::Created, None, &todo, actor, db).await?;
+    Ok(todo.into())
 }
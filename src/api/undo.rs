@@ -0,0 +1,159 @@
+use actix_web::{post, web, HttpRequest};
+use chrono::Utc;
+use entity::todo::{Column as TodoColumn, Entity as TodoEntity};
+use entity::todo_history::{
+    ActiveModel as HistoryActiveModel, Column as HistoryColumn, Entity as HistoryEntity,
+    Model as HistoryModel,
+};
+use sea_orm::{
+    ActiveModelTrait, ColumnTrait, ConnectionTrait, DatabaseConnection, EntityTrait,
+    PaginatorTrait, QueryFilter, QueryOrder, Set, TransactionTrait,
+};
+
+use crate::{
+    api::auth::utils::req_auth,
+    api::todo::{queries::UndoQuery, utils},
+    errors::{Error as ApiError, ErrorTrait, Result as ApiResult},
+    schemas::{
+        message::MessageSchema,
+        todo::TodoSchema,
+        traits::OpenApiExample,
+        undo::{UndoOperationSchema, UndoResultSchema},
+    },
+};
+
+/// Returns the last operations of the user that can be undone with their changes, the latest first.
+/// An operation is the changes made by one request
+async fn undo_stack(
+    user_id: u32,
+    steps: u64,
+    db: &impl ConnectionTrait,
+) -> ApiResult<Vec<(String, Vec<HistoryModel>)>> {
+    let since = Utc::now().naive_utc().timestamp() - utils::undo_window_minutes() as i64 * 60;
+    let changes = HistoryEntity::find()
+        .filter(HistoryColumn::ActorId.eq(user_id))
+        .filter(HistoryColumn::UndoneAt.is_null())
+        .filter(HistoryColumn::UndoOf.is_null())
+        .filter(HistoryColumn::CreatedAt.gte(since))
+        .order_by_desc(HistoryColumn::Id)
+        .all(db)
+        .await
+        .database_err()?;
+
+    let mut operations: Vec<(String, Vec<HistoryModel>)> = Vec::new();
+    for change in changes {
+        if let Some((_, changes)) = operations
+            .iter_mut()
+            .find(|(request_id, _)| request_id == &change.request_id)
+        {
+            changes.push(change);
+        } else if operations.len() < steps as usize {
+            operations.push((change.request_id.clone(), vec![change]));
+        }
+    }
+    Ok(operations)
+}
+
+/// Undo the changes of one operation, the latest change first.
+/// If a todo is changed after the operation, returns an error 409
+async fn undo_operation(
+    request_id: String,
+    changes: Vec<HistoryModel>,
+    actor: &utils::Actor,
+    db: &impl ConnectionTrait,
+) -> ApiResult<UndoOperationSchema> {
+    let mut todos: Vec<TodoSchema> = Vec::new();
+    for change in changes {
+        // The undone changes and the changes made by undo are not conflicts
+        let later_changes = HistoryEntity::find()
+            .filter(HistoryColumn::TodoUuid.eq(change.todo_uuid))
+            .filter(HistoryColumn::Id.gt(change.id))
+            .filter(HistoryColumn::UndoneAt.is_null())
+            .filter(HistoryColumn::UndoOf.is_null())
+            .count(db)
+            .await
+            .database_err()?;
+        let todo = TodoEntity::find()
+            .filter(TodoColumn::Uuid.eq(change.todo_uuid))
+            .filter(TodoColumn::UserId.eq(change.user_id))
+            .one(db)
+            .await
+            .database_err()?;
+        let (Some(todo), 0) = (todo, later_changes) else {
+            return Err(ApiError::Conflict(format!(
+                "Can't undo, the todo `{}` has been changed after the operation",
+                change.todo_uuid
+            )));
+        };
+
+        let todo = TodoSchema::from(utils::revert_todo(todo, &change, actor, db).await?);
+        HistoryActiveModel {
+            undone_at: Set(Some(Utc::now().naive_utc().timestamp())),
+            ..change.into()
+        }
+        .update(db)
+        .await
+        .database_err()?;
+        // Keep the last state of each todo
+        todos.retain(|t| t.uuid != todo.uuid);
+        todos.push(todo);
+    }
+    Ok(UndoOperationSchema::new(request_id, todos))
+}
+
+/// Undo the last operations of the user, like creating, updating, deleting or restoring todos.
+///
+/// An operation is all the changes made by one request (e.g. a bulk operation), the requests with the same
+/// `X-Request-Id` header are one operation. The operations can be undone for `UNDO_WINDOW_MINUTES` minutes (default: 60).
+///
+/// The undo is applied in a single transaction, if a todo is changed after the undone operation
+/// nothing is undone and returns `409 Conflict`.
+#[utoipa::path(
+    context_path = "/api",
+    params(UndoQuery),
+    responses(
+        (
+            status = 200, description = "The undone operations", body = UndoResultSchema,
+            example = json!(UndoResultSchema::openapi_example())
+        ),
+        (
+            status = 400, description = "The steps is invalid", body = MessageSchema,
+            example = json!(MessageSchema::new(400, "The steps must be between 1 and 50"))
+        ),
+        (
+            status = 404, description = "There is nothing to undo", body = MessageSchema,
+            example = json!(MessageSchema::new(404, "There is nothing to undo"))
+        ),
+        (
+            status = 409, description = "A todo has been changed after the operation", body = MessageSchema,
+            example = json!(MessageSchema::new(409, "Can't undo, the todo `{uuid}` has been changed after the operation"))
+        ),
+    ),
+    tag = "Todo",
+    security(("Bearer Token" = []))
+)]
+#[post("/undo")]
+pub async fn undo(
+    req: HttpRequest,
+    db: web::Data<DatabaseConnection>,
+    params: web::Query<UndoQuery>,
+) -> ApiResult<UndoResultSchema> {
+    let db = db.get_ref();
+    let user = req_auth(req.clone(), db).await?;
+    let steps = params.steps()?;
+    let actor = utils::Actor::new(user.id, &req);
+
+    let txn = db.begin().await.database_err()?;
+    let operations = undo_stack(user.id, steps, &txn).await?;
+    if operations.is_empty() {
+        return Err(ApiError::NotFound("There is nothing to undo".to_owned()));
+    }
+    let mut undone = Vec::new();
+    for (request_id, changes) in operations {
+        let actor = actor.undoing(&request_id);
+        // The transaction is rolled back when it's dropped
+        undone.push(undo_operation(request_id, changes, &actor, &txn).await?);
+    }
+    txn.commit().await.database_err()?;
+    Ok(UndoResultSchema { operations: undone })
+}
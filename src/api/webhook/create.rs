@@ -0,0 +1,104 @@
+use actix_web::{post, web, HttpRequest};
+use chrono::Utc;
+use entity::webhook::{
+    ActiveModel as NewWebhook, Column as WebhookColumn, Entity as WebhookEntity,
+};
+use sea_orm::{
+    ActiveModelTrait, ColumnTrait, DatabaseConnection, EntityTrait, PaginatorTrait, QueryFilter,
+    Set,
+};
+
+use crate::{
+    api::{auth::utils::req_auth, todo::utils::unique_uuid, webhook::utils},
+    errors::{Error as ApiError, ErrorTrait, Result as ApiResult},
+    schemas::{
+        message::MessageSchema,
+        todo::TodoEventType,
+        traits::OpenApiExample,
+        webhook::{CreateWebhookSchema, WebhookSchema},
+    },
+};
+
+/// Create a new webhook, the todo events are posted to its URL.
+///
+/// The response contains the webhook `secret`, it's returned only once. Each payload is signed with it,
+/// check the `X-Oxide-Signature` header to verify that the payload is sent by the server.
+#[utoipa::path(
+    context_path = "/api/webhooks",
+    request_body = CreateWebhookSchema,
+    responses(
+        (
+            status = 200, description = "The created webhook, with its secret", body = WebhookSchema,
+            example = json!(WebhookSchema::openapi_example())
+        ),
+        (
+            status = 400, description = "The URL is invalid", body = MessageSchema,
+            example = json!(MessageSchema::new(400, "The webhook URL should be an absolute `http` or `https` URL"))
+        ),
+        (
+            status = 400, description = "The URL host resolves to a non-public address", body = MessageSchema,
+            example = json!(MessageSchema::new(400, "The webhook URL is not allowed: The host `127.0.0.1` resolves to a non-public address"))
+        ),
+        (
+            status = 400, description = "The events are empty", body = MessageSchema,
+            example = json!(MessageSchema::new(400, "The webhook should have at least one event"))
+        ),
+        (
+            status = 400, description = "The maximum number of webhooks is reached", body = MessageSchema,
+            example = json!(MessageSchema::new(400, "The maximum number of webhooks is 10"))
+        ),
+    ),
+    tag = "Webhook",
+    security(("Bearer Token" = []))
+)]
+#[post("")]
+pub async fn create(
+    req: HttpRequest,
+    db: web::Data<DatabaseConnection>,
+    payload: web::Json<CreateWebhookSchema>,
+) -> ApiResult<WebhookSchema> {
+    let db = db.get_ref();
+    let user = req_auth(req, db).await?;
+    let payload = payload.into_inner();
+
+    utils::check_url(&payload.url).await?;
+    let events = payload
+        .events
+        .unwrap_or_else(|| TodoEventType::ALL.to_vec());
+    if events.is_empty() {
+        return Err(ApiError::BadRequest(
+            "The webhook should have at least one event".to_owned(),
+        ));
+    }
+    let webhooks_count = WebhookEntity::find()
+        .filter(WebhookColumn::UserId.eq(user.id))
+        .count(db)
+        .await
+        .database_err()?;
+    if webhooks_count >= utils::MAX_WEBHOOKS_PER_USER {
+        return Err(ApiError::BadRequest(format!(
+            "The maximum number of webhooks is {}",
+            utils::MAX_WEBHOOKS_PER_USER
+        )));
+    }
+
+    let webhook = NewWebhook {
+        uuid: Set(unique_uuid(WebhookEntity::find(), WebhookColumn::Uuid, db).await?),
+        user_id: Set(user.id),
+        url: Set(payload.url),
+        secret: Set(utils::new_secret()),
+        events: Set(TodoEventType::ALL
+            .iter()
+            .filter(|event| events.contains(event))
+            .map(TodoEventType::as_str)
+            .collect::<Vec<_>>()
+            .join(",")),
+        created_at: Set(Utc::now().naive_utc().timestamp()),
+        ..Default::default()
+    }
+    .insert(db)
+    .await
+    .database_err()?;
+    log::info!("Created the webhook `{}` for `{}`", webhook.uuid, user.name);
+    Ok(WebhookSchema::new(webhook, true))
+}
@@ -0,0 +1,55 @@
+use actix_web::{delete, web, HttpRequest};
+use entity::webhook_delivery::{Column as DeliveryColumn, Entity as DeliveryEntity};
+use sea_orm::{
+    ColumnTrait, DatabaseConnection, EntityTrait, ModelTrait, QueryFilter, TransactionTrait,
+};
+use uuid::Uuid;
+
+use crate::{
+    api::{auth::utils::req_auth, webhook::utils},
+    errors::{ErrorTrait, Result as ApiResult},
+    schemas::{message::MessageSchema, traits::OpenApiExample, webhook::WebhookSchema},
+};
+
+/// Delete a webhook by uuid, with its deliveries. The pending deliveries will not be sent.
+#[utoipa::path(
+    context_path = "/api/webhooks",
+    params(
+        (
+            "uuid", description = "The uuid of the webhook",
+            example = "3f4b1b5e-5d0a-4e0b-9c8e-2e6c7f0f9a1d"
+        )
+    ),
+    responses(
+        (
+            status = 200, description = "The deleted webhook", body = WebhookSchema,
+            example = json!(WebhookSchema::openapi_example())
+        ),
+        (
+            status = 404, description = "There is no webhook with the given uuid", body = MessageSchema,
+            example = json!(MessageSchema::new(404, "There is no webhook with the given uuid"))
+        ),
+    ),
+    tag = "Webhook",
+    security(("Bearer Token" = []))
+)]
+#[delete("/{uuid}")]
+pub async fn delete(
+    req: HttpRequest,
+    db: web::Data<DatabaseConnection>,
+    uuid: web::Path<Uuid>,
+) -> ApiResult<WebhookSchema> {
+    let db = db.get_ref();
+    let user = req_auth(req, db).await?;
+    let webhook = utils::find_webhook_by_uuid(uuid.into_inner(), user.id, db).await?;
+
+    let txn = db.begin().await.database_err()?;
+    DeliveryEntity::delete_many()
+        .filter(DeliveryColumn::WebhookId.eq(webhook.id))
+        .exec(&txn)
+        .await
+        .database_err()?;
+    webhook.clone().delete(&txn).await.database_err()?;
+    txn.commit().await.database_err()?;
+    Ok(WebhookSchema::new(webhook, false))
+}
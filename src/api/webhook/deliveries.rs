@@ -0,0 +1,126 @@
+use actix_web::{get, post, web, HttpRequest};
+use entity::webhook_delivery::{
+    Column as DeliveryColumn, Entity as DeliveryEntity, Status as DeliveryStatus,
+};
+use sea_orm::{ColumnTrait, DatabaseConnection, EntityTrait, QueryFilter, QueryOrder, QuerySelect};
+use serde::{Deserialize, Serialize};
+use utoipa::IntoParams;
+use uuid::Uuid;
+
+use crate::{
+    api::{auth::utils::req_auth, webhook::utils},
+    errors::{ErrorTrait, Result as ApiResult},
+    schemas::{
+        message::MessageSchema,
+        traits::OpenApiExample,
+        webhook::{WebhookDeliveryListSchema, WebhookDeliverySchema},
+    },
+};
+
+/// The maximum number of the returned deliveries
+const RECENT_DELIVERIES: u64 = 50;
+
+/// The status filter of the deliveries
+#[derive(IntoParams, Deserialize, Serialize, Debug, Clone, Default)]
+pub struct DeliveriesQuery {
+    /// Filter the deliveries by status (`pending`, `succeeded` or `failed`), use `failed` to get the dead-letter list
+    #[param(value_type = Option<String>, example = "failed")]
+    pub status: Option<DeliveryStatus>,
+}
+
+/// Get the recent deliveries of a webhook, the newest first (up to 50 deliveries).
+#[utoipa::path(
+    context_path = "/api/webhooks",
+    params(
+        (
+            "uuid", description = "The uuid of the webhook",
+            example = "3f4b1b5e-5d0a-4e0b-9c8e-2e6c7f0f9a1d"
+        ),
+        DeliveriesQuery
+    ),
+    responses(
+        (
+            status = 200, description = "The recent deliveries of the webhook", body = WebhookDeliveryListSchema,
+            example = json!(WebhookDeliveryListSchema::openapi_example())
+        ),
+        (
+            status = 404, description = "There is no webhook with the given uuid", body = MessageSchema,
+            example = json!(MessageSchema::new(404, "There is no webhook with the given uuid"))
+        ),
+    ),
+    tag = "Webhook",
+    security(("Bearer Token" = []))
+)]
+#[get("/{uuid}/deliveries")]
+pub async fn deliveries(
+    req: HttpRequest,
+    db: web::Data<DatabaseConnection>,
+    uuid: web::Path<Uuid>,
+    query: web::Query<DeliveriesQuery>,
+) -> ApiResult<WebhookDeliveryListSchema> {
+    let db = db.get_ref();
+    let user = req_auth(req, db).await?;
+    let webhook = utils::find_webhook_by_uuid(uuid.into_inner(), user.id, db).await?;
+
+    let mut select = DeliveryEntity::find().filter(DeliveryColumn::WebhookId.eq(webhook.id));
+    if let Some(status) = query.status {
+        select = select.filter(DeliveryColumn::Status.eq(status));
+    }
+    Ok(WebhookDeliveryListSchema {
+        data: select
+            .order_by_desc(DeliveryColumn::Id)
+            .limit(RECENT_DELIVERIES)
+            .all(db)
+            .await
+            .database_err()?
+            .into_iter()
+            .map(WebhookDeliverySchema::from)
+            .collect(),
+    })
+}
+
+/// Redeliver a delivery of a webhook, it's attempted again as soon as possible with a fresh attempts counter.
+///
+/// Use it to retry the deliveries in the dead-letter list (the `failed` deliveries), or to resend a delivered event.
+#[utoipa::path(
+    context_path = "/api/webhooks",
+    params(
+        (
+            "uuid", description = "The uuid of the webhook",
+            example = "3f4b1b5e-5d0a-4e0b-9c8e-2e6c7f0f9a1d"
+        ),
+        (
+            "delivery_uuid", description = "The uuid of the delivery",
+            example = "9b2f4c3e-1a7d-4f5b-8e6c-0d9a8b7c6e5f"
+        )
+    ),
+    responses(
+        (
+            status = 200, description = "The delivery, pending to be attempted", body = WebhookDeliverySchema,
+            example = json!(WebhookDeliverySchema::openapi_example())
+        ),
+        (
+            status = 404, description = "There is no webhook with the given uuid", body = MessageSchema,
+            example = json!(MessageSchema::new(404, "There is no webhook with the given uuid"))
+        ),
+        (
+            status = 404, description = "There is no delivery with the given uuid", body = MessageSchema,
+            example = json!(MessageSchema::new(404, "There is no delivery with the given uuid"))
+        ),
+    ),
+    tag = "Webhook",
+    security(("Bearer Token" = []))
+)]
+#[post("/{uuid}/deliveries/{delivery_uuid}/redeliver")]
+pub async fn redeliver(
+    req: HttpRequest,
+    db: web::Data<DatabaseConnection>,
+    path: web::Path<(Uuid, Uuid)>,
+) -> ApiResult<WebhookDeliverySchema> {
+    let db = db.get_ref();
+    let user = req_auth(req, db).await?;
+    let (uuid, delivery_uuid) = path.into_inner();
+    let webhook = utils::find_webhook_by_uuid(uuid, user.id, db).await?;
+    let delivery = utils::find_delivery_by_uuid(delivery_uuid, &webhook, db).await?;
+    Ok(utils::redeliver(delivery, db).await?.into())
+}
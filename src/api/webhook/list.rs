@@ -0,0 +1,45 @@
+use actix_web::{get, web, HttpRequest};
+use entity::webhook::{Column as WebhookColumn, Entity as WebhookEntity};
+use sea_orm::{ColumnTrait, DatabaseConnection, EntityTrait, QueryFilter, QueryOrder};
+
+use crate::{
+    api::auth::utils::req_auth,
+    errors::{ErrorTrait, Result as ApiResult},
+    schemas::{
+        traits::OpenApiExample,
+        webhook::{WebhookListSchema, WebhookSchema},
+    },
+};
+
+/// List the webhooks of the user, the oldest first. The secrets are not returned.
+#[utoipa::path(
+    context_path = "/api/webhooks",
+    responses(
+        (
+            status = 200, description = "The webhooks of the user", body = WebhookListSchema,
+            example = json!(WebhookListSchema::openapi_example())
+        ),
+    ),
+    tag = "Webhook",
+    security(("Bearer Token" = []))
+)]
+#[get("")]
+pub async fn list(
+    req: HttpRequest,
+    db: web::Data<DatabaseConnection>,
+) -> ApiResult<WebhookListSchema> {
+    let db = db.get_ref();
+    let user = req_auth(req, db).await?;
+
+    Ok(WebhookListSchema {
+        data: WebhookEntity::find()
+            .filter(WebhookColumn::UserId.eq(user.id))
+            .order_by_asc(WebhookColumn::Id)
+            .all(db)
+            .await
+            .database_err()?
+            .into_iter()
+            .map(|webhook| WebhookSchema::new(webhook, false))
+            .collect(),
+    })
+}
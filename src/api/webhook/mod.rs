@@ -0,0 +1,18 @@
+use actix_web::web;
+
+pub mod create;
+pub mod delete;
+pub mod deliveries;
+pub mod list;
+pub mod utils;
+
+pub fn init_routes(cfg: &mut web::ServiceConfig) {
+    cfg.service(
+        web::scope("/webhooks")
+            .service(create::create)
+            .service(list::list)
+            .service(deliveries::deliveries)
+            .service(deliveries::redeliver)
+            .service(delete::delete),
+    );
+}
@@ -0,0 +1,383 @@
+use std::{
+    env,
+    net::{IpAddr, SocketAddr, ToSocketAddrs},
+    time::Duration,
+};
+
+use actix_tls::connect::{Connector as TcpConnector, Resolve, Resolver};
+use actix_web::{http::Uri, rt::task::spawn_blocking};
+use chrono::Utc;
+use entity::todo_history::Model as HistoryModel;
+use entity::webhook::{Column as WebhookColumn, Entity as WebhookEntity, Model as WebhookModel};
+use entity::webhook_delivery::{
+    ActiveModel as NewDelivery, Column as DeliveryColumn, Entity as DeliveryEntity,
+    Model as DeliveryModel, Status as DeliveryStatus,
+};
+use futures_util::{future::LocalBoxFuture, stream, StreamExt};
+use hmac::{Hmac, Mac};
+use sea_orm::{
+    ActiveModelTrait, ColumnTrait, ConnectionTrait, DatabaseConnection, EntityTrait, QueryFilter,
+    QueryOrder, QuerySelect, Set,
+};
+use sha2::Sha256;
+use uuid::Uuid;
+
+use crate::{
+    api::todo::utils::unique_uuid,
+    errors::{Error as ApiError, ErrorTrait, Result as ApiResult},
+    schemas::todo::{TodoEventSchema, TodoEventType},
+};
+
+/// The maximum number of webhooks per user
+pub const MAX_WEBHOOKS_PER_USER: u64 = 10;
+/// The maximum length of the webhook URL
+const MAX_URL_LENGTH: usize = 2048;
+/// The maximum time to wait for the webhook response
+const DELIVERY_TIMEOUT: Duration = Duration::from_secs(10);
+/// The delay before the first retry in seconds, it's doubled after each failed attempt
+const RETRY_BASE_DELAY: i64 = 30;
+/// The maximum number of deliveries attempted in one run
+const DELIVERIES_BATCH_SIZE: u64 = 50;
+/// The maximum number of deliveries attempted at the same time
+pub const DELIVERIES_CONCURRENCY: usize = 10;
+/// The header of the payload signature
+pub const SIGNATURE_HEADER: &str = "X-Oxide-Signature";
+/// The header of the event type
+pub const EVENT_HEADER: &str = "X-Oxide-Event";
+/// The header of the delivery uuid
+pub const DELIVERY_HEADER: &str = "X-Oxide-Delivery";
+
+/// Returns the maximum number of attempts of a delivery before it's moved to the dead-letter list
+pub fn webhook_max_attempts() -> u32 {
+    env::var("WEBHOOK_MAX_ATTEMPTS")
+        .unwrap_or_else(|_| "5".to_string())
+        .parse()
+        .unwrap_or(5)
+}
+
+/// Returns the seconds to wait before the next attempt, after the given number of failed attempts
+pub fn retry_delay(attempts: u32) -> i64 {
+    RETRY_BASE_DELAY << attempts.saturating_sub(1).min(16)
+}
+
+/// Returns the signature of the payload, `sha256=` followed by the hex encoded HMAC-SHA256
+/// of the payload with the webhook secret
+pub fn sign(secret: &str, payload: &str) -> String {
+    // Safety: HMAC accepts keys of any size
+    let mut mac = Hmac::<Sha256>::new_from_slice(secret.as_bytes()).unwrap();
+    mac.update(payload.as_bytes());
+    format!("sha256={}", hex::encode(mac.finalize().into_bytes()))
+}
+
+/// Returns a new random webhook secret
+pub fn new_secret() -> String {
+    format!("{}{}", Uuid::new_v4().simple(), Uuid::new_v4().simple())
+}
+
+/// Returns the hosts that the webhooks can be sent to even if they resolve to non-public addresses,
+/// from the comma separated `WEBHOOK_ALLOWED_HOSTS` (e.g. `localhost,127.0.0.1`), none by default
+fn allowed_hosts() -> Vec<String> {
+    env::var("WEBHOOK_ALLOWED_HOSTS")
+        .unwrap_or_default()
+        .split(',')
+        .map(|host| host.trim().to_lowercase())
+        .filter(|host| !host.is_empty())
+        .collect()
+}
+
+/// Returns whether the address is public, the loopback, private, link-local, unspecified
+/// and the other local addresses are not
+fn is_public_ip(ip: IpAddr) -> bool {
+    match ip {
+        IpAddr::V4(ip) => {
+            let [first, second, ..] = ip.octets();
+            !(ip.is_loopback()
+                || ip.is_private()
+                || ip.is_link_local()
+                || ip.is_unspecified()
+                || ip.is_broadcast()
+                || ip.is_multicast()
+                || first == 0
+                // Carrier-grade NAT (100.64.0.0/10)
+                || (first == 100 && second & 0xc0 == 64))
+        }
+        IpAddr::V6(ip) => match ip.to_ipv4_mapped() {
+            Some(ip) => is_public_ip(IpAddr::V4(ip)),
+            None => {
+                let first = ip.segments()[0];
+                !(ip.is_loopback()
+                    || ip.is_unspecified()
+                    || ip.is_multicast()
+                    // Unique local (fc00::/7) and link-local (fe80::/10)
+                    || first & 0xfe00 == 0xfc00
+                    || first & 0xffc0 == 0xfe80)
+            }
+        },
+    }
+}
+
+/// Resolve the host of a webhook, if it resolves to a non-public address returns an error,
+/// so the webhooks can't reach the internal services. The hosts in `WEBHOOK_ALLOWED_HOSTS` are not checked
+pub async fn resolve_host(host: &str, port: u16) -> Result<Vec<SocketAddr>, String> {
+    let host = host
+        .trim_start_matches('[')
+        .trim_end_matches(']')
+        .to_owned();
+    let addrs = spawn_blocking({
+        let host = host.clone();
+        move || (host.as_str(), port).to_socket_addrs()
+    })
+    .await
+    .ok()
+    .and_then(Result::ok)
+    .map(Iterator::collect::<Vec<_>>)
+    .filter(|addrs| !addrs.is_empty())
+    .ok_or_else(|| format!("The host `{host}` can't be resolved"))?;
+    if allowed_hosts().contains(&host.to_lowercase())
+        || addrs.iter().all(|addr| is_public_ip(addr.ip()))
+    {
+        Ok(addrs)
+    } else {
+        Err(format!(
+            "The host `{host}` resolves to a non-public address"
+        ))
+    }
+}
+
+/// The DNS resolver of the webhooks client, it refuses the non-public addresses like `resolve_host`.
+/// Resolving the host again on connecting prevents it from changing its address after it's checked
+struct PublicResolver;
+
+impl Resolve for PublicResolver {
+    fn lookup<'a>(
+        &'a self,
+        host: &'a str,
+        port: u16,
+    ) -> LocalBoxFuture<'a, Result<Vec<SocketAddr>, Box<dyn std::error::Error>>> {
+        Box::pin(async move { resolve_host(host, port).await.map_err(Into::into) })
+    }
+}
+
+/// Returns the HTTP client of the webhooks, it connects only to the public addresses
+pub fn webhook_client(timeout: Duration) -> awc::Client {
+    let connector = awc::Connector::new()
+        .connector(TcpConnector::new(Resolver::custom(PublicResolver)).service());
+    awc::Client::builder()
+        .connector(connector)
+        .timeout(timeout)
+        .finish()
+}
+
+/// Check the webhook URL, it should be an absolute `http` or `https` URL of a public host,
+/// otherwise returns an error 400
+pub async fn check_url(url: &str) -> ApiResult<()> {
+    if url.len() > MAX_URL_LENGTH {
+        return Err(ApiError::BadRequest(format!(
+            "The webhook URL is too long, the maximum length is {MAX_URL_LENGTH}"
+        )));
+    }
+    let url = match url.parse::<Uri>() {
+        Ok(url) if matches!(url.scheme_str(), Some("http" | "https")) && url.host().is_some() => {
+            url
+        }
+        _ => {
+            return Err(ApiError::BadRequest(
+                "The webhook URL should be an absolute `http` or `https` URL".to_owned(),
+            ))
+        }
+    };
+    let port = url
+        .port_u16()
+        .unwrap_or(if url.scheme_str() == Some("https") {
+            443
+        } else {
+            80
+        });
+    resolve_host(url.host().unwrap_or_default(), port)
+        .await
+        .map(|_| ())
+        .map_err(|err| ApiError::BadRequest(format!("The webhook URL is not allowed: {err}")))
+}
+
+/// Returns the webhook with the given uuid, if the webhook doesn't exist returns an error 404
+pub async fn find_webhook_by_uuid(
+    uuid: Uuid,
+    user_id: u32,
+    db: &impl ConnectionTrait,
+) -> ApiResult<WebhookModel> {
+    WebhookEntity::find()
+        .filter(WebhookColumn::Uuid.eq(uuid))
+        .filter(WebhookColumn::UserId.eq(user_id))
+        .one(db)
+        .await
+        .database_err()?
+        .not_found_err("There is no webhook with the given uuid")
+}
+
+/// Returns the delivery of the webhook with the given uuid, if the delivery doesn't exist returns an error 404
+pub async fn find_delivery_by_uuid(
+    uuid: Uuid,
+    webhook: &WebhookModel,
+    db: &impl ConnectionTrait,
+) -> ApiResult<DeliveryModel> {
+    DeliveryEntity::find()
+        .filter(DeliveryColumn::Uuid.eq(uuid))
+        .filter(DeliveryColumn::WebhookId.eq(webhook.id))
+        .one(db)
+        .await
+        .database_err()?
+        .not_found_err("There is no delivery with the given uuid")
+}
+
+/// Queue the deliveries of the todo change to the webhooks of the user that subscribed to its event.
+/// Should be called in the same transaction of the change, so the deliveries are queued only
+/// if the change is committed
+pub async fn queue_deliveries(history: &HistoryModel, db: &impl ConnectionTrait) -> ApiResult<()> {
+    let event = TodoEventType::from(&history.action);
+    let webhooks = WebhookEntity::find()
+        .filter(WebhookColumn::UserId.eq(history.user_id))
+        .all(db)
+        .await
+        .database_err()?;
+    let webhooks = webhooks
+        .into_iter()
+        .filter(|webhook| webhook.events.split(',').any(|e| e == event.as_str()))
+        .collect::<Vec<_>>();
+    if webhooks.is_empty() {
+        return Ok(());
+    }
+
+    let payload = serde_json::to_string(&TodoEventSchema::new(history)?)
+        .server_err("Failed to serialize the event")?;
+    for webhook in webhooks {
+        NewDelivery {
+            uuid: Set(unique_uuid(DeliveryEntity::find(), DeliveryColumn::Uuid, db).await?),
+            webhook_id: Set(webhook.id),
+            event_id: Set(history.id),
+            event: Set(event.as_str().to_owned()),
+            payload: Set(payload.clone()),
+            status: Set(DeliveryStatus::Pending),
+            attempts: Set(0),
+            next_attempt_at: Set(Some(history.created_at)),
+            created_at: Set(history.created_at),
+            ..Default::default()
+        }
+        .insert(db)
+        .await
+        .database_err()?;
+    }
+    Ok(())
+}
+
+/// Reset the delivery to be attempted again as soon as possible, with a fresh attempts counter
+pub async fn redeliver(
+    delivery: DeliveryModel,
+    db: &impl ConnectionTrait,
+) -> ApiResult<DeliveryModel> {
+    NewDelivery {
+        status: Set(DeliveryStatus::Pending),
+        attempts: Set(0),
+        next_attempt_at: Set(Some(Utc::now().naive_utc().timestamp())),
+        error: Set(None),
+        delivered_at: Set(None),
+        ..delivery.into()
+    }
+    .update(db)
+    .await
+    .database_err()
+}
+
+/// Post the delivery payload to the webhook, returns the response status code
+/// and the error if the attempt is failed
+async fn attempt(
+    client: &awc::Client,
+    webhook: &WebhookModel,
+    delivery: &DeliveryModel,
+) -> (Option<u16>, Option<String>) {
+    // The addresses are checked again, the allowed hosts may have changed since the webhook is created
+    if let Err(err) = check_url(&webhook.url).await {
+        return (None, Some(err.to_string()));
+    }
+    let response = client
+        .post(&webhook.url)
+        .insert_header(("Content-Type", "application/json"))
+        .insert_header((SIGNATURE_HEADER, sign(&webhook.secret, &delivery.payload)))
+        .insert_header((EVENT_HEADER, delivery.event.as_str()))
+        .insert_header((DELIVERY_HEADER, delivery.uuid.to_string()))
+        .send_body(delivery.payload.clone())
+        .await;
+    match response {
+        Ok(response) if response.status().is_success() => (Some(response.status().as_u16()), None),
+        Ok(response) => (
+            Some(response.status().as_u16()),
+            Some(format!("The webhook responded with {}", response.status())),
+        ),
+        Err(err) => (None, Some(err.to_string())),
+    }
+}
+
+/// Attempt the pending deliveries that are due at the given time (Unix timestamp).
+/// The failed deliveries are retried with exponential backoff, after the maximum attempts
+/// they are marked as `failed` (the dead-letter list). The deliveries are attempted concurrently, at most
+/// `DELIVERIES_CONCURRENCY` at a time, so the slow webhooks don't hold the batch. Returns the number of the attempted deliveries
+pub async fn deliver_due(now: i64, db: &DatabaseConnection) -> ApiResult<u64> {
+    let due = DeliveryEntity::find()
+        .filter(DeliveryColumn::Status.eq(DeliveryStatus::Pending))
+        .filter(DeliveryColumn::NextAttemptAt.lte(now))
+        .order_by_asc(DeliveryColumn::Id)
+        .limit(DELIVERIES_BATCH_SIZE)
+        .find_also_related(WebhookEntity)
+        .all(db)
+        .await
+        .database_err()?;
+    let client = webhook_client(DELIVERY_TIMEOUT);
+    let max_attempts = webhook_max_attempts();
+
+    let results = stream::iter(due)
+        .filter_map(|(delivery, webhook)| async move { Some((delivery, webhook?)) })
+        .map(|(delivery, webhook)| {
+            let client = &client;
+            async move {
+                let result = attempt(client, &webhook, &delivery).await;
+                (delivery, webhook, result)
+            }
+        })
+        .buffer_unordered(DELIVERIES_CONCURRENCY)
+        .collect::<Vec<_>>()
+        .await;
+
+    let mut count = 0;
+    for (delivery, webhook, (response_status, error)) in results {
+        let attempts = delivery.attempts + 1;
+        let (status, next_attempt_at, delivered_at) = match error {
+            None => (DeliveryStatus::Succeeded, None, Some(now)),
+            Some(_) if attempts >= max_attempts => (DeliveryStatus::Failed, None, None),
+            Some(_) => (
+                DeliveryStatus::Pending,
+                Some(now + retry_delay(attempts)),
+                None,
+            ),
+        };
+        if status == DeliveryStatus::Failed {
+            log::warn!(
+                "The delivery `{}` to `{}` is failed after {attempts} attempts",
+                delivery.uuid,
+                webhook.url
+            );
+        }
+        NewDelivery {
+            status: Set(status),
+            attempts: Set(attempts),
+            next_attempt_at: Set(next_attempt_at),
+            response_status: Set(response_status),
+            error: Set(error),
+            delivered_at: Set(delivered_at),
+            ..delivery.into()
+        }
+        .update(db)
+        .await
+        .database_err()?;
+        count += 1;
+    }
+    Ok(count)
+}
@@ -0,0 +1,183 @@
+use actix_web::{get, rt, web, HttpRequest, HttpResponse};
+use actix_ws::{CloseReason, Closed, Message, MessageStream, Session};
+use futures_channel::mpsc::{self, UnboundedSender};
+use futures_util::{select, StreamExt};
+use sea_orm::{DatabaseConnection, TransactionTrait};
+use uuid::Uuid;
+
+use crate::{
+    api::auth::utils::req_auth,
+    api::sync,
+    api::todo::{events, utils},
+    errors::{Error as ApiError, ErrorTrait, Result as ApiResult},
+    schemas::{
+        message::MessageSchema,
+        todo::{TodoEventSchema, TodoSchema},
+        ws::{WsCommandSchema, WsMessageSchema},
+    },
+};
+
+/// Apply the command in its own transaction, returns the todo after the change
+async fn run_command(
+    command: &WsCommandSchema,
+    user_id: u32,
+    db: &DatabaseConnection,
+) -> ApiResult<TodoSchema> {
+    // Each command is a separate operation in the todo history, so it can be undone alone
+    let actor = utils::Actor {
+        user_id,
+        request_id: Uuid::new_v4().to_string(),
+        undo_of: None,
+    };
+    let txn = db.begin().await.database_err()?;
+    let todo = sync::apply_change(&command.command, &actor, &txn).await?;
+    txn.commit().await.database_err()?;
+    Ok(todo)
+}
+
+/// Returns the reply of the text message, the result of the command or its error
+async fn handle_text(text: &str, user_id: u32, db: &DatabaseConnection) -> WsMessageSchema {
+    let command: WsCommandSchema = match serde_json::from_str(text) {
+        Ok(command) => command,
+        Err(err) => {
+            return WsMessageSchema::Error {
+                id: None,
+                error: ApiError::BadRequest(format!("The command is invalid: {err}")).into(),
+            }
+        }
+    };
+    match run_command(&command, user_id, db).await {
+        Ok(todo) => WsMessageSchema::Result {
+            id: command.id,
+            todo,
+        },
+        Err(err) => WsMessageSchema::Error {
+            id: command.id,
+            error: err.into(),
+        },
+    }
+}
+
+/// Send the message as a JSON text message
+async fn send(session: &mut Session, message: &WsMessageSchema) -> Result<(), Closed> {
+    match serde_json::to_string(message) {
+        Ok(message) => session.text(message).await,
+        Err(err) => {
+            log::error!("Failed to serialize the WebSocket message: {err}");
+            Ok(())
+        }
+    }
+}
+
+/// Forward the todo events to the connection, until the connection is closed or the events can't be read
+async fn forward_events(
+    user_id: u32,
+    after: u32,
+    db: DatabaseConnection,
+    sender: UnboundedSender<ApiResult<Vec<TodoEventSchema>>>,
+) {
+    let mut events = Box::pin(events::event_stream(user_id, after, db));
+    while let Some(batch) = events.next().await {
+        let failed = batch.is_err();
+        if sender.unbounded_send(batch).is_err() || failed {
+            break;
+        }
+    }
+}
+
+/// Serve the WebSocket connection, until the client or the server closes it
+async fn serve(
+    user_id: u32,
+    after: u32,
+    mut session: Session,
+    messages: MessageStream,
+    db: DatabaseConnection,
+) {
+    let mut messages = messages.fuse();
+    // The events are read in their own task, so a command never waits for a suspended events query
+    let (sender, mut events) = mpsc::unbounded();
+    rt::spawn(forward_events(user_id, after, db.clone(), sender));
+    let reason: Option<CloseReason> = loop {
+        let sent = select! {
+            message = messages.next() => match message {
+                Some(Ok(Message::Text(text))) => {
+                    let reply = handle_text(&text, user_id, &db).await;
+                    send(&mut session, &reply).await
+                }
+                Some(Ok(Message::Binary(_))) => {
+                    let error = ApiError::BadRequest("The binary messages are not supported".to_owned());
+                    send(&mut session, &WsMessageSchema::Error { id: None, error: error.into() }).await
+                }
+                Some(Ok(Message::Ping(bytes))) => session.pong(&bytes).await,
+                Some(Ok(Message::Close(reason))) => break reason,
+                Some(Ok(_)) => Ok(()),
+                Some(Err(_)) | None => break None,
+            },
+            batch = events.next() => match batch {
+                // There are no events for a while, check that the connection is still open
+                Some(Ok(batch)) if batch.is_empty() => session.ping(b"").await,
+                Some(Ok(batch)) => {
+                    let mut sent = Ok(());
+                    for event in batch {
+                        sent = send(&mut session, &WsMessageSchema::Event(event)).await;
+                        if sent.is_err() {
+                            break;
+                        }
+                    }
+                    sent
+                }
+                Some(Err(err)) => {
+                    log::error!("Failed to read the todo events: {err}");
+                    break None;
+                }
+                None => break None,
+            },
+        };
+        if sent.is_err() {
+            // The connection is closed
+            return;
+        }
+    };
+    session.close(reason).await.ok();
+}
+
+/// Open a WebSocket connection to get the todo changes live and to change the todos.
+///
+/// The server sends JSON text messages, their `type` field is one of:
+/// - `event`: A change event of a todo, the same event of the events stream (`id`, `event` and `todo`).
+/// - `result`: The command is applied, with the command `id` and the `todo` after the change.
+/// - `error`: The command is rejected or the message is invalid, with the command `id` and the `error` (the same error of the REST endpoints).
+///
+/// The client sends the commands as JSON text messages, check the `WsCommandSchema` schema. The command is a change like
+/// the changes of the sync push, but its `version` is optional.
+#[utoipa::path(
+    context_path = "/api",
+    responses(
+        (status = 101, description = "The connection is upgraded to WebSocket"),
+        (
+            status = 400, description = "The request is not a WebSocket upgrade", body = MessageSchema,
+            example = json!(MessageSchema::new(400, "The request is not a WebSocket upgrade"))
+        ),
+    ),
+    tag = "Todo",
+    security(("Bearer Token" = []))
+)]
+#[get("/ws")]
+pub async fn ws(
+    req: HttpRequest,
+    body: web::Payload,
+    db: web::Data<DatabaseConnection>,
+) -> ApiResult<HttpResponse> {
+    let user = req_auth(req.clone(), db.get_ref()).await?;
+    let after = sync::last_change(user.id, db.get_ref()).await?;
+    let (response, session, messages) =
+        actix_ws::handle(&req, body).bad_request_err("The request is not a WebSocket upgrade")?;
+    rt::spawn(serve(
+        user.id,
+        after,
+        session,
+        messages,
+        db.get_ref().clone(),
+    ));
+    Ok(response)
+}
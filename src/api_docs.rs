@@ -18,6 +18,40 @@ use utoipa::{Modify, OpenApi};
         crate::api::todo::delete_todo::delete_todo,
         crate::api::todo::delete_todos::delete_todos,
         crate::api::todo::update::update_todo,
+        crate::api::todo::patch::patch_todo,
+        crate::api::todo::stats::stats,
+        crate::api::todo::bulk::bulk,
+        crate::api::todo::history::history,
+        crate::api::todo::events::events,
+        crate::api::todo::export::export,
+        crate::api::todo::import::import,
+        crate::api::todo::move_todo::move_todo,
+        crate::api::todo::trash::list_trash,
+        crate::api::todo::trash::restore,
+        crate::api::undo::undo,
+        crate::api::sync::sync,
+        crate::api::sync::push,
+        crate::api::ws::ws,
+        // Calendar routes
+        crate::api::calendar::feed::feed,
+        crate::api::calendar::token::create_token,
+        crate::api::calendar::token::delete_token,
+        // GraphQL routes
+        crate::api::graphql::execute,
+        crate::api::graphql::subscribe,
+        // Webhook routes
+        crate::api::webhook::create::create,
+        crate::api::webhook::list::list,
+        crate::api::webhook::delete::delete,
+        crate::api::webhook::deliveries::deliveries,
+        crate::api::webhook::deliveries::redeliver,
+        // Notification routes
+        crate::api::notification::preferences::get_preferences,
+        crate::api::notification::preferences::update_preferences,
+        crate::api::notification::preferences::verify_email,
+        crate::api::notification::list::list,
+        crate::api::notification::inbox::inbox,
+        crate::api::notification::inbox::read_message,
         // Server metadata
         crate::api::server_metadata::get_server_metadata,
     ),
@@ -35,6 +69,45 @@ use utoipa::{Modify, OpenApi};
             crate::schemas::todo::TodoListSchema,
             crate::schemas::todo::TodoListMetaSchema,
             crate::schemas::todo::UpdateTodoSchema,
+            crate::schemas::todo::MoveTodoSchema,
+            crate::schemas::todo::TodoStatsSchema,
+            crate::schemas::todo::TodoStatusCountSchema,
+            crate::schemas::todo::TodoTrendSchema,
+            crate::schemas::todo::BulkTodoSchema,
+            crate::schemas::todo::BulkTodoItemSchema,
+            crate::schemas::todo::BulkTodoResultSchema,
+            crate::schemas::todo::TodoHistorySchema,
+            crate::schemas::todo::TodoHistoryListSchema,
+            crate::schemas::todo::TodoEventSchema,
+            crate::schemas::todo::ImportResultSchema,
+            crate::schemas::todo::ImportErrorSchema,
+            crate::schemas::undo::UndoOperationSchema,
+            crate::schemas::undo::UndoResultSchema,
+            crate::schemas::sync::SyncSchema,
+            crate::schemas::sync::TodoTombstoneSchema,
+            crate::schemas::sync::SyncChangeSchema,
+            crate::schemas::sync::SyncPushSchema,
+            crate::schemas::sync::SyncChangeResultSchema,
+            crate::schemas::sync::SyncPushResultSchema,
+            crate::schemas::ws::WsCommandSchema,
+            // Calendar schemas
+            crate::schemas::calendar::CalendarTokenSchema,
+            // GraphQL schemas
+            crate::schemas::graphql::GraphQLRequestSchema,
+            // Webhook schemas
+            crate::schemas::webhook::CreateWebhookSchema,
+            crate::schemas::webhook::WebhookSchema,
+            crate::schemas::webhook::WebhookListSchema,
+            crate::schemas::webhook::WebhookDeliverySchema,
+            crate::schemas::webhook::WebhookDeliveryListSchema,
+            // Notification schemas
+            crate::schemas::notification::NotificationPreferenceSchema,
+            crate::schemas::notification::NotificationPreferencesSchema,
+            crate::schemas::notification::VerifyEmailSchema,
+            crate::schemas::notification::NotificationSchema,
+            crate::schemas::notification::NotificationListSchema,
+            crate::schemas::notification::InboxMessageSchema,
+            crate::schemas::notification::InboxSchema,
             // Server metadata
             crate::schemas::server_metadata::ServerMetadataSchema,
         )
@@ -42,6 +115,10 @@ use utoipa::{Modify, OpenApi};
     tags(
         (name = "Auth", description = "A authentication routes"),
         (name = "Todo", description = "A todo routes"),
+        (name = "Webhook", description = "A webhook routes"),
+        (name = "Notification", description = "A notification routes"),
+        (name = "Calendar", description = "A calendar feed routes"),
+        (name = "GraphQL", description = "A GraphQL routes"),
         (name = "Server Metadata", description = "A server metadata routes"),
     ),
     modifiers(&SecurityAddon)
@@ -0,0 +1,113 @@
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use actix_web::{
+    http::header::{ETag, EntityTag, IfMatch, IfModifiedSince, IfNoneMatch, LastModified},
+    HttpMessage, HttpRequest, HttpResponse, HttpResponseBuilder,
+};
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+
+use crate::errors::{ErrorTrait, Result as ApiResult};
+
+/// The validators of a response, used to answer the conditional requests
+/// (`If-None-Match` and `If-Modified-Since`) with `304 Not Modified`
+#[derive(Debug, Clone)]
+pub struct Validators {
+    /// The strong entity tag of the response
+    pub etag: EntityTag,
+    /// The last modification time of the response (Unix timestamp)
+    pub last_modified: Option<i64>,
+}
+
+impl Validators {
+    /// Create validators with the given entity tag
+    pub fn new(etag: EntityTag, last_modified: Option<i64>) -> Self {
+        Self {
+            etag,
+            last_modified,
+        }
+    }
+
+    /// Create validators with an entity tag that is a hash of the given source
+    pub fn hashed(source: impl AsRef<[u8]>, last_modified: Option<i64>) -> Self {
+        Self::new(EntityTag::new_strong(hash(source, 32)), last_modified)
+    }
+
+    /// Make the entity tag specific to the given representation variant (e.g. the query string),
+    /// the entity tag is not changed if the variant is empty
+    pub fn with_variant(mut self, variant: &str) -> Self {
+        if !variant.is_empty() {
+            self.etag = EntityTag::new_strong(format!("{}-{}", self.etag.tag(), hash(variant, 8)));
+        }
+        self
+    }
+
+    /// Returns `true` if the client representation is still fresh, so the response
+    /// should be `304 Not Modified`.
+    ///
+    /// The `If-Modified-Since` header is ignored if the `If-None-Match` header is present
+    /// (See RFC 7232 §6)
+    pub fn is_fresh(&self, req: &HttpRequest) -> bool {
+        if let Some(if_none_match) = req.get_header::<IfNoneMatch>() {
+            return match if_none_match {
+                IfNoneMatch::Any => true,
+                IfNoneMatch::Items(etags) => etags.iter().any(|etag| etag.weak_eq(&self.etag)),
+            };
+        }
+        match (req.get_header::<IfModifiedSince>(), self.last_modified) {
+            (Some(IfModifiedSince(since)), Some(last_modified)) => {
+                SystemTime::from(since) >= system_time(last_modified)
+            }
+            _ => false,
+        }
+    }
+
+    /// Returns the response of the given body, or `304 Not Modified` if the client
+    /// representation is still fresh. The validators headers are added to both.
+    pub fn respond(&self, req: &HttpRequest, body: &impl Serialize) -> ApiResult<HttpResponse> {
+        serde_json::to_vec(body)
+            .server_err("Failed to serialize the response")
+            .map(|body| self.respond_bytes(req, body))
+    }
+
+    /// Same as [`Validators::respond`] but for a serialized json body
+    pub fn respond_bytes(&self, req: &HttpRequest, body: Vec<u8>) -> HttpResponse {
+        if self.is_fresh(req) {
+            let mut response = HttpResponse::NotModified();
+            self.add_headers(&mut response);
+            return response.finish();
+        }
+        let mut response = HttpResponse::Ok();
+        self.add_headers(&mut response);
+        response.content_type("application/json").body(body)
+    }
+
+    /// Add the `ETag` and `Last-Modified` headers to the response
+    pub fn add_headers(&self, response: &mut HttpResponseBuilder) {
+        response.insert_header(ETag(self.etag.clone()));
+        if let Some(last_modified) = self.last_modified {
+            response.insert_header(LastModified(system_time(last_modified).into()));
+        }
+    }
+}
+
+/// Returns `true` if the `If-Match` header is missing or matches the given entity tag
+/// (See RFC 7232 §3.1, the strong comparison is used)
+pub fn if_match(req: &HttpRequest, etag: &EntityTag) -> bool {
+    match req.get_header::<IfMatch>() {
+        None | Some(IfMatch::Any) => true,
+        Some(IfMatch::Items(etags)) => etags.iter().any(|tag| tag.strong_eq(etag)),
+    }
+}
+
+/// Returns the first `len` hex characters of the sha256 hash of the given source
+fn hash(source: impl AsRef<[u8]>, len: usize) -> String {
+    let mut hash = hex::encode(Sha256::digest(source));
+    hash.truncate(len);
+    hash
+}
+
+/// Convert a Unix timestamp to a system time
+fn system_time(timestamp: i64) -> SystemTime {
+    UNIX_EPOCH + Duration::from_secs(timestamp.max(0) as u64)
+}
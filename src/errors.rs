@@ -1,11 +1,11 @@
 use actix_web::{
     body::BoxBody,
     error::{JsonPayloadError, QueryPayloadError},
-    http::StatusCode,
+    http::{header::ETag, StatusCode},
     HttpRequest, HttpResponse, Responder, ResponseError,
 };
 
-use crate::schemas::message::MessageSchema;
+use crate::schemas::{message::MessageSchema, todo::TodoSchema};
 
 #[derive(thiserror::Error, Debug, Clone)]
 pub enum Error {
@@ -19,8 +19,15 @@ pub enum Error {
     Forbidden(String),
     #[error("{0}")]
     Unauthorized(String),
+    #[error("{0}")]
+    Conflict(String),
+    #[error("{0}")]
+    UnsupportedMediaType(String),
     #[error("Too many requests, retry in {0}s")]
     TooManyRequests(u64),
+    /// The todo is modified by someone else, contains the current todo
+    #[error("The todo has been modified, the current version is {}", .0.version)]
+    PreconditionFailed(Box<TodoSchema>),
 }
 
 pub trait ErrorTrait {
@@ -32,6 +39,7 @@ pub trait ErrorTrait {
     fn unauthorized_err(self, message: &str) -> Self::Output;
 
     fn database_err(self) -> Self::Output;
+    #[allow(dead_code)]
     fn already_username_err(self, username: &str) -> Self::Output;
     fn key_creation_err(self) -> Self::Output;
     fn invalid_token_err(self) -> Self::Output;
@@ -149,12 +157,38 @@ impl ResponseError for Error {
             Self::NotFound(_) => StatusCode::NOT_FOUND,
             Self::Forbidden(_) => StatusCode::FORBIDDEN,
             Self::Unauthorized(_) => StatusCode::UNAUTHORIZED,
+            Self::Conflict(_) => StatusCode::CONFLICT,
+            Self::UnsupportedMediaType(_) => StatusCode::UNSUPPORTED_MEDIA_TYPE,
             Self::TooManyRequests(_) => StatusCode::TOO_MANY_REQUESTS,
+            Self::PreconditionFailed(_) => StatusCode::PRECONDITION_FAILED,
         }
     }
 
     fn error_response(&self) -> HttpResponse {
-        HttpResponse::build(self.status_code()).json(MessageSchema::from(self.clone()))
+        match self {
+            // Return the current todo, so the client can merge the changes
+            Self::PreconditionFailed(todo) => HttpResponse::build(self.status_code())
+                .insert_header(ETag(todo.etag()))
+                .json(todo),
+            _ => HttpResponse::build(self.status_code()).json(MessageSchema::from(self.clone())),
+        }
+    }
+}
+
+impl From<Error> for tonic::Status {
+    fn from(err: Error) -> Self {
+        let message = err.to_string();
+        match err {
+            Error::InternalServer(_) => Self::internal(message),
+            Error::BadRequest(_) => Self::invalid_argument(message),
+            Error::NotFound(_) => Self::not_found(message),
+            Error::Forbidden(_) => Self::permission_denied(message),
+            Error::Unauthorized(_) => Self::unauthenticated(message),
+            Error::Conflict(_) => Self::aborted(message),
+            Error::UnsupportedMediaType(_) => Self::invalid_argument(message),
+            Error::TooManyRequests(_) => Self::resource_exhausted(message),
+            Error::PreconditionFailed(_) => Self::failed_precondition(message),
+        }
     }
 }
 
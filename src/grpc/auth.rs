@@ -0,0 +1,59 @@
+use sea_orm::DatabaseConnection;
+use tonic::{Request, Response, Status};
+
+use crate::{
+    api::auth::revoke::revoke_token,
+    grpc::{
+        proto::auth::{auth_server::Auth, Credentials, RevokeRequest, User},
+        utils,
+    },
+    schemas::{
+        auth::{LoginSchema, RegisterSchema},
+        user::UserSchema,
+    },
+};
+
+/// The gRPC authentication service, it shares the logic of the `/api/auth` endpoints
+pub struct AuthService {
+    db: DatabaseConnection,
+}
+
+impl AuthService {
+    pub fn new(db: DatabaseConnection) -> Self {
+        Self { db }
+    }
+}
+
+impl From<UserSchema> for User {
+    fn from(user: UserSchema) -> Self {
+        Self {
+            username: user.name,
+            token: user.token,
+        }
+    }
+}
+
+#[tonic::async_trait]
+impl Auth for AuthService {
+    async fn register(&self, request: Request<Credentials>) -> Result<Response<User>, Status> {
+        let Credentials { username, password } = request.into_inner();
+        log::info!("Registering user: {username}");
+        let user = RegisterSchema { username, password }
+            .create(&self.db)
+            .await?;
+        Ok(Response::new(user.into()))
+    }
+
+    async fn login(&self, request: Request<Credentials>) -> Result<Response<User>, Status> {
+        let Credentials { username, password } = request.into_inner();
+        log::info!("Logging in user: {username}");
+        let user = LoginSchema { username, password }.login(&self.db).await?;
+        Ok(Response::new(user.into()))
+    }
+
+    async fn revoke(&self, request: Request<RevokeRequest>) -> Result<Response<User>, Status> {
+        let user = utils::req_auth(&request, &self.db).await?;
+        let user = revoke_token(&self.db, user).await?;
+        Ok(Response::new(user.into()))
+    }
+}
@@ -0,0 +1,42 @@
+use std::net::SocketAddr;
+
+use sea_orm::DatabaseConnection;
+use tonic::transport::{server::Router, Server};
+
+pub mod auth;
+pub mod todo;
+pub mod utils;
+
+/// The generated code of the protocol files in `proto/`
+pub mod proto {
+    pub mod auth {
+        tonic::include_proto!("oxide_todo.auth");
+    }
+    pub mod todo {
+        tonic::include_proto!("oxide_todo.todo");
+    }
+}
+
+use proto::{auth::auth_server::AuthServer, todo::todos_server::TodosServer};
+
+/// Returns the gRPC port, from `GRPC_PORT` environment variable
+pub fn grpc_port() -> u16 {
+    std::env::var("GRPC_PORT")
+        .ok()
+        .and_then(|port| port.parse().ok())
+        .unwrap_or(50051)
+}
+
+/// Returns the gRPC server with the auth and todo services
+pub fn router(db: DatabaseConnection) -> Router {
+    Server::builder()
+        .add_service(AuthServer::new(auth::AuthService::new(db.clone())))
+        .add_service(TodosServer::new(todo::TodoService::new(db)))
+}
+
+/// Serve the gRPC services on the given address, the server runs until the process exits
+pub async fn serve(addr: SocketAddr, db: DatabaseConnection) {
+    if let Err(err) = router(db).serve(addr).await {
+        log::error!("The gRPC server is stopped: {err}");
+    }
+}
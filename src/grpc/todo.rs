@@ -0,0 +1,182 @@
+use std::pin::Pin;
+
+use entity::todo::{Column as TodoColumn, Status as TodoStatus};
+use futures_util::{stream, Stream, StreamExt, TryStreamExt};
+use sea_orm::{DatabaseConnection, PaginatorTrait, QueryOrder, QuerySelect, TransactionTrait};
+use tonic::{Request, Response, Status};
+
+use crate::{
+    api::{
+        sync,
+        todo::{
+            events,
+            queries::{TodoFilters, TodoOrder, TodoOrderBy},
+            utils as todo_utils,
+        },
+    },
+    errors::{Error as ApiError, ErrorTrait},
+    grpc::{
+        proto::todo::{
+            self as proto, todos_server::Todos, CreateTodoRequest, DeleteTodoRequest,
+            EventsRequest, GetTodoRequest, ListTodosRequest, ListTodosResponse, Todo, TodoEvent,
+            UpdateTodoRequest,
+        },
+        utils,
+    },
+    schemas::todo::{TodoContentSchema, TodoSchema},
+};
+
+/// The gRPC todo service, it shares the logic of the `/api/todos` endpoints
+pub struct TodoService {
+    db: DatabaseConnection,
+}
+
+impl TodoService {
+    pub fn new(db: DatabaseConnection) -> Self {
+        Self { db }
+    }
+}
+
+/// Returns the list filters of the request
+fn list_filters(request: ListTodosRequest) -> Result<TodoFilters, ApiError> {
+    let order_by = match proto::TodoOrderBy::from_i32(request.order_by) {
+        Some(proto::TodoOrderBy::Unspecified) => None,
+        Some(proto::TodoOrderBy::CreatedAt) => Some(TodoOrderBy::CreatedAt),
+        Some(proto::TodoOrderBy::UpdatedAt) => Some(TodoOrderBy::UpdatedAt),
+        Some(proto::TodoOrderBy::Position) => Some(TodoOrderBy::Position),
+        None => return Err(ApiError::BadRequest("The order by is invalid".to_owned())),
+    };
+    let order = match proto::TodoOrder::from_i32(request.order) {
+        Some(proto::TodoOrder::Unspecified) => None,
+        Some(proto::TodoOrder::Older) => Some(TodoOrder::Older),
+        Some(proto::TodoOrder::Newer) => Some(TodoOrder::Newer),
+        None => return Err(ApiError::BadRequest("The order is invalid".to_owned())),
+    };
+    Ok(TodoFilters {
+        status: request
+            .status
+            .map(utils::parse_status)
+            .transpose()?
+            .flatten(),
+        title: request.title,
+        order_by,
+        order,
+        offset: request.offset,
+        limit: request.limit,
+    })
+}
+
+#[tonic::async_trait]
+impl Todos for TodoService {
+    type EventsStream = Pin<Box<dyn Stream<Item = Result<TodoEvent, Status>> + Send>>;
+
+    async fn create(&self, request: Request<CreateTodoRequest>) -> Result<Response<Todo>, Status> {
+        let user = utils::req_auth(&request, &self.db).await?;
+        let actor = utils::actor(user.id, &request);
+        let CreateTodoRequest { title, status } = request.into_inner();
+        let content = TodoContentSchema {
+            title,
+            status: utils::parse_status(status)?.unwrap_or(TodoStatus::Pending),
+        };
+
+        let txn = self.db.begin().await.database_err()?;
+        let todo = todo_utils::create_todo(&txn, content, &actor).await?;
+        txn.commit().await.database_err()?;
+        Ok(Response::new(todo.into()))
+    }
+
+    async fn get(&self, request: Request<GetTodoRequest>) -> Result<Response<Todo>, Status> {
+        let user = utils::req_auth(&request, &self.db).await?;
+        let uuid = utils::parse_uuid(&request.get_ref().uuid)?;
+        let todo = todo_utils::find_todo_by_uuid(uuid, user.id, &self.db).await?;
+        Ok(Response::new(TodoSchema::from(todo).into()))
+    }
+
+    async fn list(
+        &self,
+        request: Request<ListTodosRequest>,
+    ) -> Result<Response<ListTodosResponse>, Status> {
+        let user = utils::req_auth(&request, &self.db).await?;
+        let filters = list_filters(request.into_inner())?;
+        let query = filters.filter(todo_utils::user_todos(user.id));
+
+        let total = query.clone().count(&self.db).await.database_err()?;
+        let todos = query
+            .order_by(TodoColumn::from(filters.order_by()), filters.order().into())
+            .limit(filters.limit())
+            .offset(filters.offset())
+            .all(&self.db)
+            .await
+            .database_err()?;
+        Ok(Response::new(ListTodosResponse {
+            todos: todos
+                .into_iter()
+                .map(|todo| TodoSchema::from(todo).into())
+                .collect(),
+            total,
+        }))
+    }
+
+    async fn update(&self, request: Request<UpdateTodoRequest>) -> Result<Response<Todo>, Status> {
+        let user = utils::req_auth(&request, &self.db).await?;
+        let actor = utils::actor(user.id, &request);
+        let UpdateTodoRequest {
+            uuid,
+            title,
+            status,
+            version,
+        } = request.into_inner();
+        let uuid = utils::parse_uuid(&uuid)?;
+        let status = match status {
+            Some(status) => Some(
+                utils::parse_status(status)?.bad_request_err("The status should be specified")?,
+            ),
+            None => None,
+        };
+
+        let txn = self.db.begin().await.database_err()?;
+        let todo = todo_utils::find_todo_by_uuid(uuid, user.id, &txn).await?;
+        todo_utils::check_todo_version(&todo, version)?;
+        // The title is not changed if it's the same title
+        let title = title.filter(|title| title != &todo.title);
+        let todo = todo_utils::update_todo(todo, title, status, &actor, &txn).await?;
+        txn.commit().await.database_err()?;
+        Ok(Response::new(TodoSchema::from(todo).into()))
+    }
+
+    async fn delete(&self, request: Request<DeleteTodoRequest>) -> Result<Response<Todo>, Status> {
+        let user = utils::req_auth(&request, &self.db).await?;
+        let actor = utils::actor(user.id, &request);
+        let uuid = utils::parse_uuid(&request.get_ref().uuid)?;
+
+        let txn = self.db.begin().await.database_err()?;
+        let todo = todo_utils::find_todo_by_uuid(uuid, user.id, &txn).await?;
+        todo_utils::check_todo_version(&todo, request.get_ref().version)?;
+        let todo = todo_utils::trash_todo(todo, &actor, &txn).await?;
+        txn.commit().await.database_err()?;
+        Ok(Response::new(TodoSchema::from(todo).into()))
+    }
+
+    async fn events(
+        &self,
+        request: Request<EventsRequest>,
+    ) -> Result<Response<Self::EventsStream>, Status> {
+        let user = utils::req_auth(&request, &self.db).await?;
+        let after = match request.get_ref().after {
+            Some(after) => after,
+            None => sync::last_change(user.id, &self.db).await?,
+        };
+        // The empty batches (the keep-alive of the events stream) are skipped
+        let events = events::event_stream(user.id, after, self.db.clone())
+            .flat_map(|batch| {
+                let events: Vec<_> = match batch {
+                    Ok(batch) => batch.into_iter().map(Ok).collect(),
+                    Err(err) => vec![Err(err)],
+                };
+                stream::iter(events)
+            })
+            .map_ok(TodoEvent::from)
+            .map_err(Status::from);
+        Ok(Response::new(Box::pin(events)))
+    }
+}
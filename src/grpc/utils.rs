@@ -0,0 +1,105 @@
+use entity::todo::Status as TodoStatus;
+use entity::user::Model as UserModel;
+use sea_orm::DatabaseConnection;
+use tonic::Request;
+use uuid::Uuid;
+
+use crate::{
+    api::{auth::utils::get_user_by_token, todo::utils::Actor},
+    errors::{Error as ApiError, ErrorTrait, Result as ApiResult},
+    grpc::proto::todo as proto,
+    schemas::todo::{TodoEventSchema, TodoEventType, TodoSchema},
+};
+
+/// Returns the token of the `authorization` metadata, it should be `Bearer {token}`
+pub fn extract_token<T>(request: &Request<T>) -> ApiResult<String> {
+    request
+        .metadata()
+        .get("authorization")
+        .map(|token| token.to_str().map(|token| token.strip_prefix("Bearer ")))
+        .unauthorized_err("`authorization` metadata is missing")?
+        .unauthorized_err("The token is invalid, cannot convert it to string")?
+        .unauthorized_err("Token should start with `Bearer` prefix")
+        .map(ToOwned::to_owned)
+}
+
+/// Returns the user of the `authorization` metadata
+pub async fn req_auth<T>(request: &Request<T>, db: &DatabaseConnection) -> ApiResult<UserModel> {
+    get_user_by_token(db, &extract_token(request)?).await
+}
+
+/// Returns the actor of the request, the request id is taken from the `x-request-id` metadata or generated
+pub fn actor<T>(user_id: u32, request: &Request<T>) -> Actor {
+    Actor::with_request_id(
+        user_id,
+        request
+            .metadata()
+            .get("x-request-id")
+            .and_then(|id| id.to_str().ok()),
+    )
+}
+
+/// Parse the uuid of the request
+pub fn parse_uuid(uuid: &str) -> ApiResult<Uuid> {
+    Uuid::parse_str(uuid).bad_request_err("The uuid is invalid")
+}
+
+/// Returns the status of the protocol status value, `None` if the status is unspecified
+pub fn parse_status(status: i32) -> ApiResult<Option<TodoStatus>> {
+    match proto::TodoStatus::from_i32(status)
+        .ok_or_else(|| ApiError::BadRequest("The status is invalid".to_owned()))?
+    {
+        proto::TodoStatus::Unspecified => Ok(None),
+        proto::TodoStatus::Completed => Ok(Some(TodoStatus::Completed)),
+        proto::TodoStatus::Pending => Ok(Some(TodoStatus::Pending)),
+        proto::TodoStatus::Progress => Ok(Some(TodoStatus::Progress)),
+        proto::TodoStatus::Cancelled => Ok(Some(TodoStatus::Cancelled)),
+    }
+}
+
+impl From<TodoStatus> for proto::TodoStatus {
+    fn from(status: TodoStatus) -> Self {
+        match status {
+            TodoStatus::Completed => Self::Completed,
+            TodoStatus::Pending => Self::Pending,
+            TodoStatus::Progress => Self::Progress,
+            TodoStatus::Cancelled => Self::Cancelled,
+        }
+    }
+}
+
+impl From<TodoSchema> for proto::Todo {
+    fn from(todo: TodoSchema) -> Self {
+        Self {
+            uuid: todo.uuid.to_string(),
+            title: todo.title,
+            status: proto::TodoStatus::from(todo.status).into(),
+            created_at: todo.created_at,
+            updated_at: todo.updated_at,
+            completed_at: todo.completed_at,
+            version: todo.version,
+            deleted_at: todo.deleted_at,
+            position: todo.position,
+        }
+    }
+}
+
+impl From<TodoEventType> for proto::TodoEventType {
+    fn from(event: TodoEventType) -> Self {
+        match event {
+            TodoEventType::Created => Self::Created,
+            TodoEventType::Updated => Self::Updated,
+            TodoEventType::Deleted => Self::Deleted,
+        }
+    }
+}
+
+impl From<TodoEventSchema> for proto::TodoEvent {
+    fn from(event: TodoEventSchema) -> Self {
+        Self {
+            id: event.id,
+            event: proto::TodoEventType::from(event.event).into(),
+            todo: Some(event.todo.into()),
+        }
+    }
+}
@@ -0,0 +1,301 @@
+use std::{
+    env,
+    future::{ready, Future, Ready},
+    pin::Pin,
+    rc::Rc,
+};
+
+use actix_http::h1;
+use actix_web::{
+    body::{self, BoxBody, MessageBody},
+    dev::{forward_ready, Service, ServiceRequest, ServiceResponse, Transform},
+    http::{
+        header::{HeaderName, HeaderValue},
+        StatusCode,
+    },
+    web, HttpResponse,
+};
+use chrono::Utc;
+use entity::idempotency_key::{
+    ActiveModel as NewIdempotencyKey, Column as IdempotencyKeyColumn,
+    Entity as IdempotencyKeyEntity, Model as IdempotencyKeyModel,
+};
+use sea_orm::{
+    ActiveModelTrait, ColumnTrait, DatabaseConnection, EntityTrait, ModelTrait, QueryFilter, Set,
+};
+use sha2::{Digest, Sha256};
+
+use crate::{
+    api::auth::utils as auth_utils,
+    errors::{Error as ApiError, ErrorTrait, Result as ApiResult},
+};
+
+/// The header of the idempotency key
+pub const IDEMPOTENCY_KEY_HEADER: &str = "Idempotency-Key";
+/// The header that is added to the replayed responses
+pub const IDEMPOTENT_REPLAYED_HEADER: &str = "Idempotent-Replayed";
+/// The maximum length of the idempotency key
+const MAX_KEY_LENGTH: usize = 255;
+/// The seconds after which a request that is still in progress is considered abandoned,
+/// e.g. the server is stopped while handling it
+const ABANDONED_AFTER: i64 = 60;
+
+/// Returns the number of hours that the responses of the idempotency keys are stored
+pub fn idempotency_key_ttl_hours() -> u64 {
+    env::var("IDEMPOTENCY_KEY_TTL_HOURS")
+        .unwrap_or_else(|_| "24".to_string())
+        .parse()
+        .unwrap_or(24)
+}
+
+/// Permanently delete the idempotency keys that are created before the given time (Unix timestamp),
+/// returns the number of the deleted keys
+pub async fn purge_keys(before: i64, db: &DatabaseConnection) -> ApiResult<u64> {
+    IdempotencyKeyEntity::delete_many()
+        .filter(IdempotencyKeyColumn::CreatedAt.lt(before))
+        .exec(db)
+        .await
+        .database_err()
+        .map(|res| res.rows_affected)
+}
+
+/// Middleware that stores the response of the requests with `Idempotency-Key` header,
+/// and replays it for the repeated requests with the same key and payload.
+///
+/// The keys are per user, the requests without a valid token are passed to the handler as is.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Idempotency;
+
+impl<S, B> Transform<S, ServiceRequest> for Idempotency
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = actix_web::Error> + 'static,
+    B: MessageBody + 'static,
+{
+    type Response = ServiceResponse<BoxBody>;
+    type Error = actix_web::Error;
+    type Transform = IdempotencyMiddleware<S>;
+    type InitError = ();
+    type Future = Ready<Result<Self::Transform, Self::InitError>>;
+
+    fn new_transform(&self, service: S) -> Self::Future {
+        ready(Ok(IdempotencyMiddleware {
+            service: Rc::new(service),
+        }))
+    }
+}
+
+pub struct IdempotencyMiddleware<S> {
+    service: Rc<S>,
+}
+
+impl<S, B> Service<ServiceRequest> for IdempotencyMiddleware<S>
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = actix_web::Error> + 'static,
+    B: MessageBody + 'static,
+{
+    type Response = ServiceResponse<BoxBody>;
+    type Error = actix_web::Error;
+    #[allow(clippy::type_complexity)]
+    type Future = Pin<Box<dyn Future<Output = Result<Self::Response, Self::Error>>>>;
+
+    forward_ready!(service);
+
+    fn call(&self, req: ServiceRequest) -> Self::Future {
+        let service = Rc::clone(&self.service);
+        Box::pin(async move {
+            match req.headers().get(IDEMPOTENCY_KEY_HEADER) {
+                Some(key) if !req.method().is_safe() => {
+                    let key = key.to_str().map(ToOwned::to_owned);
+                    idempotent_call(req, key.ok(), service).await
+                }
+                _ => service
+                    .call(req)
+                    .await
+                    .map(ServiceResponse::map_into_boxed_body),
+            }
+        })
+    }
+}
+
+/// Call the service once for the idempotency key, or replay the stored response
+async fn idempotent_call<S, B>(
+    mut req: ServiceRequest,
+    key: Option<String>,
+    service: Rc<S>,
+) -> Result<ServiceResponse<BoxBody>, actix_web::Error>
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = actix_web::Error>,
+    B: MessageBody + 'static,
+{
+    let Some(key) = key.filter(|key| !key.is_empty() && key.len() <= MAX_KEY_LENGTH) else {
+        return Ok(req.error_response(ApiError::BadRequest(format!(
+            "The `{IDEMPOTENCY_KEY_HEADER}` header must be 1 to {MAX_KEY_LENGTH} visible characters"
+        ))));
+    };
+    let Some(db) = req.app_data::<web::Data<DatabaseConnection>>().cloned() else {
+        return service
+            .call(req)
+            .await
+            .map(ServiceResponse::map_into_boxed_body);
+    };
+    // The handler will return the authentication error
+    let Ok(user) = auth_utils::req_auth(req.request().clone(), &db).await else {
+        return service
+            .call(req)
+            .await
+            .map(ServiceResponse::map_into_boxed_body);
+    };
+
+    let payload = req.extract::<web::Bytes>().await?;
+    let fingerprint = request_fingerprint(&req, &payload);
+    let (_, mut new_payload) = h1::Payload::create(true);
+    new_payload.unread_data(payload);
+    req.set_payload(new_payload.into());
+
+    let stored = match begin(user.id, &key, &fingerprint, &db).await {
+        Ok(stored) => stored,
+        Err(err) => return Ok(req.error_response(err)),
+    };
+    if let Some(response) = replay(&stored) {
+        return Ok(req.into_response(response));
+    }
+
+    let res = match service.call(req).await {
+        Ok(res) => res,
+        Err(err) => {
+            // Release the key, so the request can be retried
+            stored.delete(db.get_ref()).await.ok();
+            return Err(err);
+        }
+    };
+    let (req, res) = res.into_parts();
+    let (res, body) = res.into_parts();
+    let body = match body::to_bytes(body).await {
+        Ok(body) => body,
+        Err(_) => {
+            stored.delete(db.get_ref()).await.ok();
+            return Ok(ServiceResponse::new(
+                req,
+                HttpResponse::from_error(ApiError::InternalServer(
+                    "Failed to read the response body".to_owned(),
+                )),
+            ));
+        }
+    };
+    let result = if res.status().is_server_error() {
+        // The server errors are not stored, so the request can be retried
+        stored.delete(db.get_ref()).await.map(|_| ()).database_err()
+    } else {
+        // The repeated headers (e.g. `Set-Cookie` and `Link`) are kept as separate pairs
+        let headers: Vec<(&str, &str)> = res
+            .headers()
+            .iter()
+            .filter_map(|(name, value)| Some((name.as_str(), value.to_str().ok()?)))
+            .collect();
+        NewIdempotencyKey {
+            status_code: Set(Some(res.status().as_u16())),
+            headers: Set(serde_json::to_string(&headers).ok()),
+            body: Set(Some(body.to_vec())),
+            ..stored.into()
+        }
+        .update(db.get_ref())
+        .await
+        .map(|_| ())
+        .database_err()
+    };
+    if let Err(err) = result {
+        log::error!("Failed to store the response of the idempotency key: {err}");
+    }
+    Ok(ServiceResponse::new(req, res.set_body(BoxBody::new(body))))
+}
+
+/// Returns the hash of the request method, path, query and body
+fn request_fingerprint(req: &ServiceRequest, payload: &[u8]) -> String {
+    let mut hasher = Sha256::new();
+    hasher.update(req.method().as_str());
+    hasher.update(b"\n");
+    hasher.update(req.path());
+    hasher.update(b"?");
+    hasher.update(req.query_string());
+    hasher.update(b"\n");
+    hasher.update(payload);
+    hex::encode(hasher.finalize())
+}
+
+/// Start a request with the idempotency key. Returns the stored key, it has a response if the request is already done
+///
+/// If the key is used with another request returns an error 409,
+/// and if the request with the same key is still in progress returns an error 409
+async fn begin(
+    user_id: u32,
+    key: &str,
+    fingerprint: &str,
+    db: &DatabaseConnection,
+) -> ApiResult<IdempotencyKeyModel> {
+    let now = Utc::now().naive_utc().timestamp();
+    let expired_before = now - idempotency_key_ttl_hours() as i64 * 3600;
+    let find = || {
+        IdempotencyKeyEntity::find()
+            .filter(IdempotencyKeyColumn::UserId.eq(user_id))
+            .filter(IdempotencyKeyColumn::Key.eq(key))
+            .one(db)
+    };
+
+    if let Some(stored) = find().await.database_err()? {
+        let abandoned = stored.status_code.is_none() && stored.created_at < now - ABANDONED_AFTER;
+        if stored.created_at >= expired_before && !abandoned {
+            return check_stored(stored, fingerprint);
+        }
+        stored.delete(db).await.database_err()?;
+    }
+    let new_key = NewIdempotencyKey {
+        user_id: Set(user_id),
+        key: Set(key.to_owned()),
+        fingerprint: Set(fingerprint.to_owned()),
+        created_at: Set(now),
+        ..Default::default()
+    };
+    match new_key.insert(db).await {
+        Ok(stored) => Ok(stored),
+        // Another request with the same key is inserted at the same time
+        Err(_) => check_stored(find().await.database_err()?.database_err()?, fingerprint),
+    }
+}
+
+/// Check the stored key against the request fingerprint, and whether if its response is stored
+fn check_stored(stored: IdempotencyKeyModel, fingerprint: &str) -> ApiResult<IdempotencyKeyModel> {
+    if stored.fingerprint != fingerprint {
+        Err(ApiError::Conflict(format!(
+            "The idempotency key `{}` is already used with a different request",
+            stored.key
+        )))
+    } else if stored.status_code.is_none() {
+        Err(ApiError::Conflict(format!(
+            "The request with the idempotency key `{}` is still in progress",
+            stored.key
+        )))
+    } else {
+        Ok(stored)
+    }
+}
+
+/// Returns the stored response of the key, `None` if there is no stored response
+fn replay(stored: &IdempotencyKeyModel) -> Option<HttpResponse> {
+    let status = StatusCode::from_u16(stored.status_code?).ok()?;
+    let headers: Vec<(String, String)> = stored
+        .headers
+        .as_deref()
+        .and_then(|headers| serde_json::from_str(headers).ok())
+        .unwrap_or_default();
+    let mut response = HttpResponse::build(status);
+    for (name, value) in headers {
+        if let (Ok(name), Ok(value)) = (HeaderName::try_from(name), HeaderValue::try_from(value)) {
+            response.append_header((name, value));
+        }
+    }
+    Some(
+        response
+            .insert_header((IDEMPOTENT_REPLAYED_HEADER, "true"))
+            .body(stored.body.clone().unwrap_or_default()),
+    )
+}
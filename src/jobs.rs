@@ -0,0 +1,89 @@
+use std::time::Duration;
+
+use actix_web::rt::{self, time};
+use chrono::Utc;
+use sea_orm::DatabaseConnection;
+
+use crate::api::todo::utils as todo_utils;
+use crate::api::webhook::utils as webhook_utils;
+use crate::idempotency;
+use crate::notifications;
+
+/// The interval of purging the expired todos from the trash
+const TRASH_PURGE_INTERVAL: Duration = Duration::from_secs(60 * 60);
+/// The interval of purging the expired idempotency keys
+const IDEMPOTENCY_KEYS_PURGE_INTERVAL: Duration = Duration::from_secs(60 * 60);
+/// The interval of attempting the due webhook deliveries
+const WEBHOOK_DELIVERIES_INTERVAL: Duration = Duration::from_secs(5);
+/// The interval of dispatching the due notifications
+const NOTIFICATIONS_DISPATCH_INTERVAL: Duration = Duration::from_secs(5);
+
+/// Spawn a background job that permanently deletes the todos that stayed in the trash
+/// more than the retention period, runs every hour
+pub fn spawn_trash_purge(db: DatabaseConnection) {
+    rt::spawn(async move {
+        let mut interval = time::interval(TRASH_PURGE_INTERVAL);
+        loop {
+            interval.tick().await;
+            let before = Utc::now().naive_utc().timestamp()
+                - todo_utils::trash_retention_days() as i64 * 86_400;
+            match todo_utils::purge_trash(before, &db).await {
+                Ok(0) => {}
+                Ok(count) => log::info!("Purged {count} todos from the trash"),
+                Err(err) => log::error!("Failed to purge the trash: {err}"),
+            }
+        }
+    });
+}
+
+/// Spawn a background job that deletes the idempotency keys that are stored
+/// more than the time to live, runs every hour
+pub fn spawn_idempotency_keys_purge(db: DatabaseConnection) {
+    rt::spawn(async move {
+        let mut interval = time::interval(IDEMPOTENCY_KEYS_PURGE_INTERVAL);
+        loop {
+            interval.tick().await;
+            let before = Utc::now().naive_utc().timestamp()
+                - idempotency::idempotency_key_ttl_hours() as i64 * 3600;
+            match idempotency::purge_keys(before, &db).await {
+                Ok(0) => {}
+                Ok(count) => log::info!("Purged {count} expired idempotency keys"),
+                Err(err) => log::error!("Failed to purge the idempotency keys: {err}"),
+            }
+        }
+    });
+}
+
+/// Spawn a background job that posts the due webhook deliveries, and retries the failed ones
+/// with exponential backoff, runs every 5 seconds
+pub fn spawn_webhook_deliveries(db: DatabaseConnection) {
+    rt::spawn(async move {
+        let mut interval = time::interval(WEBHOOK_DELIVERIES_INTERVAL);
+        loop {
+            interval.tick().await;
+            let now = Utc::now().naive_utc().timestamp();
+            match webhook_utils::deliver_due(now, &db).await {
+                Ok(0) => {}
+                Ok(count) => log::info!("Attempted {count} webhook deliveries"),
+                Err(err) => log::error!("Failed to deliver the webhooks: {err}"),
+            }
+        }
+    });
+}
+
+/// Spawn a background job that dispatches the due notifications to their channels, and retries
+/// the failed ones with exponential backoff, runs every 5 seconds
+pub fn spawn_notifications_dispatch(db: DatabaseConnection) {
+    rt::spawn(async move {
+        let mut interval = time::interval(NOTIFICATIONS_DISPATCH_INTERVAL);
+        loop {
+            interval.tick().await;
+            let now = Utc::now().naive_utc().timestamp();
+            match notifications::dispatch_due(now, &db).await {
+                Ok(0) => {}
+                Ok(count) => log::info!("Dispatched {count} notifications"),
+                Err(err) => log::error!("Failed to dispatch the notifications: {err}"),
+            }
+        }
+    });
+}
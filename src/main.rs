@@ -1,9 +1,10 @@
+use std::net::ToSocketAddrs;
 use std::path::Path;
 
 use actix_extensible_rate_limit::backend::memory::InMemoryBackend;
 use actix_web::middleware::Logger;
 use actix_web::web::{JsonConfig, QueryConfig};
-use actix_web::{web, App, HttpRequest, HttpServer};
+use actix_web::{rt, web, App, HttpRequest, HttpServer};
 use errors::Error as ApiError;
 use migration::{Migrator, MigratorTrait};
 use sea_orm::{Database, DatabaseConnection};
@@ -11,7 +12,12 @@ use utoipa_swagger_ui::SwaggerUi;
 
 mod api;
 mod api_docs;
+mod conditional;
 mod errors;
+mod grpc;
+mod idempotency;
+mod jobs;
+mod notifications;
 mod ratelimit;
 mod schemas;
 
@@ -49,6 +55,17 @@ async fn main() -> std::io::Result<()> {
     Migrator::up(&pool, None)
         .await
         .expect("Failed to run migrations");
+    jobs::spawn_trash_purge(pool.clone());
+    jobs::spawn_idempotency_keys_purge(pool.clone());
+    jobs::spawn_webhook_deliveries(pool.clone());
+    jobs::spawn_notifications_dispatch(pool.clone());
+
+    // The gRPC server runs on its own port, next to the HTTP server
+    let grpc_addr = (host.as_str(), grpc::grpc_port())
+        .to_socket_addrs()?
+        .next()
+        .expect("The gRPC address is invalid");
+    rt::spawn(grpc::serve(grpc_addr, pool.clone()));
 
     log::info!("Listening on http://{}", addr);
     log::info!(
@@ -56,13 +73,15 @@ async fn main() -> std::io::Result<()> {
         addr,
     );
     log::info!("Swagger UI is available at http://{}/docs/swagger/", addr);
+    log::info!("gRPC server is listening on http://{}", grpc_addr);
 
     let ratelimit_backend = InMemoryBackend::builder().build();
 
     println!(
         "The RESTful API is available at <http://{addr}/api/>
         \rOpenAPI document is available at <http://{addr}/docs/openapi.json>
-        \rSwagger UI is available at <http://{addr}/docs/swagger/>",
+        \rSwagger UI is available at <http://{addr}/docs/swagger/>
+        \rThe gRPC services are available at <http://{grpc_addr}>",
     );
 
     HttpServer::new(move || {
@@ -0,0 +1,16 @@
+use async_trait::async_trait;
+use entity::notification::Model as NotificationModel;
+use sea_orm::DatabaseConnection;
+
+/// A channel that the notifications are delivered through. The notifications are persisted
+/// when the todo events happen, then dispatched to their channels by the background worker
+#[async_trait(?Send)]
+pub trait NotificationChannel {
+    /// Deliver the notification, returns the error message if the delivery is failed,
+    /// the failed notifications are retried with exponential backoff
+    async fn send(
+        &self,
+        notification: &NotificationModel,
+        db: &DatabaseConnection,
+    ) -> Result<(), String>;
+}
@@ -0,0 +1,108 @@
+use std::{env, time::Duration};
+
+use actix_web::http::Uri;
+use async_trait::async_trait;
+use entity::notification::Model as NotificationModel;
+use lettre::{
+    message::{header::ContentType, Mailbox},
+    transport::smtp::authentication::Credentials,
+    Address, AsyncSmtpTransport, AsyncTransport, Message, Tokio1Executor,
+};
+use sea_orm::DatabaseConnection;
+
+use super::NotificationChannel;
+
+/// The default sender of the emails
+const DEFAULT_FROM: &str = "Oxide Todo <noreply@localhost>";
+/// The maximum time to wait for the SMTP server
+const SEND_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Returns the URL of the SMTP server, `None` if it's not configured
+fn smtp_url() -> Option<String> {
+    env::var("SMTP_URL").ok().filter(|url| !url.is_empty())
+}
+
+/// Returns `true` if the SMTP server is configured, the email channel is disabled otherwise
+pub fn is_enabled() -> bool {
+    smtp_url().is_some()
+}
+
+/// Build the SMTP transport from the URL. `smtp://[user:password@]host[:port]` for a plain
+/// connection, add `?tls=required` to require `STARTTLS`, or use `smtps://` for implicit TLS
+fn transport(url: &str) -> Result<AsyncSmtpTransport<Tokio1Executor>, String> {
+    let invalid = || "The `SMTP_URL` should be a `smtp://` or `smtps://` URL".to_owned();
+    let tls_err = |err| format!("Failed to set up the SMTP TLS: {err}");
+    let uri = url.parse::<Uri>().map_err(|_| invalid())?;
+    let host = uri.host().ok_or_else(invalid)?;
+
+    let mut builder = match (uri.scheme_str(), uri.query()) {
+        (Some("smtps"), None) => {
+            AsyncSmtpTransport::<Tokio1Executor>::relay(host).map_err(tls_err)?
+        }
+        (Some("smtp"), Some("tls=required")) => {
+            AsyncSmtpTransport::<Tokio1Executor>::starttls_relay(host).map_err(tls_err)?
+        }
+        (Some("smtp"), None) => AsyncSmtpTransport::<Tokio1Executor>::builder_dangerous(host),
+        _ => return Err(invalid()),
+    };
+    if let Some(port) = uri.port_u16() {
+        builder = builder.port(port);
+    }
+    if let Some((credentials, _)) = uri.authority().and_then(|a| a.as_str().rsplit_once('@')) {
+        let (username, password) = credentials.split_once(':').unwrap_or((credentials, ""));
+        builder = builder.credentials(Credentials::new(username.to_owned(), password.to_owned()));
+    }
+    Ok(builder.timeout(Some(SEND_TIMEOUT)).build())
+}
+
+/// The email channel, sends the notifications to the target address through the SMTP server
+pub struct EmailChannel {
+    /// The SMTP transport and the sender, or the configuration error
+    config: Result<(AsyncSmtpTransport<Tokio1Executor>, Mailbox), String>,
+}
+
+impl EmailChannel {
+    /// Create the email channel from the `SMTP_URL` and `SMTP_FROM` environment variables
+    pub fn from_env() -> Self {
+        let config = smtp_url()
+            .ok_or_else(|| "The SMTP server is not configured".to_owned())
+            .and_then(|url| transport(&url))
+            .and_then(|transport| {
+                env::var("SMTP_FROM")
+                    .unwrap_or_else(|_| DEFAULT_FROM.to_owned())
+                    .parse::<Mailbox>()
+                    .map(|from| (transport, from))
+                    .map_err(|err| format!("The `SMTP_FROM` address is invalid: {err}"))
+            });
+        Self { config }
+    }
+}
+
+#[async_trait(?Send)]
+impl NotificationChannel for EmailChannel {
+    async fn send(
+        &self,
+        notification: &NotificationModel,
+        _: &DatabaseConnection,
+    ) -> Result<(), String> {
+        let (transport, from) = self.config.as_ref().map_err(Clone::clone)?;
+        let to = notification
+            .target
+            .as_deref()
+            .unwrap_or_default()
+            .parse::<Address>()
+            .map_err(|err| format!("The email address is invalid: {err}"))?;
+        let message = Message::builder()
+            .from(from.clone())
+            .to(Mailbox::new(None, to))
+            .subject(&notification.subject)
+            .header(ContentType::TEXT_PLAIN)
+            .body(notification.body.clone())
+            .map_err(|err| err.to_string())?;
+        transport
+            .send(message)
+            .await
+            .map(|_| ())
+            .map_err(|err| err.to_string())
+    }
+}
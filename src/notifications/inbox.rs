@@ -0,0 +1,42 @@
+use async_trait::async_trait;
+use entity::inbox_message::{
+    ActiveModel as NewMessage, Column as MessageColumn, Entity as MessageEntity,
+};
+use entity::notification::Model as NotificationModel;
+use sea_orm::{ActiveModelTrait, DatabaseConnection, EntityTrait, Set};
+
+use super::NotificationChannel;
+use crate::api::todo::utils::unique_uuid;
+
+/// The inbox channel, stores the notifications as messages in the in-app inbox of the user
+pub struct InboxChannel;
+
+#[async_trait(?Send)]
+impl NotificationChannel for InboxChannel {
+    async fn send(
+        &self,
+        notification: &NotificationModel,
+        db: &DatabaseConnection,
+    ) -> Result<(), String> {
+        NewMessage {
+            uuid: Set(unique_uuid(MessageEntity::find(), MessageColumn::Uuid, db)
+                .await
+                .map_err(|err| err.to_string())?),
+            user_id: Set(notification.user_id),
+            notification_id: Set(notification.id),
+            event: Set(notification.event.clone()),
+            todo_uuid: Set(notification
+                .todo_uuid
+                .ok_or("The notification is not of a todo event")?),
+            subject: Set(notification.subject.clone()),
+            body: Set(notification.body.clone()),
+            created_at: Set(notification.created_at),
+            read_at: Set(None),
+            ..Default::default()
+        }
+        .insert(db)
+        .await
+        .map(|_| ())
+        .map_err(|err| err.to_string())
+    }
+}
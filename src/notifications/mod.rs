@@ -0,0 +1,235 @@
+//! The notifications of the todo events. The users choose which events are sent to which channel
+//! in their preferences, the notifications are persisted with the change and dispatched
+//! by a background worker, so the request handlers don't wait for the channels
+
+use std::env;
+
+use chrono::Utc;
+use entity::notification::{
+    ActiveModel as NewNotification, Column as NotificationColumn, Entity as NotificationEntity,
+    Status as NotificationStatus,
+};
+use entity::notification_preference::{
+    Channel, Column as PreferenceColumn, Entity as PreferenceEntity,
+};
+use entity::todo_history::Model as HistoryModel;
+use futures_util::{stream, StreamExt};
+use sea_orm::{
+    ActiveModelTrait, ColumnTrait, ConnectionTrait, DatabaseConnection, EntityTrait, QueryFilter,
+    QueryOrder, QuerySelect, Set,
+};
+
+use crate::{
+    api::{
+        todo::utils::unique_uuid,
+        webhook::utils::{retry_delay, DELIVERIES_CONCURRENCY},
+    },
+    errors::{ErrorTrait, Result as ApiResult},
+    schemas::todo::{TodoEventSchema, TodoEventType},
+};
+
+pub mod channel;
+pub mod email;
+pub mod inbox;
+pub mod webhook;
+
+pub use channel::NotificationChannel;
+
+/// The event of the verification email of the `email` channel
+pub const VERIFICATION_EVENT: &str = "email.verification";
+/// The maximum number of notifications dispatched in one run
+const DISPATCH_BATCH_SIZE: u64 = 50;
+
+/// Returns the maximum number of attempts of a notification before it's marked as failed
+pub fn notification_max_attempts() -> u32 {
+    env::var("NOTIFICATION_MAX_ATTEMPTS")
+        .unwrap_or_else(|_| "5".to_string())
+        .parse()
+        .unwrap_or(5)
+}
+
+/// Returns the subject and the body of the notification of the event
+fn render(event: &TodoEventSchema) -> (String, String) {
+    let (subject, action) = match event.event {
+        TodoEventType::Created => ("New todo", "created"),
+        TodoEventType::Updated => ("Todo updated", "updated"),
+        TodoEventType::Deleted => ("Todo deleted", "moved to the trash"),
+    };
+    (
+        format!("{subject}: {}", event.todo.title),
+        format!(
+            "The todo \"{}\" is {action}.\n\nTodo: {}\n",
+            event.todo.title, event.todo.uuid
+        ),
+    )
+}
+
+/// Queue the notifications of the todo change to the channels that the user chose for its event.
+/// Should be called in the same transaction of the change, so the notifications are queued only
+/// if the change is committed
+pub async fn queue_notifications(
+    history: &HistoryModel,
+    db: &impl ConnectionTrait,
+) -> ApiResult<()> {
+    let event = TodoEventType::from(&history.action);
+    let preferences = PreferenceEntity::find()
+        .filter(PreferenceColumn::UserId.eq(history.user_id))
+        .all(db)
+        .await
+        .database_err()?
+        .into_iter()
+        .filter(|preference| preference.events.split(',').any(|e| e == event.as_str()))
+        // The emails are sent only to the verified addresses
+        .filter(|preference| {
+            preference.channel != Channel::Email || preference.verified_at.is_some()
+        })
+        .collect::<Vec<_>>();
+    if preferences.is_empty() {
+        return Ok(());
+    }
+
+    let schema = TodoEventSchema::new(history)?;
+    let payload = serde_json::to_string(&schema).server_err("Failed to serialize the event")?;
+    let (subject, body) = render(&schema);
+    for preference in preferences {
+        NewNotification {
+            uuid: Set(unique_uuid(NotificationEntity::find(), NotificationColumn::Uuid, db).await?),
+            user_id: Set(history.user_id),
+            channel: Set(preference.channel),
+            target: Set(preference.target),
+            event_id: Set(Some(history.id)),
+            event: Set(event.as_str().to_owned()),
+            todo_uuid: Set(Some(history.todo_uuid)),
+            subject: Set(subject.clone()),
+            body: Set(body.clone()),
+            payload: Set(payload.clone()),
+            status: Set(NotificationStatus::Pending),
+            attempts: Set(0),
+            next_attempt_at: Set(Some(history.created_at)),
+            created_at: Set(history.created_at),
+            ..Default::default()
+        }
+        .insert(db)
+        .await
+        .database_err()?;
+    }
+    Ok(())
+}
+
+/// Queue the verification email of the `email` channel with its token, it's sent by the dispatcher
+/// like the notifications. Should be called in the same transaction of the preferences change
+pub async fn queue_verification(
+    user_id: u32,
+    target: &str,
+    token: &str,
+    db: &impl ConnectionTrait,
+) -> ApiResult<()> {
+    let now = Utc::now().naive_utc().timestamp();
+    NewNotification {
+        uuid: Set(unique_uuid(NotificationEntity::find(), NotificationColumn::Uuid, db).await?),
+        user_id: Set(user_id),
+        channel: Set(Channel::Email),
+        target: Set(Some(target.to_owned())),
+        event_id: Set(None),
+        event: Set(VERIFICATION_EVENT.to_owned()),
+        todo_uuid: Set(None),
+        subject: Set("Verify your email address".to_owned()),
+        body: Set(format!(
+            "Your email address is set to receive the Oxide Todo notifications.\n\n\
+            Verify it with this token in `POST /api/notifications/preferences/verify`:\n\n\
+            {token}\n\nIgnore this email if you didn't ask for the notifications.\n"
+        )),
+        payload: Set("{}".to_owned()),
+        status: Set(NotificationStatus::Pending),
+        attempts: Set(0),
+        next_attempt_at: Set(Some(now)),
+        created_at: Set(now),
+        ..Default::default()
+    }
+    .insert(db)
+    .await
+    .database_err()?;
+    Ok(())
+}
+
+/// Remove the pending verification emails of the user, their tokens are no longer valid
+pub async fn remove_pending_verification(user_id: u32, db: &impl ConnectionTrait) -> ApiResult<()> {
+    NotificationEntity::delete_many()
+        .filter(NotificationColumn::UserId.eq(user_id))
+        .filter(NotificationColumn::Event.eq(VERIFICATION_EVENT))
+        .filter(NotificationColumn::Status.eq(NotificationStatus::Pending))
+        .exec(db)
+        .await
+        .database_err()?;
+    Ok(())
+}
+
+/// Dispatch the pending notifications that are due at the given time (Unix timestamp) to their channels.
+/// The failed notifications are retried with exponential backoff, after the maximum attempts
+/// they are marked as `failed`. The notifications are sent concurrently like the webhook deliveries.
+/// Returns the number of the dispatched notifications
+pub async fn dispatch_due(now: i64, db: &DatabaseConnection) -> ApiResult<u64> {
+    let due = NotificationEntity::find()
+        .filter(NotificationColumn::Status.eq(NotificationStatus::Pending))
+        .filter(NotificationColumn::NextAttemptAt.lte(now))
+        .order_by_asc(NotificationColumn::Id)
+        .limit(DISPATCH_BATCH_SIZE)
+        .all(db)
+        .await
+        .database_err()?;
+    if due.is_empty() {
+        return Ok(0);
+    }
+    let email = email::EmailChannel::from_env();
+    let webhook = webhook::WebhookChannel::default();
+    let max_attempts = notification_max_attempts();
+
+    let results = stream::iter(due)
+        .map(|notification| {
+            let channel: &dyn NotificationChannel = match notification.channel {
+                Channel::Email => &email,
+                Channel::Webhook => &webhook,
+                Channel::Inbox => &inbox::InboxChannel,
+            };
+            async move {
+                let error = channel.send(&notification, db).await.err();
+                (notification, error)
+            }
+        })
+        .buffer_unordered(DELIVERIES_CONCURRENCY)
+        .collect::<Vec<_>>()
+        .await;
+
+    let mut count = 0;
+    for (notification, error) in results {
+        let attempts = notification.attempts + 1;
+        let (status, next_attempt_at, sent_at) = match error {
+            None => (NotificationStatus::Sent, None, Some(now)),
+            Some(_) if attempts >= max_attempts => (NotificationStatus::Failed, None, None),
+            Some(_) => (
+                NotificationStatus::Pending,
+                Some(now + retry_delay(attempts)),
+                None,
+            ),
+        };
+        if status == NotificationStatus::Failed {
+            log::warn!(
+                "The notification `{}` is failed after {attempts} attempts",
+                notification.uuid
+            );
+        }
+        NewNotification {
+            status: Set(status),
+            attempts: Set(attempts),
+            next_attempt_at: Set(next_attempt_at),
+            error: Set(error),
+            sent_at: Set(sent_at),
+            ..notification.into()
+        }
+        .update(db)
+        .await
+        .database_err()?;
+        count += 1;
+    }
+    Ok(count)
+}
@@ -0,0 +1,66 @@
+use std::time::Duration;
+
+use async_trait::async_trait;
+use entity::notification::Model as NotificationModel;
+use entity::notification_preference::{
+    Channel, Column as PreferenceColumn, Entity as PreferenceEntity,
+};
+use sea_orm::{ColumnTrait, DatabaseConnection, EntityTrait, QueryFilter};
+
+use super::NotificationChannel;
+use crate::api::webhook::utils::{check_url, sign, webhook_client, EVENT_HEADER, SIGNATURE_HEADER};
+
+/// The header of the notification uuid
+pub const NOTIFICATION_HEADER: &str = "X-Oxide-Notification";
+/// The maximum time to wait for the webhook response
+const SEND_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// The webhook channel, posts the JSON event of the notifications to the target URL,
+/// signed with the secret of the webhook preference like the webhook deliveries
+pub struct WebhookChannel {
+    client: awc::Client,
+}
+
+impl Default for WebhookChannel {
+    fn default() -> Self {
+        Self {
+            client: webhook_client(SEND_TIMEOUT),
+        }
+    }
+}
+
+#[async_trait(?Send)]
+impl NotificationChannel for WebhookChannel {
+    async fn send(
+        &self,
+        notification: &NotificationModel,
+        db: &DatabaseConnection,
+    ) -> Result<(), String> {
+        let target = notification.target.as_deref().unwrap_or_default();
+        check_url(target).await.map_err(|err| err.to_string())?;
+        let secret = PreferenceEntity::find()
+            .filter(PreferenceColumn::UserId.eq(notification.user_id))
+            .filter(PreferenceColumn::Channel.eq(Channel::Webhook))
+            .filter(PreferenceColumn::Target.eq(target))
+            .one(db)
+            .await
+            .map_err(|err| err.to_string())?
+            .and_then(|preference| preference.secret)
+            .ok_or_else(|| "The webhook URL is no longer in the preferences".to_owned())?;
+        let response = self
+            .client
+            .post(target)
+            .insert_header(("Content-Type", "application/json"))
+            .insert_header((SIGNATURE_HEADER, sign(&secret, &notification.payload)))
+            .insert_header((EVENT_HEADER, notification.event.as_str()))
+            .insert_header((NOTIFICATION_HEADER, notification.uuid.to_string()))
+            .send_body(notification.payload.clone())
+            .await
+            .map_err(|err| err.to_string())?;
+        if response.status().is_success() {
+            Ok(())
+        } else {
+            Err(format!("The webhook responded with {}", response.status()))
+        }
+    }
+}
@@ -0,0 +1,40 @@
+use actix_web::{body::BoxBody, Responder};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+/// The secret token of the calendar feed
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct CalendarTokenSchema {
+    /// The secret token, it's returned only once. Creating a new token disables the previous one
+    #[schema(example = "5c1e0d7f9b3a4e2d8f6c1b0a9e8d7c6b5a4f3e2d1c0b9a8f7e6d5c4b3a2f1e0d")]
+    pub token: String,
+    /// The path of the calendar feed with the token, subscribe to it in the calendar apps
+    #[schema(
+        example = "/api/todos.ics?token=5c1e0d7f9b3a4e2d8f6c1b0a9e8d7c6b5a4f3e2d1c0b9a8f7e6d5c4b3a2f1e0d"
+    )]
+    pub url: String,
+}
+
+impl CalendarTokenSchema {
+    /// Create a new calendar token schema, with the feed path of the token
+    pub fn new(token: String) -> Self {
+        Self {
+            url: format!("/api/todos.ics?token={token}"),
+            token,
+        }
+    }
+}
+
+impl Default for CalendarTokenSchema {
+    fn default() -> Self {
+        Self::new("5c1e0d7f9b3a4e2d8f6c1b0a9e8d7c6b5a4f3e2d1c0b9a8f7e6d5c4b3a2f1e0d".to_owned())
+    }
+}
+
+impl Responder for CalendarTokenSchema {
+    type Body = BoxBody;
+
+    fn respond_to(self, _: &actix_web::HttpRequest) -> actix_web::HttpResponse<Self::Body> {
+        actix_web::HttpResponse::Ok().json(self)
+    }
+}
@@ -0,0 +1,18 @@
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+/// A GraphQL request
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct GraphQLRequestSchema {
+    /// The GraphQL document, with the query or the mutation
+    #[schema(
+        example = "query($first: Int) { todos(first: $first, filter: { status: PENDING }) { totalCount edges { cursor node { uuid title status } } } }"
+    )]
+    pub query: String,
+    /// The values of the variables of the document
+    #[schema(value_type = Object, example = json!({"first": 10}))]
+    pub variables: Option<serde_json::Value>,
+    /// The name of the operation to execute, if the document has many operations
+    pub operation_name: Option<String>,
+}
@@ -1,6 +1,13 @@
 pub mod auth;
+pub mod calendar;
+pub mod graphql;
 pub mod message;
+pub mod notification;
 pub mod server_metadata;
+pub mod sync;
 pub mod todo;
 pub mod traits;
+pub mod undo;
 pub mod user;
+pub mod webhook;
+pub mod ws;
@@ -0,0 +1,323 @@
+use actix_web::{body::BoxBody, Responder};
+use entity::inbox_message::Model as MessageModel;
+use entity::notification::{Model as NotificationModel, Status as NotificationStatus};
+use entity::notification_preference::{Channel, Model as PreferenceModel};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use super::todo::TodoEventType;
+
+/// The preference of a notification channel
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct NotificationPreferenceSchema {
+    /// The channel that the notifications are delivered through (`email`, `webhook` or `inbox`)
+    #[schema(value_type = String, example = "email")]
+    pub channel: Channel,
+    /// The email address of the `email` channel, or the URL of the `webhook` channel
+    /// Note: Should be `null` for the `inbox` channel
+    #[schema(example = "user@example.com")]
+    pub target: Option<String>,
+    /// The events that are sent to the channel (default: all the events)
+    #[schema(value_type = Vec<String>, example = json!(["todo.created", "todo.deleted"]))]
+    #[serde(default = "all_events")]
+    pub events: Vec<TodoEventType>,
+    /// Whether the email address is verified, the notifications are sent only to a verified address
+    /// Note: Will return `false` for the other channels, ignored in the request
+    #[schema(example = "false")]
+    #[serde(default)]
+    pub verified: bool,
+    /// The secret that the webhook requests are signed with, use it to verify the `X-Oxide-Signature` header
+    /// Note: Will return `null` except in the response that sets a new webhook URL, store it. Ignored in the request
+    #[schema(example = "null")]
+    #[serde(default)]
+    pub secret: Option<String>,
+}
+
+/// The notification preferences of the user, each channel at most once
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct NotificationPreferencesSchema {
+    /// The preferences of the channels, the channels that are not listed are disabled
+    pub data: Vec<NotificationPreferenceSchema>,
+}
+
+/// The token that verifies the email address of the `email` channel
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct VerifyEmailSchema {
+    /// The token of the verification email
+    #[schema(example = "5c1e0d7f9b3a4e2d8f6c1b0a9e8d7c6b5a4f3e2d1c0b9a8f7e6d5c4b3a2f1e0d")]
+    pub token: String,
+}
+
+/// A notification of a todo event
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct NotificationSchema {
+    /// The uuid of the notification
+    #[schema(value_type = String, example = "7d3c2b1a-0f9e-4d8c-b7a6-5e4d3c2b1a0f")]
+    pub uuid: Uuid,
+    /// The channel that the notification is delivered through (`email`, `webhook` or `inbox`)
+    #[schema(value_type = String, example = "email")]
+    pub channel: Channel,
+    /// The email address or the URL that the notification is sent to
+    /// Note: Will return `null` for the `inbox` channel
+    #[schema(example = "user@example.com")]
+    pub target: Option<String>,
+    /// The type of the event, `email.verification` for the verification email of the `email` channel
+    #[schema(value_type = String, example = "todo.created")]
+    pub event: String,
+    /// The uuid of the todo of the event
+    /// Note: Will return `null` for the verification email
+    #[schema(value_type = Option<String>, example = "3f4b1b5e-5d0a-4e0b-9c8e-2e6c7f0f9a1d")]
+    pub todo_uuid: Option<Uuid>,
+    /// The subject of the notification
+    #[schema(example = "New todo: Buy milk")]
+    pub subject: String,
+    /// The status of the notification (`pending`, `sent` or `failed`)
+    #[schema(value_type = String, example = "sent")]
+    pub status: NotificationStatus,
+    /// The number of the attempts
+    #[schema(example = "1")]
+    pub attempts: u32,
+    /// The time of the next attempt (Unix timestamp)
+    /// Note: Will return `null` if the notification is not pending
+    #[schema(example = "null")]
+    pub next_attempt_at: Option<i64>,
+    /// The error of the last attempt
+    /// Note: Will return `null` if the last attempt is succeeded
+    #[schema(example = "null")]
+    pub error: Option<String>,
+    /// The created time of the notification (Unix timestamp)
+    #[schema(example = "1620000000")]
+    pub created_at: i64,
+    /// The time that the notification is sent (Unix timestamp)
+    /// Note: Will return `null` if the notification is not sent
+    #[schema(example = "1620000001")]
+    pub sent_at: Option<i64>,
+}
+
+/// The recent notifications of the user
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct NotificationListSchema {
+    /// The notifications, the newest first
+    pub data: Vec<NotificationSchema>,
+}
+
+/// A message in the inbox of the user
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct InboxMessageSchema {
+    /// The uuid of the message
+    #[schema(value_type = String, example = "1e2d3c4b-5a69-4788-9a0b-c1d2e3f4a5b6")]
+    pub uuid: Uuid,
+    /// The type of the event
+    #[schema(value_type = String, example = "todo.created")]
+    pub event: String,
+    /// The uuid of the todo of the event
+    #[schema(value_type = String, example = "3f4b1b5e-5d0a-4e0b-9c8e-2e6c7f0f9a1d")]
+    pub todo_uuid: Uuid,
+    /// The subject of the message
+    #[schema(example = "New todo: Buy milk")]
+    pub subject: String,
+    /// The body of the message
+    #[schema(
+        example = "The todo \"Buy milk\" is created.\n\nTodo: 3f4b1b5e-5d0a-4e0b-9c8e-2e6c7f0f9a1d\n"
+    )]
+    pub body: String,
+    /// The created time of the message (Unix timestamp)
+    #[schema(example = "1620000000")]
+    pub created_at: i64,
+    /// The time that the message is read (Unix timestamp)
+    /// Note: Will return `null` if the message is unread
+    #[schema(example = "null")]
+    pub read_at: Option<i64>,
+}
+
+/// The inbox of the user
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct InboxSchema {
+    /// The number of the unread messages
+    #[schema(example = "1")]
+    pub unread: u64,
+    /// The messages, the newest first
+    pub data: Vec<InboxMessageSchema>,
+}
+
+/// Returns all the event types, the default events of a channel
+fn all_events() -> Vec<TodoEventType> {
+    TodoEventType::ALL.to_vec()
+}
+
+impl NotificationPreferenceSchema {
+    /// Create a new preference schema from the preference model, with its webhook secret if `with_secret` is `true`
+    pub fn new(preference: PreferenceModel, with_secret: bool) -> Self {
+        Self {
+            channel: preference.channel,
+            target: preference.target,
+            events: preference
+                .events
+                .split(',')
+                .filter_map(|event| serde_json::from_value(event.into()).ok())
+                .collect(),
+            verified: preference.verified_at.is_some(),
+            secret: preference.secret.filter(|_| with_secret),
+        }
+    }
+}
+
+impl From<PreferenceModel> for NotificationPreferenceSchema {
+    fn from(preference: PreferenceModel) -> Self {
+        Self::new(preference, false)
+    }
+}
+
+impl From<NotificationModel> for NotificationSchema {
+    fn from(notification: NotificationModel) -> Self {
+        Self {
+            uuid: notification.uuid,
+            channel: notification.channel,
+            target: notification.target,
+            event: notification.event,
+            todo_uuid: notification.todo_uuid,
+            subject: notification.subject,
+            status: notification.status,
+            attempts: notification.attempts,
+            next_attempt_at: notification.next_attempt_at,
+            error: notification.error,
+            created_at: notification.created_at,
+            sent_at: notification.sent_at,
+        }
+    }
+}
+
+impl From<MessageModel> for InboxMessageSchema {
+    fn from(message: MessageModel) -> Self {
+        Self {
+            uuid: message.uuid,
+            event: message.event,
+            todo_uuid: message.todo_uuid,
+            subject: message.subject,
+            body: message.body,
+            created_at: message.created_at,
+            read_at: message.read_at,
+        }
+    }
+}
+
+impl Default for NotificationPreferenceSchema {
+    fn default() -> Self {
+        Self {
+            channel: Channel::Email,
+            target: Some("user@example.com".to_owned()),
+            events: vec![TodoEventType::Created, TodoEventType::Deleted],
+            verified: false,
+            secret: None,
+        }
+    }
+}
+
+impl Default for NotificationPreferencesSchema {
+    fn default() -> Self {
+        Self {
+            data: vec![
+                NotificationPreferenceSchema::default(),
+                NotificationPreferenceSchema {
+                    channel: Channel::Inbox,
+                    target: None,
+                    events: all_events(),
+                    verified: false,
+                    secret: None,
+                },
+            ],
+        }
+    }
+}
+
+impl Default for NotificationSchema {
+    fn default() -> Self {
+        Self {
+            uuid: Uuid::new_v4(),
+            channel: Channel::Email,
+            target: Some("user@example.com".to_owned()),
+            event: TodoEventType::Created.as_str().to_owned(),
+            todo_uuid: Some(Uuid::new_v4()),
+            subject: "New todo: Buy milk".to_owned(),
+            status: NotificationStatus::Sent,
+            attempts: 1,
+            next_attempt_at: None,
+            error: None,
+            created_at: 1620000000,
+            sent_at: Some(1620000001),
+        }
+    }
+}
+
+impl Default for NotificationListSchema {
+    fn default() -> Self {
+        Self {
+            data: vec![NotificationSchema::default()],
+        }
+    }
+}
+
+impl Default for InboxMessageSchema {
+    fn default() -> Self {
+        let todo_uuid = Uuid::new_v4();
+        Self {
+            uuid: Uuid::new_v4(),
+            event: TodoEventType::Created.as_str().to_owned(),
+            todo_uuid,
+            subject: "New todo: Buy milk".to_owned(),
+            body: format!("The todo \"Buy milk\" is created.\n\nTodo: {todo_uuid}\n"),
+            created_at: 1620000000,
+            read_at: None,
+        }
+    }
+}
+
+impl Default for InboxSchema {
+    fn default() -> Self {
+        Self {
+            unread: 1,
+            data: vec![InboxMessageSchema::default()],
+        }
+    }
+}
+
+impl Responder for NotificationPreferenceSchema {
+    type Body = BoxBody;
+
+    fn respond_to(self, _: &actix_web::HttpRequest) -> actix_web::HttpResponse<Self::Body> {
+        actix_web::HttpResponse::Ok().json(self)
+    }
+}
+
+impl Responder for NotificationPreferencesSchema {
+    type Body = BoxBody;
+
+    fn respond_to(self, _: &actix_web::HttpRequest) -> actix_web::HttpResponse<Self::Body> {
+        actix_web::HttpResponse::Ok().json(self)
+    }
+}
+
+impl Responder for NotificationListSchema {
+    type Body = BoxBody;
+
+    fn respond_to(self, _: &actix_web::HttpRequest) -> actix_web::HttpResponse<Self::Body> {
+        actix_web::HttpResponse::Ok().json(self)
+    }
+}
+
+impl Responder for InboxMessageSchema {
+    type Body = BoxBody;
+
+    fn respond_to(self, _: &actix_web::HttpRequest) -> actix_web::HttpResponse<Self::Body> {
+        actix_web::HttpResponse::Ok().json(self)
+    }
+}
+
+impl Responder for InboxSchema {
+    type Body = BoxBody;
+
+    fn respond_to(self, _: &actix_web::HttpRequest) -> actix_web::HttpResponse<Self::Body> {
+        actix_web::HttpResponse::Ok().json(self)
+    }
+}
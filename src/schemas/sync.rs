@@ -0,0 +1,199 @@
+use actix_web::{body::BoxBody, Responder};
+use entity::todo::Status as TodoStatus;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use super::{message::MessageSchema, todo::TodoSchema};
+
+/// The operation of a pushed change
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum SyncOperation {
+    /// Create a new todo
+    Create,
+    /// Update the title or the status of a todo
+    Update,
+    /// Move a todo to the trash
+    Delete,
+}
+
+/// The result status of a pushed change
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum SyncChangeStatus {
+    /// The change is applied
+    Applied,
+    /// The todo is changed on the server after the client's version, the change is not applied
+    Conflict,
+    /// The change is rejected, see the error
+    Rejected,
+}
+
+/// A deleted todo, the todo is in the trash or permanently deleted
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct TodoTombstoneSchema {
+    /// The uuid of the deleted todo
+    #[schema(value_type = String, example = "a8bfed8d-4f8b-4150-8ace-3f8916609eba")]
+    pub uuid: Uuid,
+    /// The time the todo is deleted (Unix timestamp)
+    #[schema(example = "1620000000")]
+    pub deleted_at: i64,
+}
+
+/// The changes of the user todos since the last sync
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct SyncSchema {
+    /// The change token, send it in the `since` parameter of the next sync
+    /// Note: The token is opaque, don't parse it
+    #[schema(example = "42")]
+    pub token: String,
+    /// The created and updated todos, with their current state
+    pub todos: Vec<TodoSchema>,
+    /// The deleted todos
+    pub deleted: Vec<TodoTombstoneSchema>,
+}
+
+/// A change made by the client while offline
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct SyncChangeSchema {
+    /// The operation of the change (`create`, `update` or `delete`)
+    #[schema(value_type = String, example = "update")]
+    pub op: SyncOperation,
+    /// The uuid of the changed todo, required for `update` and `delete`
+    #[schema(value_type = Option<String>, example = "a8bfed8d-4f8b-4150-8ace-3f8916609eba")]
+    pub uuid: Option<Uuid>,
+    /// The last version of the todo that the client knows, required for `update` and `delete`
+    #[schema(example = "1")]
+    pub version: Option<u32>,
+    /// The title of the todo, required for `create`, can be `null` to keep the original title
+    #[schema(example = "Todo title")]
+    pub title: Option<String>,
+    /// The status of the todo, can be `null` to keep the original status (default for `create`: `pending`)
+    #[schema(value_type = Option<String>, example = "completed")]
+    pub status: Option<TodoStatus>,
+}
+
+/// The changes made by the client while offline, they are applied in order
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct SyncPushSchema {
+    /// The changes, up to 100 changes
+    pub changes: Vec<SyncChangeSchema>,
+}
+
+/// The result of a single pushed change
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct SyncChangeResultSchema {
+    /// The uuid of the changed todo, it's the uuid of the new todo for `create`
+    /// Note: Will return `null` if the creation is rejected
+    #[schema(value_type = Option<String>, example = "a8bfed8d-4f8b-4150-8ace-3f8916609eba")]
+    pub uuid: Option<Uuid>,
+    /// The result status (`applied`, `conflict` or `rejected`)
+    #[schema(value_type = String, example = "applied")]
+    pub status: SyncChangeStatus,
+    /// The todo after applying the change, or the server copy of the todo if there is a conflict
+    /// Note: Will return `null` if the change is rejected
+    pub todo: Option<TodoSchema>,
+    /// Why the change is rejected, the same error of the REST endpoints
+    /// Note: Will return `null` if the change is not rejected
+    pub error: Option<MessageSchema>,
+}
+
+/// The result of the pushed changes, in the same order
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct SyncPushResultSchema {
+    /// The number of applied changes
+    #[schema(example = "1")]
+    pub applied: u64,
+    /// The result of each change
+    pub results: Vec<SyncChangeResultSchema>,
+}
+
+impl SyncChangeResultSchema {
+    /// Create a new change result
+    pub fn new(
+        uuid: Option<Uuid>,
+        status: SyncChangeStatus,
+        todo: Option<TodoSchema>,
+        error: Option<MessageSchema>,
+    ) -> Self {
+        Self {
+            uuid,
+            status,
+            todo,
+            error,
+        }
+    }
+}
+
+impl SyncPushResultSchema {
+    /// Create a new push result
+    pub fn new(results: Vec<SyncChangeResultSchema>) -> Self {
+        Self {
+            applied: results
+                .iter()
+                .filter(|result| result.status == SyncChangeStatus::Applied)
+                .count() as u64,
+            results,
+        }
+    }
+}
+
+impl TodoTombstoneSchema {
+    /// Create a new tombstone
+    pub fn new(uuid: Uuid, deleted_at: i64) -> Self {
+        Self { uuid, deleted_at }
+    }
+}
+
+impl Default for SyncSchema {
+    fn default() -> Self {
+        Self {
+            token: "42".to_owned(),
+            todos: vec![TodoSchema::default()],
+            deleted: vec![TodoTombstoneSchema::new(Uuid::new_v4(), 1620000000)],
+        }
+    }
+}
+
+impl Default for SyncPushSchema {
+    fn default() -> Self {
+        Self {
+            changes: vec![SyncChangeSchema {
+                op: SyncOperation::Update,
+                uuid: Some(Uuid::new_v4()),
+                version: Some(1),
+                title: None,
+                status: Some(TodoStatus::Completed),
+            }],
+        }
+    }
+}
+
+impl Default for SyncPushResultSchema {
+    fn default() -> Self {
+        let todo = TodoSchema::default();
+        Self::new(vec![SyncChangeResultSchema::new(
+            Some(todo.uuid),
+            SyncChangeStatus::Applied,
+            Some(todo),
+            None,
+        )])
+    }
+}
+
+impl Responder for SyncPushResultSchema {
+    type Body = BoxBody;
+
+    fn respond_to(self, _: &actix_web::HttpRequest) -> actix_web::HttpResponse<Self::Body> {
+        actix_web::HttpResponse::Ok().json(self)
+    }
+}
+
+impl Responder for SyncSchema {
+    type Body = BoxBody;
+
+    fn respond_to(self, _: &actix_web::HttpRequest) -> actix_web::HttpResponse<Self::Body> {
+        actix_web::HttpResponse::Ok().json(self)
+    }
+}
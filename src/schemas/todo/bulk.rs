@@ -0,0 +1,160 @@
+use actix_web::{body::BoxBody, Responder};
+use entity::todo::Status as TodoStatus;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use crate::api::todo::queries::TodoFilters;
+
+use super::TodoSchema;
+
+/// The action of the bulk operation
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum BulkAction {
+    /// Set the status of the selected todos
+    SetStatus,
+    /// Delete the selected todos
+    Delete,
+}
+
+/// The result status of a single todo in the bulk operation
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum BulkItemStatus {
+    /// The action is applied (or would be applied in dry run)
+    Applied,
+    /// The todo is already in the requested state
+    Unchanged,
+    /// There is no todo with the given uuid
+    NotFound,
+    /// The action is rejected, see the message
+    Rejected,
+}
+
+/// The schema of the bulk operation, select todos by uuids or by filters, and apply one action on them
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct BulkTodoSchema {
+    /// The uuids of the todos, can't be used with `filter`
+    /// Note: At most 100 uuids
+    #[schema(value_type = Option<Vec<String>>, example = json!(["a8bfed8d-4f8b-4150-8ace-3f8916609eba"]))]
+    pub uuids: Option<Vec<Uuid>>,
+    /// Select the todos by the list filters (`status`, `title`, `order_by`, `order`, `offset` and `limit`), can't be used with `uuids`
+    /// Note: The filter should select at most 100 todos, the `limit` can't be more than 100
+    #[schema(value_type = Option<Object>, example = json!({"status": "pending"}))]
+    pub filter: Option<TodoFilters>,
+    /// The action to apply on the selected todos (`set_status` or `delete`)
+    #[schema(value_type = String, example = "set_status")]
+    pub action: BulkAction,
+    /// The new status of the todos, required for `set_status` action
+    #[schema(value_type = Option<String>, example = "completed")]
+    pub status: Option<TodoStatus>,
+    /// Report what would change without applying it (default: `false`)
+    #[serde(default)]
+    #[schema(example = false)]
+    pub dry_run: bool,
+}
+
+/// The result of a single todo in the bulk operation
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct BulkTodoItemSchema {
+    /// The uuid of the todo
+    #[schema(value_type = String, example = "a8bfed8d-4f8b-4150-8ace-3f8916609eba")]
+    pub uuid: Uuid,
+    /// The result status (`applied`, `unchanged`, `not_found` or `rejected`)
+    #[schema(value_type = String, example = "applied")]
+    pub status: BulkItemStatus,
+    /// The todo after applying the action, or before deleting it
+    /// Note: Will return `null` if the todo is not found
+    pub todo: Option<TodoSchema>,
+    /// Why the action is rejected
+    /// Note: Will return `null` if the action is not rejected
+    #[schema(example = "The todo title is empty")]
+    pub message: Option<String>,
+}
+
+/// The result of the bulk operation
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct BulkTodoResultSchema {
+    /// Whether if the changes are not applied
+    #[schema(example = false)]
+    pub dry_run: bool,
+    /// The number of applied todos
+    #[schema(example = "1")]
+    pub applied: u64,
+    /// The result of each selected todo
+    pub results: Vec<BulkTodoItemSchema>,
+}
+
+impl BulkTodoItemSchema {
+    /// Create a new bulk item result
+    pub fn new(
+        uuid: Uuid,
+        status: BulkItemStatus,
+        todo: Option<TodoSchema>,
+        message: Option<String>,
+    ) -> Self {
+        Self {
+            uuid,
+            status,
+            todo,
+            message,
+        }
+    }
+}
+
+impl BulkTodoResultSchema {
+    /// Create a new bulk result
+    pub fn new(dry_run: bool, results: Vec<BulkTodoItemSchema>) -> Self {
+        Self {
+            dry_run,
+            applied: results
+                .iter()
+                .filter(|result| result.status == BulkItemStatus::Applied)
+                .count() as u64,
+            results,
+        }
+    }
+}
+
+impl Default for BulkTodoSchema {
+    fn default() -> Self {
+        Self {
+            uuids: None,
+            filter: Some(TodoFilters {
+                status: Some(TodoStatus::Pending),
+                title: None,
+                order_by: None,
+                order: None,
+                offset: None,
+                limit: None,
+            }),
+            action: BulkAction::SetStatus,
+            status: Some(TodoStatus::Completed),
+            dry_run: false,
+        }
+    }
+}
+
+impl Default for BulkTodoResultSchema {
+    fn default() -> Self {
+        let todo = TodoSchema::default();
+        Self::new(
+            false,
+            vec![BulkTodoItemSchema::new(
+                todo.uuid,
+                BulkItemStatus::Applied,
+                Some(todo),
+                None,
+            )],
+        )
+    }
+}
+
+impl Responder for BulkTodoResultSchema {
+    type Body = BoxBody;
+
+    fn respond_to(self, _: &actix_web::HttpRequest) -> actix_web::HttpResponse<Self::Body> {
+        actix_web::HttpResponse::Ok().json(self)
+    }
+}
@@ -0,0 +1,79 @@
+use entity::todo_history::{Action as HistoryAction, Model as HistoryModel};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use super::TodoSchema;
+use crate::errors::{ErrorTrait, Result as ApiResult};
+
+/// The type of a todo event
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+pub enum TodoEventType {
+    /// The todo is created
+    #[serde(rename = "todo.created")]
+    Created,
+    /// The todo is updated or restored from the trash
+    #[serde(rename = "todo.updated")]
+    Updated,
+    /// The todo is moved to the trash
+    #[serde(rename = "todo.deleted")]
+    Deleted,
+}
+
+/// A change event of a todo
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct TodoEventSchema {
+    /// The id of the event, the events are ordered by it
+    #[schema(example = "1")]
+    pub id: u32,
+    /// The type of the event (`todo.created`, `todo.updated` or `todo.deleted`)
+    #[schema(value_type = String, example = "todo.updated")]
+    pub event: TodoEventType,
+    /// The todo after the change
+    pub todo: TodoSchema,
+}
+
+impl TodoEventType {
+    /// All the event types
+    pub const ALL: [Self; 3] = [Self::Created, Self::Updated, Self::Deleted];
+
+    /// Convert the event type to string
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Created => "todo.created",
+            Self::Updated => "todo.updated",
+            Self::Deleted => "todo.deleted",
+        }
+    }
+}
+
+impl From<&HistoryAction> for TodoEventType {
+    fn from(action: &HistoryAction) -> Self {
+        match action {
+            HistoryAction::Created => Self::Created,
+            HistoryAction::Updated | HistoryAction::Restored => Self::Updated,
+            HistoryAction::Deleted => Self::Deleted,
+        }
+    }
+}
+
+impl TodoEventSchema {
+    /// Create a new event from the history entry, the todo is its snapshot
+    pub fn new(history: &HistoryModel) -> ApiResult<Self> {
+        Ok(Self {
+            id: history.id,
+            event: TodoEventType::from(&history.action),
+            todo: serde_json::from_str(&history.snapshot)
+                .server_err("The todo history is invalid")?,
+        })
+    }
+}
+
+impl Default for TodoEventSchema {
+    fn default() -> Self {
+        Self {
+            id: 1,
+            event: TodoEventType::Updated,
+            todo: TodoSchema::default(),
+        }
+    }
+}
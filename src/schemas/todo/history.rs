@@ -0,0 +1,70 @@
+use actix_web::{body::BoxBody, Responder};
+use entity::todo_history::{Action as HistoryAction, Model as HistoryModel};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use utoipa::ToSchema;
+
+/// A single change of a todo
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct TodoHistorySchema {
+    /// The id of the change, the changes are ordered by it
+    #[schema(example = "1")]
+    pub id: u32,
+    /// The action that changed the todo (`created`, `updated`, `deleted` or `restored`)
+    #[schema(value_type = String, example = "updated")]
+    pub action: HistoryAction,
+    /// The username of the user that made the change
+    #[schema(example = "Awiteb")]
+    pub actor: String,
+    /// The changed fields with their old and new values
+    #[schema(value_type = Object, example = json!({"status": {"old": "pending", "new": "cancelled"}}))]
+    pub changes: Value,
+    /// The id of the request that made the change, taken from the `X-Request-Id` header or generated
+    #[schema(example = "4f9a1c52-3b1e-4b0f-8f5e-0d7c3c1f2a6b")]
+    pub request_id: String,
+    /// The time of the change (Unix timestamp)
+    #[schema(example = "1620000000")]
+    pub created_at: i64,
+}
+
+/// The history of a todo, the oldest change first
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema, Default)]
+pub struct TodoHistoryListSchema {
+    /// The changes of the todo
+    pub data: Vec<TodoHistorySchema>,
+}
+
+impl TodoHistorySchema {
+    /// Create a new history schema from the history entry and the actor username
+    pub fn new(history: HistoryModel, actor: String) -> Self {
+        Self {
+            id: history.id,
+            action: history.action,
+            actor,
+            changes: serde_json::from_str(&history.changes).unwrap_or_default(),
+            request_id: history.request_id,
+            created_at: history.created_at,
+        }
+    }
+}
+
+impl Default for TodoHistorySchema {
+    fn default() -> Self {
+        Self {
+            id: 1,
+            action: HistoryAction::Updated,
+            actor: "Awiteb".to_owned(),
+            changes: json!({"status": {"old": "pending", "new": "cancelled"}}),
+            request_id: "4f9a1c52-3b1e-4b0f-8f5e-0d7c3c1f2a6b".to_owned(),
+            created_at: 1620000000,
+        }
+    }
+}
+
+impl Responder for TodoHistoryListSchema {
+    type Body = BoxBody;
+
+    fn respond_to(self, _: &actix_web::HttpRequest) -> actix_web::HttpResponse<Self::Body> {
+        actix_web::HttpResponse::Ok().json(self)
+    }
+}
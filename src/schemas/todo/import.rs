@@ -0,0 +1,56 @@
+use actix_web::{body::BoxBody, Responder};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use crate::schemas::message::MessageSchema;
+
+/// Why a row of the imported file is not imported
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct ImportErrorSchema {
+    /// The number of the row, starts from 1 (the CSV header row is not counted)
+    #[schema(example = "3")]
+    pub row: u64,
+    /// The error, the same error of creating the todo
+    pub error: MessageSchema,
+}
+
+/// The result of importing the todos
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct ImportResultSchema {
+    /// The number of the imported todos
+    #[schema(example = "41")]
+    pub imported: u64,
+    /// The number of the todos that are moved to the trash, always `0` in `merge` mode
+    #[schema(example = "0")]
+    pub trashed: u64,
+    /// The rows that are not imported
+    pub errors: Vec<ImportErrorSchema>,
+}
+
+impl ImportErrorSchema {
+    /// Create a new import error
+    pub fn new(row: u64, error: MessageSchema) -> Self {
+        Self { row, error }
+    }
+}
+
+impl Default for ImportResultSchema {
+    fn default() -> Self {
+        Self {
+            imported: 41,
+            trashed: 0,
+            errors: vec![ImportErrorSchema::new(
+                3,
+                MessageSchema::new(400, "The todo `Todo title` is already exists"),
+            )],
+        }
+    }
+}
+
+impl Responder for ImportResultSchema {
+    type Body = BoxBody;
+
+    fn respond_to(self, _: &actix_web::HttpRequest) -> actix_web::HttpResponse<Self::Body> {
+        actix_web::HttpResponse::Ok().json(self)
+    }
+}
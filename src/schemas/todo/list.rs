@@ -43,7 +43,7 @@ pub struct TodoListMetaSchema {
 pub struct TodoListSchema {
     /// The list of todos
     #[schema(
-        example = "[{\"uuid\": \"a8bfed8d-4f8b-4150-8ace-3f8916609eba\", \"title\": \"Todo title\", \"status\": \"completed\", \"created_at\": 1620000000, \"updated_at\": 1620000000}]"
+        example = "[{\"uuid\": \"a8bfed8d-4f8b-4150-8ace-3f8916609eba\", \"title\": \"Todo title\", \"status\": \"completed\", \"created_at\": 1620000000, \"updated_at\": 1620000000, \"completed_at\": 1620000000, \"version\": 1, \"deleted_at\": null, \"position\": \"V\"}]"
     )]
     pub data: Vec<TodoSchema>,
     /// The meta data of the list
@@ -1,9 +1,23 @@
+mod bulk;
 mod content;
+mod event;
+mod history;
+mod import;
 mod list;
+mod move_todo;
+mod patch;
+mod stats;
 mod update;
 
-use actix_web::{body::BoxBody, Responder};
-pub use {content::*, list::*, update::*};
+use actix_web::{
+    body::BoxBody,
+    http::header::{ETag, EntityTag},
+    Responder,
+};
+pub use {
+    bulk::*, content::*, event::*, history::*, import::*, list::*, move_todo::*, patch::*,
+    stats::*, update::*,
+};
 
 use entity::todo::Status as TodoStatus;
 use serde::{Deserialize, Serialize};
@@ -29,16 +43,38 @@ pub struct TodoSchema {
     /// If the todo is not updated, this value is equal to `created_at`
     #[schema(example = "1620000000")]
     pub updated_at: i64,
+    /// The completed time of the todo (Unix timestamp)
+    /// If the todo is not completed, this value is `null`
+    #[schema(example = "1620000000")]
+    pub completed_at: Option<i64>,
+    /// The version of the todo, incremented on every update. It's also the `ETag` of the todo
+    /// Note: Send it in `If-Match` header or `version` field to prevent overwriting others changes
+    #[schema(example = "1")]
+    pub version: u32,
+    /// The time the todo is moved to the trash (Unix timestamp)
+    /// If the todo is not in the trash, this value is `null`
+    #[schema(example = "null")]
+    pub deleted_at: Option<i64>,
+    /// The rank of the todo in the manual order of the todos, the todos are ordered by comparing it as a string
+    /// Note: Use `POST /api/todos/{uuid}/move` to change it
+    #[schema(example = "V")]
+    #[serde(default)]
+    pub position: String,
 }
 
 impl TodoSchema {
     /// Create a new todo
+    #[allow(clippy::too_many_arguments)]
     pub fn new(
         uuid: Uuid,
         title: String,
         status: TodoStatus,
         created_at: i64,
         updated_at: i64,
+        completed_at: Option<i64>,
+        version: u32,
+        deleted_at: Option<i64>,
+        position: String,
     ) -> Self {
         Self {
             uuid,
@@ -46,8 +82,17 @@ impl TodoSchema {
             status,
             created_at,
             updated_at,
+            completed_at,
+            version,
+            deleted_at,
+            position,
         }
     }
+
+    /// Returns the strong entity tag of the todo, changes with every update
+    pub fn etag(&self) -> EntityTag {
+        EntityTag::new_strong(format!("{}-{}", self.uuid.simple(), self.version))
+    }
 }
 
 impl Default for TodoSchema {
@@ -58,6 +103,10 @@ impl Default for TodoSchema {
             TodoStatus::Completed,
             1620000000,
             1620000000,
+            Some(1620000000),
+            1,
+            None,
+            "V".to_string(),
         )
     }
 }
@@ -70,6 +119,10 @@ impl From<entity::todo::ActiveModel> for TodoSchema {
             todo.status.unwrap(),
             todo.created_at.unwrap(),
             todo.updated_at.unwrap(),
+            todo.completed_at.unwrap(),
+            todo.version.unwrap(),
+            todo.deleted_at.unwrap(),
+            todo.position.unwrap(),
         )
     }
 }
@@ -82,6 +135,10 @@ impl From<entity::todo::Model> for TodoSchema {
             todo.status,
             todo.created_at,
             todo.updated_at,
+            todo.completed_at,
+            todo.version,
+            todo.deleted_at,
+            todo.position,
         )
     }
 }
@@ -90,6 +147,8 @@ impl Responder for TodoSchema {
     type Body = BoxBody;
 
     fn respond_to(self, _: &actix_web::HttpRequest) -> actix_web::HttpResponse<Self::Body> {
-        actix_web::HttpResponse::Ok().json(self)
+        actix_web::HttpResponse::Ok()
+            .insert_header(ETag(self.etag()))
+            .json(self)
     }
 }
@@ -0,0 +1,28 @@
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+/// The schema used to move a todo between other todos, at least one of `after` and `before` is required
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct MoveTodoSchema {
+    /// The uuid of the todo that will be right before the moved todo, can be `null` to move it to the start of the list
+    #[schema(value_type = Option<String>, example = "a8bfed8d-4f8b-4150-8ace-3f8916609eba")]
+    pub after: Option<Uuid>,
+    /// The uuid of the todo that will be right after the moved todo, can be `null` to move it to the end of the list
+    #[schema(value_type = Option<String>, example = "null")]
+    pub before: Option<Uuid>,
+    /// The version of the todo that the move is based on, can be `null` to skip the version check
+    /// Note: If the version is not the current version, will return `412 Precondition Failed` with the current todo
+    #[schema(example = "1")]
+    pub version: Option<u32>,
+}
+
+impl Default for MoveTodoSchema {
+    fn default() -> Self {
+        Self {
+            after: Some(Uuid::new_v4()),
+            before: None,
+            version: Some(1),
+        }
+    }
+}
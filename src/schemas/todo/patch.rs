@@ -0,0 +1,81 @@
+use actix_web::{HttpMessage, HttpRequest};
+use json_patch::PatchOperation;
+use serde_json::Value;
+
+use crate::errors::{Error as ApiError, ErrorTrait, Result as ApiResult};
+
+/// The content type of JSON Merge Patch (RFC 7396)
+pub const MERGE_PATCH_CONTENT_TYPE: &str = "application/merge-patch+json";
+/// The content type of JSON Patch (RFC 6902)
+pub const JSON_PATCH_CONTENT_TYPE: &str = "application/json-patch+json";
+
+/// A patch of a todo, the patch is applied on the todo JSON (`TodoSchema`)
+#[derive(Debug, Clone)]
+pub enum TodoPatch {
+    /// JSON Merge Patch (RFC 7396), `null` removes the field
+    Merge(Value),
+    /// JSON Patch (RFC 6902), a list of operations
+    Json(json_patch::Patch),
+}
+
+impl TodoPatch {
+    /// Parse the patch from the request body, the patch format is chosen by the request content type.
+    /// If the content type is not supported returns an error 415
+    pub fn from_request(req: &HttpRequest, body: &[u8]) -> ApiResult<Self> {
+        let content_type = req.mime_type().ok().flatten();
+        let content_type = content_type.as_ref().map(|mime| mime.essence_str());
+        if !matches!(
+            content_type,
+            Some(MERGE_PATCH_CONTENT_TYPE | JSON_PATCH_CONTENT_TYPE)
+        ) {
+            let content_type = content_type.map_or_else(
+                || "The content type is missing".to_owned(),
+                |content_type| format!("The content type `{content_type}` is not supported"),
+            );
+            return Err(ApiError::UnsupportedMediaType(format!(
+                "{content_type}, the accepted types are: {MERGE_PATCH_CONTENT_TYPE}, {JSON_PATCH_CONTENT_TYPE}"
+            )));
+        }
+        let value: Value = serde_json::from_slice(body)
+            .map_err(|err| ApiError::BadRequest(format!("The request body is invalid: {err}")))?;
+        if content_type == Some(MERGE_PATCH_CONTENT_TYPE) {
+            Ok(Self::Merge(value))
+        } else {
+            json_patch::from_value(value)
+                .map(Self::Json)
+                .map_err(|err| ApiError::BadRequest(format!("The JSON Patch is invalid: {err}")))
+        }
+    }
+
+    /// Apply the patch on the todo JSON, if a `test` operation fails returns an error 409
+    pub fn apply(&self, doc: &mut Value) -> ApiResult<()> {
+        match self {
+            Self::Merge(patch) => {
+                patch
+                    .as_object()
+                    .bad_request_err("The JSON Merge Patch must be an object")?;
+                json_patch::merge(doc, patch);
+                Ok(())
+            }
+            Self::Json(patch) => json_patch::patch(doc, patch).map_err(|err| match err {
+                json_patch::PatchError::TestFailed => {
+                    ApiError::Conflict("The JSON Patch `test` operation failed".to_owned())
+                }
+                json_patch::PatchError::InvalidPointer => {
+                    ApiError::BadRequest("The JSON Patch has an invalid path".to_owned())
+                }
+            }),
+        }
+    }
+
+    /// Returns `true` if a JSON Patch `test` operation expects another version than the todo JSON version
+    pub fn tests_other_version(&self, doc: &Value) -> bool {
+        match self {
+            Self::Json(patch) => patch.0.iter().any(|operation| {
+                matches!(operation, PatchOperation::Test(test)
+                    if test.path == "/version" && Some(&test.value) != doc.get("version"))
+            }),
+            Self::Merge(_) => false,
+        }
+    }
+}
@@ -0,0 +1,170 @@
+use actix_web::{body::BoxBody, Responder};
+use entity::todo::{Model as TodoModel, Status as TodoStatus};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use crate::api::todo::queries::{StatsFilters, StatsPeriod};
+
+/// The number of todos for each status
+#[derive(ToSchema, Serialize, Deserialize, Debug, Clone, Default)]
+pub struct TodoStatusCountSchema {
+    /// The number of completed todos
+    #[schema(example = "1")]
+    pub completed: u64,
+    /// The number of pending todos
+    #[schema(example = "1")]
+    pub pending: u64,
+    /// The number of progress todos
+    #[schema(example = "1")]
+    pub progress: u64,
+    /// The number of cancelled todos
+    #[schema(example = "1")]
+    pub cancelled: u64,
+}
+
+/// The number of created and completed todos in a period
+#[derive(ToSchema, Serialize, Deserialize, Debug, Clone)]
+pub struct TodoTrendSchema {
+    /// The start of the period (Unix timestamp)
+    #[schema(example = "1620000000")]
+    pub start: i64,
+    /// The end of the period (Unix timestamp), not included in the period
+    #[schema(example = "1620086400")]
+    pub end: i64,
+    /// The number of todos created in the period
+    #[schema(example = "2")]
+    pub created: u64,
+    /// The number of todos completed in the period
+    #[schema(example = "1")]
+    pub completed: u64,
+}
+
+/// The statistics of the user todos
+#[derive(ToSchema, Serialize, Deserialize, Debug, Clone)]
+pub struct TodoStatsSchema {
+    /// The total number of todos
+    #[schema(example = "4")]
+    pub total: u64,
+    /// The maximum number of todos a user can have
+    #[schema(example = "500")]
+    pub max_todos: u64,
+    /// The number of todos for each status
+    pub status: TodoStatusCountSchema,
+    /// The start of the trend range (Unix timestamp)
+    #[schema(example = "1620000000")]
+    pub from: i64,
+    /// The end of the trend range (Unix timestamp)
+    #[schema(example = "1620050000")]
+    pub to: i64,
+    /// The period of the trend
+    #[schema(value_type = String, example = "day")]
+    pub period: StatsPeriod,
+    /// The created and completed todos for each period in the range, ordered by the period start
+    pub trend: Vec<TodoTrendSchema>,
+    /// The average time in seconds from creating a todo to completing it,
+    /// for the todos completed in the range
+    /// Note: Will return `null` if there is no completed todos in the range
+    #[schema(example = "3600")]
+    pub average_completion_time: Option<i64>,
+}
+
+impl TodoStatusCountSchema {
+    /// Increment the counter of the given status
+    pub fn increment(&mut self, status: &TodoStatus) {
+        match status {
+            TodoStatus::Completed => self.completed += 1,
+            TodoStatus::Pending => self.pending += 1,
+            TodoStatus::Progress => self.progress += 1,
+            TodoStatus::Cancelled => self.cancelled += 1,
+        }
+    }
+}
+
+impl TodoStatsSchema {
+    /// Create the statistics of the given todos, the range should be validated
+    pub fn new(
+        todos: &[TodoModel],
+        params: &StatsFilters,
+        (from, to): (i64, i64),
+        max_todos: u64,
+    ) -> Self {
+        let period = params.period();
+        let first_period = period.start_of(from);
+        let mut trend: Vec<_> = (0..=(period.start_of(to) - first_period) / period.seconds())
+            .map(|idx| {
+                let start = first_period + idx * period.seconds();
+                TodoTrendSchema {
+                    start,
+                    end: start + period.seconds(),
+                    created: 0,
+                    completed: 0,
+                }
+            })
+            .collect();
+        let trend_idx = |timestamp: i64| {
+            (from..=to)
+                .contains(&timestamp)
+                .then(|| ((timestamp - first_period) / period.seconds()) as usize)
+        };
+
+        let mut status = TodoStatusCountSchema::default();
+        let mut completion_times = Vec::new();
+        for todo in todos {
+            status.increment(&todo.status);
+            if let Some(idx) = trend_idx(todo.created_at) {
+                trend[idx].created += 1;
+            }
+            if let Some(completed_at) = todo.completed_at {
+                if let Some(idx) = trend_idx(completed_at) {
+                    trend[idx].completed += 1;
+                    completion_times.push(completed_at - todo.created_at);
+                }
+            }
+        }
+
+        Self {
+            total: todos.len() as u64,
+            max_todos,
+            status,
+            from,
+            to,
+            period,
+            trend,
+            average_completion_time: (!completion_times.is_empty())
+                .then(|| completion_times.iter().sum::<i64>() / completion_times.len() as i64),
+        }
+    }
+}
+
+impl Default for TodoStatsSchema {
+    fn default() -> Self {
+        Self {
+            total: 4,
+            max_todos: 500,
+            status: TodoStatusCountSchema {
+                completed: 1,
+                pending: 1,
+                progress: 1,
+                cancelled: 1,
+            },
+            from: 1620000000,
+            to: 1620050000,
+            period: StatsPeriod::Day,
+            trend: vec![TodoTrendSchema {
+                start: 1620000000,
+                end: 1620086400,
+                created: 4,
+                completed: 1,
+            }],
+            average_completion_time: Some(3600),
+        }
+    }
+}
+
+impl Responder for TodoStatsSchema {
+    type Body = BoxBody;
+
+    fn respond_to(self, _: &actix_web::HttpRequest) -> actix_web::HttpResponse<Self::Body> {
+        actix_web::HttpResponse::Ok().json(self)
+    }
+}
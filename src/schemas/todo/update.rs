@@ -11,6 +11,10 @@ pub struct UpdateTodoSchema {
     /// The status of the todo, can be `null` to keep the original status
     #[schema(value_type = Option<String>, example = "completed")]
     pub status: Option<TodoStatus>,
+    /// The version of the todo that the changes are based on, can be `null` to skip the version check
+    /// Note: If the version is not the current version, will return `412 Precondition Failed` with the current todo
+    #[schema(example = "1")]
+    pub version: Option<u32>,
 }
 
 impl Default for UpdateTodoSchema {
@@ -18,6 +22,7 @@ impl Default for UpdateTodoSchema {
         Self {
             title: Some("Todo title".to_string()),
             status: None,
+            version: Some(1),
         }
     }
 }
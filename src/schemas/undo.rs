@@ -0,0 +1,48 @@
+use actix_web::{body::BoxBody, Responder};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use super::todo::TodoSchema;
+
+/// A single undone operation
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct UndoOperationSchema {
+    /// The request id of the undone operation
+    #[schema(example = "4f9a1c52-3b1e-4b0f-8f5e-0d7c3c1f2a6b")]
+    pub request_id: String,
+    /// The todos after undoing the operation
+    pub todos: Vec<TodoSchema>,
+}
+
+/// The result of the undo, the latest undone operation first
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct UndoResultSchema {
+    /// The undone operations
+    pub operations: Vec<UndoOperationSchema>,
+}
+
+impl UndoOperationSchema {
+    /// Create a new undone operation
+    pub fn new(request_id: String, todos: Vec<TodoSchema>) -> Self {
+        Self { request_id, todos }
+    }
+}
+
+impl Default for UndoResultSchema {
+    fn default() -> Self {
+        Self {
+            operations: vec![UndoOperationSchema::new(
+                "4f9a1c52-3b1e-4b0f-8f5e-0d7c3c1f2a6b".to_owned(),
+                vec![TodoSchema::default()],
+            )],
+        }
+    }
+}
+
+impl Responder for UndoResultSchema {
+    type Body = BoxBody;
+
+    fn respond_to(self, _: &actix_web::HttpRequest) -> actix_web::HttpResponse<Self::Body> {
+        actix_web::HttpResponse::Ok().json(self)
+    }
+}
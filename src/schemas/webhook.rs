@@ -0,0 +1,219 @@
+use actix_web::{body::BoxBody, Responder};
+use entity::webhook::Model as WebhookModel;
+use entity::webhook_delivery::{Model as DeliveryModel, Status as DeliveryStatus};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use super::todo::TodoEventType;
+
+/// The webhook to create
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct CreateWebhookSchema {
+    /// The URL that the events are posted to, must be `http` or `https`
+    #[schema(example = "https://example.com/hooks/todos")]
+    pub url: String,
+    /// The events that are sent to the webhook (default: all the events)
+    #[schema(value_type = Option<Vec<String>>, example = json!(["todo.created", "todo.deleted"]))]
+    pub events: Option<Vec<TodoEventType>>,
+}
+
+/// A webhook
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct WebhookSchema {
+    /// The uuid of the webhook
+    #[schema(value_type = String, example = "3f4b1b5e-5d0a-4e0b-9c8e-2e6c7f0f9a1d")]
+    pub uuid: Uuid,
+    /// The URL that the events are posted to
+    #[schema(example = "https://example.com/hooks/todos")]
+    pub url: String,
+    /// The events that are sent to the webhook
+    #[schema(value_type = Vec<String>, example = json!(["todo.created", "todo.deleted"]))]
+    pub events: Vec<TodoEventType>,
+    /// The secret that the payloads are signed with, use it to verify the `X-Oxide-Signature` header
+    /// Note: Will return `null` except in the creation response, store it
+    #[schema(example = "5c1e0d7f9b3a4e2d8f6c1b0a9e8d7c6b5a4f3e2d1c0b9a8f7e6d5c4b3a2f1e0d")]
+    pub secret: Option<String>,
+    /// The created time of the webhook (Unix timestamp)
+    #[schema(example = "1620000000")]
+    pub created_at: i64,
+}
+
+/// The webhooks of the user
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct WebhookListSchema {
+    /// The webhooks, the oldest first
+    pub data: Vec<WebhookSchema>,
+}
+
+/// A delivery of an event to a webhook
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct WebhookDeliverySchema {
+    /// The uuid of the delivery
+    #[schema(value_type = String, example = "9b2f4c3e-1a7d-4f5b-8e6c-0d9a8b7c6e5f")]
+    pub uuid: Uuid,
+    /// The id of the delivered event
+    #[schema(example = "42")]
+    pub event_id: u32,
+    /// The type of the delivered event
+    #[schema(value_type = String, example = "todo.updated")]
+    pub event: String,
+    /// The status of the delivery (`pending`, `succeeded` or `failed`)
+    /// Note: The `failed` deliveries are the dead-letter list, they will not be retried unless redelivered
+    #[schema(value_type = String, example = "succeeded")]
+    pub status: DeliveryStatus,
+    /// The number of the attempts
+    #[schema(example = "1")]
+    pub attempts: u32,
+    /// The time of the next attempt (Unix timestamp)
+    /// Note: Will return `null` if the delivery is not pending
+    #[schema(example = "null")]
+    pub next_attempt_at: Option<i64>,
+    /// The status code of the last attempt response
+    /// Note: Will return `null` if there is no response
+    #[schema(example = "200")]
+    pub response_status: Option<u16>,
+    /// The error of the last attempt
+    /// Note: Will return `null` if the last attempt is succeeded
+    #[schema(example = "null")]
+    pub error: Option<String>,
+    /// The created time of the delivery (Unix timestamp)
+    #[schema(example = "1620000000")]
+    pub created_at: i64,
+    /// The time of the successful attempt (Unix timestamp)
+    /// Note: Will return `null` if the delivery is not succeeded
+    #[schema(example = "1620000001")]
+    pub delivered_at: Option<i64>,
+}
+
+/// The recent deliveries of a webhook
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct WebhookDeliveryListSchema {
+    /// The deliveries, the newest first
+    pub data: Vec<WebhookDeliverySchema>,
+}
+
+impl WebhookSchema {
+    /// Create a new webhook schema from the webhook model, with its secret if `with_secret` is `true`
+    pub fn new(webhook: WebhookModel, with_secret: bool) -> Self {
+        Self {
+            uuid: webhook.uuid,
+            events: webhook
+                .events
+                .split(',')
+                .filter_map(|event| serde_json::from_value(event.into()).ok())
+                .collect(),
+            url: webhook.url,
+            secret: with_secret.then_some(webhook.secret),
+            created_at: webhook.created_at,
+        }
+    }
+}
+
+impl From<DeliveryModel> for WebhookDeliverySchema {
+    fn from(delivery: DeliveryModel) -> Self {
+        Self {
+            uuid: delivery.uuid,
+            event_id: delivery.event_id,
+            event: delivery.event,
+            status: delivery.status,
+            attempts: delivery.attempts,
+            next_attempt_at: delivery.next_attempt_at,
+            response_status: delivery.response_status,
+            error: delivery.error,
+            created_at: delivery.created_at,
+            delivered_at: delivery.delivered_at,
+        }
+    }
+}
+
+impl Default for CreateWebhookSchema {
+    fn default() -> Self {
+        Self {
+            url: "https://example.com/hooks/todos".to_owned(),
+            events: Some(vec![TodoEventType::Created, TodoEventType::Deleted]),
+        }
+    }
+}
+
+impl Default for WebhookSchema {
+    fn default() -> Self {
+        Self {
+            uuid: Uuid::new_v4(),
+            url: "https://example.com/hooks/todos".to_owned(),
+            events: vec![TodoEventType::Created, TodoEventType::Deleted],
+            secret: Some(
+                "5c1e0d7f9b3a4e2d8f6c1b0a9e8d7c6b5a4f3e2d1c0b9a8f7e6d5c4b3a2f1e0d".to_owned(),
+            ),
+            created_at: 1620000000,
+        }
+    }
+}
+
+impl Default for WebhookListSchema {
+    fn default() -> Self {
+        Self {
+            data: vec![WebhookSchema {
+                secret: None,
+                ..Default::default()
+            }],
+        }
+    }
+}
+
+impl Default for WebhookDeliverySchema {
+    fn default() -> Self {
+        Self {
+            uuid: Uuid::new_v4(),
+            event_id: 42,
+            event: TodoEventType::Updated.as_str().to_owned(),
+            status: DeliveryStatus::Succeeded,
+            attempts: 1,
+            next_attempt_at: None,
+            response_status: Some(200),
+            error: None,
+            created_at: 1620000000,
+            delivered_at: Some(1620000001),
+        }
+    }
+}
+
+impl Default for WebhookDeliveryListSchema {
+    fn default() -> Self {
+        Self {
+            data: vec![WebhookDeliverySchema::default()],
+        }
+    }
+}
+
+impl Responder for WebhookSchema {
+    type Body = BoxBody;
+
+    fn respond_to(self, _: &actix_web::HttpRequest) -> actix_web::HttpResponse<Self::Body> {
+        actix_web::HttpResponse::Ok().json(self)
+    }
+}
+
+impl Responder for WebhookListSchema {
+    type Body = BoxBody;
+
+    fn respond_to(self, _: &actix_web::HttpRequest) -> actix_web::HttpResponse<Self::Body> {
+        actix_web::HttpResponse::Ok().json(self)
+    }
+}
+
+impl Responder for WebhookDeliverySchema {
+    type Body = BoxBody;
+
+    fn respond_to(self, _: &actix_web::HttpRequest) -> actix_web::HttpResponse<Self::Body> {
+        actix_web::HttpResponse::Ok().json(self)
+    }
+}
+
+impl Responder for WebhookDeliveryListSchema {
+    type Body = BoxBody;
+
+    fn respond_to(self, _: &actix_web::HttpRequest) -> actix_web::HttpResponse<Self::Body> {
+        actix_web::HttpResponse::Ok().json(self)
+    }
+}
@@ -0,0 +1,40 @@
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use super::{
+    message::MessageSchema,
+    sync::SyncChangeSchema,
+    todo::{TodoEventSchema, TodoSchema},
+};
+
+/// A command sent by the client over the WebSocket
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct WsCommandSchema {
+    /// The correlation id of the command, the result of the command has the same id
+    #[schema(example = "1")]
+    pub id: Option<String>,
+    /// The change to apply, the `version` is optional
+    pub command: SyncChangeSchema,
+}
+
+/// A message sent by the server over the WebSocket, the `type` field is the message type
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum WsMessageSchema {
+    /// A change event of a todo, the same event of the events stream
+    Event(TodoEventSchema),
+    /// The command is applied, contains the todo after the change
+    Result {
+        /// The correlation id of the command
+        id: Option<String>,
+        /// The todo after the change
+        todo: TodoSchema,
+    },
+    /// The command or the message is invalid, or the command is rejected
+    Error {
+        /// The correlation id of the command, `null` if the message is not a valid command
+        id: Option<String>,
+        /// The error, the same error of the REST endpoints
+        error: MessageSchema,
+    },
+}
@@ -0,0 +1,281 @@
+use std::time::Duration;
+
+use crate::grpc::proto::{
+    auth::{auth_client::AuthClient, Credentials, RevokeRequest},
+    todo::{
+        todos_client::TodosClient, CreateTodoRequest, DeleteTodoRequest, EventsRequest,
+        GetTodoRequest, ListTodosRequest, TodoEventType, TodoStatus, UpdateTodoRequest,
+    },
+};
+use crate::tests::init_test_pool;
+use actix_web::rt::{self, net::TcpListener, time};
+use entity::todo_history::{Column as HistoryColumn, Entity as HistoryEntity};
+use sea_orm::{ColumnTrait, EntityTrait, QueryFilter};
+use tokio_stream::wrappers::TcpListenerStream;
+use tonic::{transport::Channel, Code, Request};
+use uuid::Uuid;
+
+/// Start the gRPC server on a random port, returns a channel connected to it
+async fn grpc_channel() -> Channel {
+    let pool = init_test_pool().await;
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    rt::spawn(crate::grpc::router(pool).serve_with_incoming(TcpListenerStream::new(listener)));
+    Channel::from_shared(format!("http://{addr}"))
+        .unwrap()
+        .connect()
+        .await
+        .unwrap()
+}
+
+/// Add the token to the request `authorization` metadata
+fn authorized<T>(message: T, token: &str) -> Request<T> {
+    let mut request = Request::new(message);
+    request
+        .metadata_mut()
+        .insert("authorization", format!("Bearer {token}").parse().unwrap());
+    request
+}
+
+async fn login(channel: &Channel) -> String {
+    AuthClient::new(channel.clone())
+        .login(Credentials {
+            username: "testusername1".to_owned(),
+            password: "testpassword".to_owned(),
+        })
+        .await
+        .unwrap()
+        .into_inner()
+        .token
+}
+
+#[actix_web::test]
+#[serial_test::serial]
+async fn grpc_auth() {
+    let channel = grpc_channel().await;
+    let mut client = AuthClient::new(channel);
+    let credentials = Credentials {
+        username: format!("grpc_{}", Uuid::new_v4().simple()),
+        password: "grpcpassword".to_owned(),
+    };
+
+    let user = client
+        .register(credentials.clone())
+        .await
+        .unwrap()
+        .into_inner();
+    assert_eq!(user.username, credentials.username);
+    let err = client.register(credentials.clone()).await.unwrap_err();
+    assert_eq!(err.code(), Code::InvalidArgument);
+
+    let err = client
+        .login(Credentials {
+            password: "wrongpassword".to_owned(),
+            ..credentials.clone()
+        })
+        .await
+        .unwrap_err();
+    assert_eq!(err.code(), Code::InvalidArgument);
+    let token = client.login(credentials).await.unwrap().into_inner().token;
+
+    // Revoking the token disables the previous tokens
+    time::sleep(Duration::from_secs(1)).await;
+    let new_token = client
+        .revoke(authorized(RevokeRequest {}, &token))
+        .await
+        .unwrap()
+        .into_inner()
+        .token;
+    assert_ne!(new_token, token);
+    let err = client
+        .revoke(authorized(RevokeRequest {}, &token))
+        .await
+        .unwrap_err();
+    assert_eq!(err.code(), Code::PermissionDenied);
+}
+
+#[actix_web::test]
+#[serial_test::serial]
+async fn grpc_todo_operations() {
+    let channel = grpc_channel().await;
+    let token = login(&channel).await;
+    let mut client = TodosClient::new(channel);
+    let title = format!("grpc_todo_{}", Uuid::new_v4().simple());
+
+    let todo = client
+        .create(authorized(
+            CreateTodoRequest {
+                title: title.clone(),
+                status: TodoStatus::Unspecified.into(),
+            },
+            &token,
+        ))
+        .await
+        .unwrap()
+        .into_inner();
+    assert_eq!(todo.title, title);
+    assert_eq!(todo.status(), TodoStatus::Pending);
+
+    let found = client
+        .get(authorized(
+            GetTodoRequest {
+                uuid: todo.uuid.clone(),
+            },
+            &token,
+        ))
+        .await
+        .unwrap()
+        .into_inner();
+    assert_eq!(found, todo);
+
+    let list = client
+        .list(authorized(
+            ListTodosRequest {
+                title: Some(title.clone()),
+                status: Some(TodoStatus::Pending.into()),
+                ..Default::default()
+            },
+            &token,
+        ))
+        .await
+        .unwrap()
+        .into_inner();
+    assert_eq!(list.total, 1);
+    assert_eq!(list.todos, vec![todo.clone()]);
+
+    let update = UpdateTodoRequest {
+        uuid: todo.uuid.clone(),
+        title: None,
+        status: Some(TodoStatus::Completed.into()),
+        version: Some(todo.version + 1),
+    };
+    let err = client
+        .update(authorized(update.clone(), &token))
+        .await
+        .unwrap_err();
+    assert_eq!(err.code(), Code::FailedPrecondition);
+
+    let updated = client
+        .update(authorized(
+            UpdateTodoRequest {
+                version: Some(todo.version),
+                ..update
+            },
+            &token,
+        ))
+        .await
+        .unwrap()
+        .into_inner();
+    assert_eq!(updated.status(), TodoStatus::Completed);
+    assert!(updated.completed_at.is_some());
+    assert_eq!(updated.version, todo.version + 1);
+
+    let deleted = client
+        .delete(authorized(
+            DeleteTodoRequest {
+                uuid: todo.uuid.clone(),
+                version: None,
+            },
+            &token,
+        ))
+        .await
+        .unwrap()
+        .into_inner();
+    assert!(deleted.deleted_at.is_some());
+    let err = client
+        .get(authorized(GetTodoRequest { uuid: todo.uuid }, &token))
+        .await
+        .unwrap_err();
+    assert_eq!(err.code(), Code::NotFound);
+
+    let err = client
+        .get(authorized(
+            GetTodoRequest {
+                uuid: "invalid".to_owned(),
+            },
+            &token,
+        ))
+        .await
+        .unwrap_err();
+    assert_eq!(err.code(), Code::InvalidArgument);
+}
+
+#[actix_web::test]
+#[serial_test::serial]
+async fn grpc_todo_request_id() {
+    let channel = grpc_channel().await;
+    let token = login(&channel).await;
+    let mut client = TodosClient::new(channel);
+
+    // The request ids longer than the maximum length are replaced with a generated one
+    for (request_id, kept) in [("grpc-request", true), (&*"x".repeat(65), false)] {
+        let mut request = authorized(
+            CreateTodoRequest {
+                title: format!("grpc_request_id_{}", Uuid::new_v4().simple()),
+                status: TodoStatus::Unspecified.into(),
+            },
+            &token,
+        );
+        request
+            .metadata_mut()
+            .insert("x-request-id", request_id.parse().unwrap());
+        let todo = client.create(request).await.unwrap().into_inner();
+        let history = HistoryEntity::find()
+            .filter(HistoryColumn::TodoUuid.eq(Uuid::parse_str(&todo.uuid).unwrap()))
+            .one(&init_test_pool().await)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(history.request_id == request_id, kept);
+        assert!(history.request_id.len() <= 64);
+    }
+}
+
+#[actix_web::test]
+#[serial_test::serial]
+async fn grpc_todo_unauthenticated() {
+    let channel = grpc_channel().await;
+    let mut client = TodosClient::new(channel);
+
+    let err = client.list(ListTodosRequest::default()).await.unwrap_err();
+    assert_eq!(err.code(), Code::Unauthenticated);
+    let err = client
+        .list(authorized(ListTodosRequest::default(), "invalid"))
+        .await
+        .unwrap_err();
+    assert_eq!(err.code(), Code::Unauthenticated);
+}
+
+#[actix_web::test]
+#[serial_test::serial]
+async fn grpc_todo_events() {
+    let channel = grpc_channel().await;
+    let token = login(&channel).await;
+    let mut client = TodosClient::new(channel);
+    let mut events = client
+        .events(authorized(EventsRequest { after: None }, &token))
+        .await
+        .unwrap()
+        .into_inner();
+
+    let title = format!("grpc_event_{}", Uuid::new_v4().simple());
+    client
+        .create(authorized(
+            CreateTodoRequest {
+                title: title.clone(),
+                status: TodoStatus::Progress.into(),
+            },
+            &token,
+        ))
+        .await
+        .unwrap();
+    let event = time::timeout(Duration::from_secs(10), events.message())
+        .await
+        .expect("Timeout while waiting for the event")
+        .unwrap()
+        .unwrap();
+    assert_eq!(event.event(), TodoEventType::Created);
+    let todo = event.todo.unwrap();
+    assert_eq!(todo.title, title);
+    assert_eq!(todo.status(), TodoStatus::Progress);
+}
@@ -1,5 +1,6 @@
 use migration::{Migrator, MigratorTrait};
 
+mod grpc;
 mod login;
 mod register;
 mod revoke;
@@ -40,6 +41,11 @@ pub fn check_content_type(res: &TestResponseType) {
     );
 }
 
+/// Parse the JSON body of the response
+pub async fn parse<T: serde::de::DeserializeOwned>(mut res: TestResponseType) -> T {
+    serde_json::from_slice(res.body().await.unwrap().to_vec().as_slice()).unwrap()
+}
+
 /// Check if the response content length is not 0
 pub fn check_content_length(res: &TestResponseType) {
     assert!(
@@ -0,0 +1,167 @@
+use crate::errors::Error as ApiError;
+use crate::schemas::todo::{BulkItemStatus, BulkTodoResultSchema, TodoListSchema, TodoSchema};
+use crate::schemas::user::UserSchema;
+use crate::tests::login::login_req;
+use crate::tests::todo::create_todo::create_todo_req;
+use crate::tests::todo::list_todo::list_todo_req;
+use crate::tests::{check_content_length, check_content_type, init_test_pool, TestResponseType};
+use actix_web::web::{self, JsonConfig};
+use actix_web::App;
+use entity::todo::Status as TodoStatus;
+use serde_json::{json, Value};
+use uuid::Uuid;
+
+pub async fn bulk_todo_req(payload: Value) -> TestResponseType {
+    let pool = init_test_pool().await;
+    let user: UserSchema = serde_json::from_slice(
+        login_req("testusername1".to_owned(), "testpassword".to_owned())
+            .await
+            .body()
+            .await
+            .unwrap()
+            .to_vec()
+            .as_slice(),
+    )
+    .unwrap();
+    let srv = actix_test::start(move || {
+        App::new()
+            .app_data(web::Data::new(pool.clone()))
+            .app_data(JsonConfig::default().error_handler(|err, _| ApiError::from(err).into()))
+            .service(web::scope("/todo").service(crate::api::todo::bulk::bulk))
+    });
+    srv.post("/todo/bulk")
+        .insert_header(("Authorization", format!("Bearer {}", user.token)))
+        .send_json(&payload)
+        .await
+        .unwrap()
+}
+
+async fn bulk_result(payload: Value) -> BulkTodoResultSchema {
+    let mut res = bulk_todo_req(payload).await;
+    check_content_type(&res);
+    check_content_length(&res);
+    assert_eq!(res.status(), 200);
+    serde_json::from_slice(res.body().await.unwrap().to_vec().as_slice()).unwrap()
+}
+
+async fn list_todos(params: &str) -> TodoListSchema {
+    serde_json::from_slice(
+        list_todo_req(params)
+            .await
+            .body()
+            .await
+            .unwrap()
+            .to_vec()
+            .as_slice(),
+    )
+    .unwrap()
+}
+
+#[rstest::rstest]
+#[case::without_selector(json!({"action": "delete"}))]
+#[case::with_both_selectors(json!({"action": "delete", "uuids": [], "filter": {}}))]
+#[case::without_status(json!({"action": "set_status", "filter": {}}))]
+#[case::invalid_action(json!({"action": "add_tags", "filter": {}}))]
+#[case::invalid_status(json!({"action": "set_status", "status": "done", "filter": {}}))]
+#[case::too_many_uuids(json!({"action": "delete", "uuids": vec![Uuid::new_v4(); 101]}))]
+#[case::too_large_limit(json!({"action": "delete", "filter": {"limit": 101}}))]
+#[actix_web::test]
+#[serial_test::serial]
+async fn bulk_todo_bad_request(#[case] payload: Value) {
+    let res = bulk_todo_req(payload).await;
+    check_content_type(&res);
+    check_content_length(&res);
+    assert_eq!(res.status(), 400);
+}
+
+#[actix_web::test]
+#[serial_test::serial]
+async fn bulk_todo_set_status() {
+    let mut uuids = Vec::new();
+    for title in ["bulk_todo_1", "bulk_todo_2"] {
+        let mut res = create_todo_req(title.to_owned(), "pending".to_owned()).await;
+        let todo: TodoSchema =
+            serde_json::from_slice(res.body().await.unwrap().to_vec().as_slice()).unwrap();
+        uuids.push(todo.uuid);
+    }
+    let unknown = Uuid::new_v4();
+
+    // Dry run, nothing should be changed
+    let result = bulk_result(json!({
+        "action": "set_status",
+        "status": "progress",
+        "uuids": [uuids[0], uuids[1], unknown],
+        "dry_run": true
+    }))
+    .await;
+    assert!(result.dry_run);
+    assert_eq!(result.applied, 2);
+    assert_eq!(result.results[2].uuid, unknown);
+    assert_eq!(result.results[2].status, BulkItemStatus::NotFound);
+    assert_eq!(
+        list_todos("title=bulk_todo&status=progress")
+            .await
+            .meta
+            .total,
+        0
+    );
+
+    let result = bulk_result(json!({
+        "action": "set_status",
+        "status": "progress",
+        "uuids": [uuids[0], uuids[0]]
+    }))
+    .await;
+    assert!(!result.dry_run);
+    assert_eq!(result.applied, 1);
+    assert_eq!(result.results.len(), 1);
+    assert_eq!(
+        result.results[0].todo.as_ref().unwrap().status,
+        TodoStatus::Progress
+    );
+
+    // The first todo is already in progress
+    let result = bulk_result(json!({
+        "action": "set_status",
+        "status": "progress",
+        "filter": {"title": "bulk_todo"}
+    }))
+    .await;
+    assert_eq!(result.applied, 1);
+    assert_eq!(result.results.len(), 2);
+    assert!(result
+        .results
+        .iter()
+        .any(|r| r.uuid == uuids[0] && r.status == BulkItemStatus::Unchanged));
+    assert_eq!(
+        list_todos("title=bulk_todo&status=progress")
+            .await
+            .meta
+            .total,
+        2
+    );
+}
+
+#[actix_web::test]
+#[serial_test::serial]
+async fn bulk_todo_delete() {
+    for title in ["bulk_delete_1", "bulk_delete_2"] {
+        let res = create_todo_req(title.to_owned(), "pending".to_owned()).await;
+        assert_eq!(res.status(), 200);
+    }
+    let result = bulk_result(json!({
+        "action": "delete",
+        "filter": {"title": "bulk_delete", "limit": 1}
+    }))
+    .await;
+    assert_eq!(result.applied, 1);
+    assert_eq!(list_todos("title=bulk_delete").await.meta.total, 1);
+
+    let result = bulk_result(json!({
+        "action": "delete",
+        "filter": {"title": "bulk_delete"}
+    }))
+    .await;
+    assert_eq!(result.applied, 1);
+    assert_eq!(list_todos("title=bulk_delete").await.meta.total, 0);
+}
@@ -0,0 +1,283 @@
+use std::collections::HashMap;
+
+use crate::schemas::todo::TodoSchema;
+use crate::schemas::user::UserSchema;
+use crate::tests::login::login_req;
+use crate::tests::todo::create_todo::create_todo_req;
+use crate::tests::todo::delete_todo::delete_todo_req;
+use crate::tests::{init_test_pool, TestResponseType};
+use actix_http::Method;
+use actix_web::{web, App};
+use base64::{engine::general_purpose::STANDARD, Engine};
+use uuid::Uuid;
+
+/// An exchange of a recorded CalDAV client session
+struct Exchange {
+    /// The description of the exchange
+    name: String,
+    /// The request method, path, headers and body
+    method: String,
+    path: String,
+    headers: Vec<(String, String)>,
+    body: String,
+    /// The expected response status and headers, a header value `{=name}` captures the value
+    status: u16,
+    response_headers: Vec<(String, String)>,
+    /// The fragments that the response body should contain, or should not contain if they start with `!`
+    fragments: Vec<String>,
+}
+
+/// Parse a recorded session, the exchanges start with `###` followed by the request line, the headers and
+/// the body. The response starts with `---` followed by the status, the headers and the body fragments
+fn parse_recording(recording: &str) -> Vec<Exchange> {
+    recording
+        .split("\n### ")
+        .skip(1)
+        .map(|exchange| {
+            let (request, response) = exchange.split_once("\n--- ").unwrap();
+            let mut request = request.lines();
+            let name = request.next().unwrap().to_owned();
+            let (method, path) = request.next().unwrap().split_once(' ').unwrap();
+            let headers = request
+                .by_ref()
+                .take_while(|line| !line.is_empty())
+                .map(|line| line.split_once(": ").unwrap())
+                .map(|(name, value)| (name.to_owned(), value.to_owned()))
+                .collect();
+            let body = request.collect::<Vec<_>>().join("\r\n");
+
+            let mut response = response.lines();
+            let status = response.next().unwrap().trim().parse().unwrap();
+            let response_headers = response
+                .by_ref()
+                .take_while(|line| !line.is_empty())
+                .map(|line| line.split_once(": ").unwrap())
+                .map(|(name, value)| (name.to_owned(), value.to_owned()))
+                .collect();
+            let fragments = response
+                .filter(|line| !line.is_empty())
+                .map(ToOwned::to_owned)
+                .collect();
+            Exchange {
+                name,
+                method: method.to_owned(),
+                path: path.to_owned(),
+                headers,
+                body,
+                status,
+                response_headers,
+                fragments,
+            }
+        })
+        .collect()
+}
+
+/// Replace the variables of the text with their values
+fn substitute(text: &str, variables: &HashMap<String, String>) -> String {
+    variables
+        .iter()
+        .fold(text.to_owned(), |text, (name, value)| {
+            text.replace(&format!("{{{name}}}"), value)
+        })
+}
+
+fn basic_credentials(username: &str, password: &str) -> String {
+    STANDARD.encode(format!("{username}:{password}"))
+}
+
+async fn caldav_server() -> actix_test::TestServer {
+    let pool = init_test_pool().await;
+    actix_test::start(move || {
+        App::new()
+            .app_data(web::Data::new(pool.clone()))
+            .service(web::scope("/api").configure(crate::api::caldav::init_routes))
+    })
+}
+
+async fn body(mut res: TestResponseType) -> String {
+    String::from_utf8(res.body().await.unwrap().to_vec()).unwrap()
+}
+
+/// Replay the recorded session against the server, and check the responses
+async fn replay(recording: &str) {
+    // Make sure the user exists
+    login_req("testusername1".to_owned(), "testpassword".to_owned()).await;
+    let srv = caldav_server().await;
+    let mut variables = HashMap::from([
+        (
+            "basic".to_owned(),
+            basic_credentials("testusername1", "testpassword"),
+        ),
+        ("uuid".to_owned(), Uuid::new_v4().to_string()),
+    ]);
+
+    for exchange in parse_recording(recording) {
+        let method = Method::from_bytes(exchange.method.as_bytes()).unwrap();
+        let mut req = srv.request(method, srv.url(&substitute(&exchange.path, &variables)));
+        for (name, value) in &exchange.headers {
+            req = req.insert_header((name.as_str(), substitute(value, &variables)));
+        }
+        let res = req
+            .send_body(substitute(&exchange.body, &variables))
+            .await
+            .unwrap();
+        assert_eq!(res.status(), exchange.status, "{}", exchange.name);
+        for (name, value) in &exchange.response_headers {
+            let header = res
+                .headers()
+                .get(name.as_str())
+                .unwrap_or_else(|| panic!("{}: `{name}` header is missing", exchange.name))
+                .to_str()
+                .unwrap()
+                .to_owned();
+            match value
+                .strip_prefix("{=")
+                .and_then(|value| value.strip_suffix('}'))
+            {
+                Some(variable) => {
+                    variables.insert(variable.to_owned(), header);
+                }
+                None => assert_eq!(header, substitute(value, &variables), "{}", exchange.name),
+            }
+        }
+        let body = body(res).await;
+        for fragment in &exchange.fragments {
+            match fragment.strip_prefix('!') {
+                Some(fragment) => assert!(
+                    !body.contains(&substitute(fragment, &variables)),
+                    "{}: `{fragment}` is in {body}",
+                    exchange.name
+                ),
+                None => assert!(
+                    body.contains(&substitute(fragment, &variables)),
+                    "{}: `{fragment}` is not in {body}",
+                    exchange.name
+                ),
+            }
+        }
+    }
+}
+
+#[actix_web::test]
+#[serial_test::serial]
+async fn caldav_replay_thunderbird() {
+    replay(include_str!("caldav/thunderbird.txt")).await;
+}
+
+#[actix_web::test]
+#[serial_test::serial]
+async fn caldav_replay_davx5() {
+    replay(include_str!("caldav/davx5.txt")).await;
+}
+
+#[actix_web::test]
+#[serial_test::serial]
+async fn caldav_unauthorized() {
+    let srv = caldav_server().await;
+    let propfind = Method::from_bytes(b"PROPFIND").unwrap();
+
+    let res = srv
+        .request(propfind.clone(), srv.url("/api/caldav/todos/"))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(res.status(), 401);
+    assert!(res
+        .headers()
+        .get("WWW-Authenticate")
+        .unwrap()
+        .to_str()
+        .unwrap()
+        .starts_with("Basic realm="));
+
+    let res = srv
+        .request(propfind.clone(), srv.url("/api/caldav/todos/"))
+        .insert_header((
+            "Authorization",
+            format!(
+                "Basic {}",
+                basic_credentials("testusername1", "wrongpassword")
+            ),
+        ))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(res.status(), 401);
+
+    // The Bearer token is accepted too
+    let user: UserSchema = serde_json::from_str(
+        &body(login_req("testusername1".to_owned(), "testpassword".to_owned()).await).await,
+    )
+    .unwrap();
+    let res = srv
+        .request(propfind, srv.url("/api/caldav/todos/"))
+        .insert_header(("Authorization", format!("Bearer {}", user.token)))
+        .insert_header(("Depth", "0"))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(res.status(), 207);
+}
+
+#[actix_web::test]
+#[serial_test::serial]
+async fn caldav_resource_conditions() {
+    let srv = caldav_server().await;
+    let authorization = format!(
+        "Basic {}",
+        basic_credentials("testusername1", "testpassword")
+    );
+    let todo: TodoSchema = serde_json::from_str(
+        &body(
+            create_todo_req(
+                format!("caldav_conditions_{}", Uuid::new_v4().simple()),
+                "pending".to_owned(),
+            )
+            .await,
+        )
+        .await,
+    )
+    .unwrap();
+    let path = |uuid: Uuid| format!("/api/caldav/todos/{uuid}.ics");
+
+    // The fresh copy is not sent again
+    let res = srv
+        .get(path(todo.uuid))
+        .insert_header(("Authorization", authorization.as_str()))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(res.status(), 200);
+    let etag = res
+        .headers()
+        .get("ETag")
+        .unwrap()
+        .to_str()
+        .unwrap()
+        .to_owned();
+    let res = srv
+        .get(path(todo.uuid))
+        .insert_header(("Authorization", authorization.as_str()))
+        .insert_header(("If-None-Match", etag.as_str()))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(res.status(), 304);
+    assert_eq!(res.headers().get("ETag").unwrap(), etag.as_str());
+    assert!(res.headers().get("Content-Type").is_none());
+    assert!(body(res).await.is_empty());
+
+    // The uuid of a todo in the trash can't be used for a new todo
+    assert_eq!(delete_todo_req(todo.uuid).await.status(), 200);
+    let res = srv
+        .put(path(todo.uuid))
+        .insert_header(("Authorization", authorization.as_str()))
+        .insert_header(("Content-Type", "text/calendar"))
+        .send_body(
+            "BEGIN:VCALENDAR\r\nVERSION:2.0\r\nPRODID:-//Test//EN\r\n\
+            BEGIN:VTODO\r\nUID:1\r\nSUMMARY:caldav reused uuid\r\nEND:VTODO\r\nEND:VCALENDAR\r\n",
+        )
+        .await
+        .unwrap();
+    assert_eq!(res.status(), 403);
+}
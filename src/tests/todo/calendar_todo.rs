@@ -0,0 +1,121 @@
+use crate::schemas::calendar::CalendarTokenSchema;
+use crate::schemas::todo::TodoSchema;
+use crate::schemas::user::UserSchema;
+use crate::tests::login::login_req;
+use crate::tests::todo::create_todo::create_todo_req;
+use crate::tests::{check_content_length, check_content_type, init_test_pool, TestResponseType};
+use actix_http::Method;
+use actix_web::{web, App};
+
+async fn calendar_server() -> (actix_test::TestServer, String) {
+    let pool = init_test_pool().await;
+    let user: UserSchema = serde_json::from_slice(
+        login_req("testusername1".to_owned(), "testpassword".to_owned())
+            .await
+            .body()
+            .await
+            .unwrap()
+            .to_vec()
+            .as_slice(),
+    )
+    .unwrap();
+    let srv = actix_test::start(move || {
+        App::new()
+            .app_data(web::Data::new(pool.clone()))
+            .service(web::scope("/api").configure(crate::api::calendar::init_routes))
+    });
+    (srv, user.token)
+}
+
+pub async fn calendar_token_req(method: Method) -> TestResponseType {
+    let (srv, token) = calendar_server().await;
+    srv.request(method, srv.url("/api/calendar/token"))
+        .insert_header(("Authorization", format!("Bearer {token}")))
+        .send()
+        .await
+        .unwrap()
+}
+
+/// The server is returned with the response, the feed is streamed while the server is running
+pub async fn feed_req(params: &str) -> (actix_test::TestServer, TestResponseType) {
+    let (srv, _) = calendar_server().await;
+    let res = srv
+        .get(format!("/api/todos.ics{params}"))
+        .send()
+        .await
+        .unwrap();
+    (srv, res)
+}
+
+async fn body(mut res: TestResponseType) -> String {
+    String::from_utf8(res.body().await.unwrap().to_vec()).unwrap()
+}
+
+async fn new_token() -> CalendarTokenSchema {
+    let res = calendar_token_req(Method::POST).await;
+    check_content_type(&res);
+    check_content_length(&res);
+    assert_eq!(res.status(), 200);
+    serde_json::from_str(&body(res).await).unwrap()
+}
+
+#[actix_web::test]
+#[serial_test::serial]
+async fn calendar_feed_unauthorized() {
+    let (_srv, res) = feed_req("").await;
+    assert_eq!(res.status(), 401);
+    let (_srv, res) = feed_req("?token=invalid").await;
+    assert_eq!(res.status(), 401);
+    let (srv, _) = calendar_server().await;
+    let res = srv.post("/api/calendar/token").send().await.unwrap();
+    assert_eq!(res.status(), 400);
+}
+
+#[actix_web::test]
+#[serial_test::serial]
+async fn calendar_feed() {
+    let pending: TodoSchema = serde_json::from_str(
+        &body(create_todo_req("calendar_todo_pending".to_owned(), "pending".to_owned()).await)
+            .await,
+    )
+    .unwrap();
+    let title = format!("calendar_todo_completed{}", "_long".repeat(12));
+    let completed: TodoSchema = serde_json::from_str(
+        &body(create_todo_req(title.clone(), "completed".to_owned()).await).await,
+    )
+    .unwrap();
+
+    let token = new_token().await;
+    assert_eq!(token.url, format!("/api/todos.ics?token={}", token.token));
+    let (_srv, res) = feed_req(token.url.trim_start_matches("/api/todos.ics")).await;
+    assert_eq!(res.status(), 200);
+    assert_eq!(
+        res.headers().get("Content-Type").unwrap(),
+        "text/calendar; charset=utf-8"
+    );
+    let feed = body(res).await;
+    assert!(feed.starts_with("BEGIN:VCALENDAR\r\nVERSION:2.0\r\n"));
+    assert!(feed.ends_with("END:VCALENDAR\r\n"));
+    assert!(feed.contains(&format!("UID:{}\r\n", pending.uuid)));
+    assert!(feed.contains("SUMMARY:calendar_todo_pending\r\nSTATUS:NEEDS-ACTION\r\n"));
+    assert!(feed.contains(&format!("UID:{}\r\n", completed.uuid)));
+    assert!(feed.contains("\r\nSTATUS:COMPLETED\r\nCOMPLETED:"));
+    // The long lines are folded
+    assert!(feed.lines().all(|line| line.len() <= 75));
+    assert!(feed
+        .replace("\r\n ", "")
+        .contains(&format!("SUMMARY:{title}\r\n")));
+
+    // A new token disables the previous one
+    let rotated = new_token().await;
+    assert_ne!(rotated.token, token.token);
+    let (_srv, res) = feed_req(&format!("?token={}", token.token)).await;
+    assert_eq!(res.status(), 401);
+    let (_srv, res) = feed_req(&format!("?token={}", rotated.token)).await;
+    assert_eq!(res.status(), 200);
+
+    let res = calendar_token_req(Method::DELETE).await;
+    assert_eq!(res.status(), 200);
+    let (_srv, res) = feed_req(&format!("?token={}", rotated.token)).await;
+    assert_eq!(res.status(), 401);
+}
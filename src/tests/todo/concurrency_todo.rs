@@ -0,0 +1,220 @@
+use crate::api::todo::utils;
+use crate::errors::Error as ApiError;
+use crate::schemas::todo::{TodoContentSchema, TodoListSchema, TodoSchema};
+use crate::schemas::user::UserSchema;
+use crate::tests::login::login_req;
+use crate::tests::todo::create_todo::create_todo_req;
+use crate::tests::todo::delete_todo::delete_todo_req;
+use crate::tests::todo::list_todo::list_todo_req;
+use crate::tests::todo::trash_todo::restore_todo_req;
+use crate::tests::{init_test_pool, parse};
+use actix_web::test::TestRequest;
+use actix_web::web::JsonConfig;
+use actix_web::{rt, web, App};
+use entity::todo::Status as TodoStatus;
+use entity::user::{Column as UserColumn, Entity as UserEntity};
+use futures_util::future::join_all;
+use sea_orm::{ColumnTrait, EntityTrait, QueryFilter};
+use serde_json::json;
+use uuid::Uuid;
+
+/// The number of the concurrent requests
+const CONCURRENT_REQUESTS: usize = 10;
+
+/// Send concurrent create requests with the given titles, returns the status codes
+async fn concurrent_create_req(titles: Vec<String>) -> Vec<u16> {
+    let pool = init_test_pool().await;
+    let user: UserSchema = serde_json::from_slice(
+        login_req("testusername1".to_owned(), "testpassword".to_owned())
+            .await
+            .body()
+            .await
+            .unwrap()
+            .to_vec()
+            .as_slice(),
+    )
+    .unwrap();
+    let srv = actix_test::start(move || {
+        App::new()
+            .app_data(web::Data::new(pool.clone()))
+            .app_data(JsonConfig::default().error_handler(|err, _| ApiError::from(err).into()))
+            .service(web::scope("/todo").service(crate::api::todo::create::create))
+    });
+    let requests: Vec<_> = titles
+        .into_iter()
+        .map(|title| {
+            rt::spawn(
+                srv.post("/todo")
+                    .insert_header(("Authorization", format!("Bearer {}", user.token)))
+                    .send_json(&json!({"title": title, "status": "pending"})),
+            )
+        })
+        .collect();
+    let mut statuses = Vec::new();
+    for request in requests {
+        statuses.push(request.await.unwrap().unwrap().status().as_u16());
+    }
+    statuses
+}
+
+/// Send concurrent update requests of the todo with the given version and titles, returns the status codes
+async fn concurrent_update_req(uuid: Uuid, version: u32, titles: Vec<String>) -> Vec<u16> {
+    let pool = init_test_pool().await;
+    let user: UserSchema =
+        parse(login_req("testusername1".to_owned(), "testpassword".to_owned()).await).await;
+    let srv = actix_test::start(move || {
+        App::new()
+            .app_data(web::Data::new(pool.clone()))
+            .service(web::scope("/todo").service(crate::api::todo::update::update_todo))
+    });
+    let requests: Vec<_> = titles
+        .into_iter()
+        .map(|title| {
+            rt::spawn(
+                srv.put(format!("/todo/{uuid}"))
+                    .insert_header(("Authorization", format!("Bearer {}", user.token)))
+                    .send_json(&json!({"title": title, "version": version})),
+            )
+        })
+        .collect();
+    let mut statuses = Vec::new();
+    for request in requests {
+        statuses.push(request.await.unwrap().unwrap().status().as_u16());
+    }
+    statuses
+}
+
+async fn todos_count() -> u64 {
+    let todos: TodoListSchema = parse(list_todo_req("limit=1").await).await;
+    todos.meta.total
+}
+
+#[actix_web::test]
+#[serial_test::serial]
+async fn concurrent_create_same_title() {
+    let statuses =
+        concurrent_create_req(vec!["concurrent_title".to_owned(); CONCURRENT_REQUESTS]).await;
+    assert_eq!(statuses.iter().filter(|s| **s == 200).count(), 1);
+    assert!(statuses.iter().all(|s| *s == 200 || *s == 400));
+    let todos: TodoListSchema = parse(list_todo_req("title=concurrent_title").await).await;
+    assert_eq!(todos.meta.total, 1);
+}
+
+#[actix_web::test]
+#[serial_test::serial]
+async fn concurrent_create_quota() {
+    let available = 3;
+    let max = todos_count().await + available;
+    std::env::set_var("MAXIMUM_TODO_PER_USER", max.to_string());
+    let statuses = concurrent_create_req(
+        (0..CONCURRENT_REQUESTS)
+            .map(|idx| format!("concurrent_quota_{idx}"))
+            .collect(),
+    )
+    .await;
+    let count = todos_count().await;
+
+    // The trashed todo can't be restored while the maximum is reached
+    let todos: TodoListSchema = parse(list_todo_req("title=concurrent_quota").await).await;
+    let trashed: TodoSchema = parse(delete_todo_req(todos.data[0].uuid).await).await;
+    let new_statuses = concurrent_create_req(vec!["concurrent_quota_new".to_owned()]).await;
+    let restore_status = restore_todo_req(trashed.uuid).await.status();
+    std::env::remove_var("MAXIMUM_TODO_PER_USER");
+
+    assert_eq!(
+        statuses.iter().filter(|s| **s == 200).count() as u64,
+        available
+    );
+    assert!(statuses.iter().all(|s| *s == 200 || *s == 400));
+    assert_eq!(count, max);
+    assert_eq!(new_statuses, [200]);
+    assert_eq!(restore_status, 400);
+}
+
+#[actix_web::test]
+#[serial_test::serial]
+async fn concurrent_update_same_version() {
+    let title = format!("concurrent_update_{}", Uuid::new_v4().simple());
+    let todo: TodoSchema = parse(create_todo_req(title.clone(), "pending".to_owned()).await).await;
+    let statuses = concurrent_update_req(
+        todo.uuid,
+        todo.version,
+        (0..CONCURRENT_REQUESTS)
+            .map(|idx| format!("{title}_{idx}"))
+            .collect(),
+    )
+    .await;
+    assert_eq!(statuses.iter().filter(|s| **s == 200).count(), 1);
+    assert!(statuses.iter().all(|s| *s == 200 || *s == 412));
+
+    let todos: TodoListSchema = parse(list_todo_req(&format!("title={title}_")).await).await;
+    assert_eq!(todos.meta.total, 1);
+    assert_eq!(todos.data[0].version, todo.version + 1);
+}
+
+#[actix_web::test]
+#[serial_test::serial]
+async fn stale_update_same_version() {
+    let pool = init_test_pool().await;
+    let title = format!("stale_update_{}", Uuid::new_v4().simple());
+    let todo: TodoSchema = parse(create_todo_req(title.clone(), "pending".to_owned()).await).await;
+    let user = UserEntity::find()
+        .filter(UserColumn::Name.eq("testusername1"))
+        .one(&pool)
+        .await
+        .unwrap()
+        .unwrap();
+    let model = utils::find_todo_by_uuid(todo.uuid, user.id, &pool)
+        .await
+        .unwrap();
+    let actor = utils::Actor::new(user.id, &TestRequest::default().to_http_request());
+
+    // Both writes read the same version, only the first one is saved
+    let first = utils::update_todo(
+        model.clone(),
+        Some(format!("{title}_first")),
+        None,
+        &actor,
+        &pool,
+    )
+    .await;
+    let second =
+        utils::update_todo(model, Some(format!("{title}_second")), None, &actor, &pool).await;
+    assert_eq!(first.unwrap().version, todo.version + 1);
+    match second {
+        Err(ApiError::PreconditionFailed(current)) => {
+            assert_eq!(current.title, format!("{title}_first"));
+            assert_eq!(current.version, todo.version + 1);
+        }
+        res => panic!("Expected a precondition failure, got {res:?}"),
+    }
+}
+
+#[actix_web::test]
+#[serial_test::serial]
+async fn concurrent_create_same_uuid() {
+    let pool = init_test_pool().await;
+    let user = UserEntity::find()
+        .filter(UserColumn::Name.eq("testusername1"))
+        .one(&pool)
+        .await
+        .unwrap()
+        .unwrap();
+    let actor = utils::Actor::new(user.id, &TestRequest::default().to_http_request());
+    let uuid = Uuid::new_v4();
+
+    // All the creations check the uuid before any of them is saved
+    let results = join_all((0..CONCURRENT_REQUESTS).map(|_| {
+        let content = TodoContentSchema {
+            title: format!("same_uuid_{}", Uuid::new_v4().simple()),
+            status: TodoStatus::Pending,
+        };
+        utils::create_todo_at(&pool, Some(uuid), content, None, None, &actor)
+    }))
+    .await;
+    assert_eq!(results.iter().filter(|result| result.is_ok()).count(), 1);
+    assert!(results
+        .iter()
+        .filter_map(|result| result.as_ref().err())
+        .all(|err| matches!(err, ApiError::Conflict(_))));
+}
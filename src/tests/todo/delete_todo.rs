@@ -1,4 +1,4 @@
-use crate::schemas::todo::TodoListSchema;
+use crate::schemas::todo::{TodoListSchema, TodoSchema};
 use crate::schemas::user::UserSchema;
 use crate::tests::login::login_req;
 use crate::tests::todo::list_todo::list_todo_req;
@@ -9,6 +9,14 @@ use uuid::Uuid;
 use super::create_todo::create_todo_req;
 
 pub async fn delete_todo_req(uuid: Uuid) -> TestResponseType {
+    delete_todo_req_with(uuid, "", &[]).await
+}
+
+pub async fn delete_todo_req_with(
+    uuid: Uuid,
+    params: &str,
+    headers: &[(&str, &str)],
+) -> TestResponseType {
     let pool = init_test_pool().await;
     let user: UserSchema = serde_json::from_slice(
         login_req("testusername1".to_owned(), "testpassword".to_owned())
@@ -25,11 +33,13 @@ pub async fn delete_todo_req(uuid: Uuid) -> TestResponseType {
             .app_data(web::Data::new(pool.clone()))
             .service(web::scope("/todo").service(crate::api::todo::delete_todo::delete_todo))
     });
-    srv.delete(format!("todo/{uuid}"))
-        .insert_header(("Authorization", format!("Bearer {}", user.token)))
-        .send()
-        .await
-        .unwrap()
+    let mut req = srv
+        .delete(format!("todo/{uuid}?{params}"))
+        .insert_header(("Authorization", format!("Bearer {}", user.token)));
+    for header in headers {
+        req = req.insert_header(*header);
+    }
+    req.send().await.unwrap()
 }
 
 #[actix_web::test]
@@ -82,3 +92,21 @@ async fn delete_invalid_todo() {
     check_content_length(&response);
     assert_eq!(response.status().as_u16(), 404);
 }
+
+#[actix_web::test]
+#[serial_test::serial]
+async fn delete_todo_version() {
+    let mut todo =
+        create_todo_req("VersionedTodoFromDelete".to_owned(), "pending".to_owned()).await;
+    let todo: TodoSchema =
+        serde_json::from_slice(todo.body().await.unwrap().to_vec().as_slice()).unwrap();
+
+    let response = delete_todo_req_with(todo.uuid, "version=2", &[]).await;
+    check_content_type(&response);
+    assert_eq!(response.status().as_u16(), 412);
+    let response = delete_todo_req_with(todo.uuid, "", &[("If-Match", "\"other\"")]).await;
+    assert_eq!(response.status().as_u16(), 412);
+    let response =
+        delete_todo_req_with(todo.uuid, "", &[("If-Match", &todo.etag().to_string())]).await;
+    assert_eq!(response.status().as_u16(), 200);
+}
@@ -0,0 +1,129 @@
+use std::time::Duration;
+
+use crate::schemas::sync::SyncSchema;
+use crate::schemas::todo::TodoSchema;
+use crate::schemas::user::UserSchema;
+use crate::tests::login::login_req;
+use crate::tests::todo::create_todo::create_todo_req;
+use crate::tests::todo::delete_todo::delete_todo_req;
+use crate::tests::todo::sync_todo::sync_req;
+use crate::tests::todo::update_todo::update_todo_req;
+use crate::tests::{init_test_pool, parse, TestResponseType};
+use actix_web::{rt::time, web, App};
+use futures_util::StreamExt;
+
+/// Open the events stream, the server is returned to keep it running while reading the stream
+pub async fn events_req(headers: &[(&str, &str)]) -> (actix_test::TestServer, TestResponseType) {
+    let pool = init_test_pool().await;
+    let user: UserSchema = serde_json::from_slice(
+        login_req("testusername1".to_owned(), "testpassword".to_owned())
+            .await
+            .body()
+            .await
+            .unwrap()
+            .to_vec()
+            .as_slice(),
+    )
+    .unwrap();
+    let srv = actix_test::start(move || {
+        App::new()
+            .app_data(web::Data::new(pool.clone()))
+            .service(web::scope("/todo").service(crate::api::todo::events::events))
+    });
+    let mut req = srv
+        .get("/todo/events")
+        .insert_header(("Authorization", format!("Bearer {}", user.token)));
+    for header in headers {
+        req = req.insert_header(*header);
+    }
+    let res = req.send().await.unwrap();
+    (srv, res)
+}
+
+/// Read the given number of events from the stream, as `(id, event, todo)`
+async fn read_events(res: &mut TestResponseType, count: usize) -> Vec<(u32, String, TodoSchema)> {
+    let mut buffer = String::new();
+    let mut events = Vec::new();
+    while events.len() < count {
+        let chunk = time::timeout(Duration::from_secs(10), res.next())
+            .await
+            .expect("Timeout while waiting for the events")
+            .unwrap()
+            .unwrap();
+        buffer.push_str(std::str::from_utf8(&chunk).unwrap());
+        while let Some(end) = buffer.find("\n\n") {
+            let message: String = buffer.drain(..end + 2).collect();
+            let field = |name: &str| {
+                message
+                    .lines()
+                    .find_map(|line| line.strip_prefix(&format!("{name}: ")))
+                    .map(ToOwned::to_owned)
+            };
+            if let (Some(id), Some(event), Some(data)) =
+                (field("id"), field("event"), field("data"))
+            {
+                events.push((
+                    id.parse().unwrap(),
+                    event,
+                    serde_json::from_str(&data).unwrap(),
+                ));
+            }
+        }
+    }
+    events
+}
+
+#[actix_web::test]
+#[serial_test::serial]
+async fn events_todo_live() {
+    let (_srv, mut res) = events_req(&[]).await;
+    assert_eq!(res.status(), 200);
+    assert_eq!(
+        res.headers().get("Content-Type").unwrap(),
+        "text/event-stream"
+    );
+    let todo: TodoSchema =
+        parse(create_todo_req("events_todo_live".to_owned(), "pending".to_owned()).await).await;
+
+    let events = read_events(&mut res, 1).await;
+    assert_eq!(events[0].1, "todo.created");
+    assert_eq!(events[0].2.uuid, todo.uuid);
+}
+
+#[actix_web::test]
+#[serial_test::serial]
+async fn events_todo_resume() {
+    let last: SyncSchema = parse(sync_req("").await).await;
+    let todo: TodoSchema =
+        parse(create_todo_req("events_todo_resume".to_owned(), "pending".to_owned()).await).await;
+    assert_eq!(
+        update_todo_req(todo.uuid, "events_todo_resume", "completed")
+            .await
+            .status(),
+        200
+    );
+    assert_eq!(delete_todo_req(todo.uuid).await.status(), 200);
+
+    let (_srv, mut res) = events_req(&[("Last-Event-ID", &last.token)]).await;
+    assert_eq!(res.status(), 200);
+    let events = read_events(&mut res, 3).await;
+    let types: Vec<_> = events.iter().map(|event| event.1.as_str()).collect();
+    assert_eq!(types, ["todo.created", "todo.updated", "todo.deleted"]);
+    assert!(events.windows(2).all(|events| events[0].0 < events[1].0));
+    assert!(events.iter().all(|event| event.2.uuid == todo.uuid));
+    assert_eq!(events[1].2.status.as_str(), "completed");
+    assert!(events[2].2.deleted_at.is_some());
+
+    // Resume after the second event
+    let (_srv, mut res) = events_req(&[("Last-Event-ID", &events[1].0.to_string())]).await;
+    let resumed = read_events(&mut res, 1).await;
+    assert_eq!(resumed[0].0, events[2].0);
+}
+
+#[actix_web::test]
+#[serial_test::serial]
+async fn events_todo_invalid_last_event_id() {
+    let (_srv, res) = events_req(&[("Last-Event-ID", "invalid")]).await;
+    crate::tests::check_content_type(&res);
+    assert_eq!(res.status(), 400);
+}
@@ -4,9 +4,18 @@ use crate::tests::login::login_req;
 use crate::tests::todo::list_todo::list_todo_req;
 use crate::tests::{check_content_length, check_content_type, init_test_pool, TestResponseType};
 use actix_web::{web, App};
+use serde_json::Value;
 use uuid::Uuid;
 
-pub async fn get_todo_req(uuid: Uuid) -> TestResponseType {
+pub async fn get_todo_req(uuid: Uuid, params: &str) -> TestResponseType {
+    get_todo_req_with_headers(uuid, params, &[]).await
+}
+
+pub async fn get_todo_req_with_headers(
+    uuid: Uuid,
+    params: &str,
+    headers: &[(&str, &str)],
+) -> TestResponseType {
     let pool = init_test_pool().await;
     let user: UserSchema = serde_json::from_slice(
         login_req("testusername1".to_owned(), "testpassword".to_owned())
@@ -23,11 +32,13 @@ pub async fn get_todo_req(uuid: Uuid) -> TestResponseType {
             .app_data(web::Data::new(pool.clone()))
             .service(web::scope("/todo").service(crate::api::todo::get_todo::get_todo))
     });
-    srv.get(format!("todo/{uuid}"))
-        .insert_header(("Authorization", format!("Bearer {}", user.token)))
-        .send()
-        .await
-        .unwrap()
+    let mut req = srv
+        .get(format!("todo/{uuid}?{params}"))
+        .insert_header(("Authorization", format!("Bearer {}", user.token)));
+    for header in headers {
+        req = req.insert_header(*header);
+    }
+    req.send().await.unwrap()
 }
 
 #[actix_web::test]
@@ -44,7 +55,7 @@ async fn get_valid_todo() {
     )
     .unwrap();
     for todo in todos.data {
-        let mut response = get_todo_req(todo.uuid).await;
+        let mut response = get_todo_req(todo.uuid, "").await;
         check_content_type(&response);
         check_content_length(&response);
         assert_eq!(response.status().as_u16(), 200);
@@ -62,8 +73,77 @@ async fn get_valid_todo() {
 #[actix_web::test]
 #[serial_test::serial]
 async fn get_invalid_todo() {
-    let response = get_todo_req(Uuid::new_v4()).await;
+    let response = get_todo_req(Uuid::new_v4(), "").await;
     check_content_type(&response);
     check_content_length(&response);
     assert_eq!(response.status().as_u16(), 404);
 }
+
+#[actix_web::test]
+#[serial_test::serial]
+async fn get_todo_with_fields() {
+    let todos: TodoListSchema = serde_json::from_slice(
+        list_todo_req("")
+            .await
+            .body()
+            .await
+            .unwrap()
+            .to_vec()
+            .as_slice(),
+    )
+    .unwrap();
+    let todo = todos.data.first().expect("There are no todos");
+    let mut response = get_todo_req(todo.uuid, "fields=uuid,status&include=owner").await;
+    check_content_type(&response);
+    check_content_length(&response);
+    assert_eq!(response.status().as_u16(), 200);
+    let body: Value =
+        serde_json::from_slice(response.body().await.unwrap().to_vec().as_slice()).unwrap();
+    assert_eq!(body.as_object().unwrap().len(), 3);
+    assert_eq!(body["uuid"], todo.uuid.to_string());
+    assert_eq!(body["status"], todo.status.as_str());
+    assert_eq!(body["owner"]["username"], "testusername1");
+
+    let response = get_todo_req(todo.uuid, "fields=uuid,secret").await;
+    check_content_type(&response);
+    assert_eq!(response.status().as_u16(), 400);
+}
+
+#[actix_web::test]
+#[serial_test::serial]
+async fn get_todo_conditional() {
+    let todos: TodoListSchema = serde_json::from_slice(
+        list_todo_req("")
+            .await
+            .body()
+            .await
+            .unwrap()
+            .to_vec()
+            .as_slice(),
+    )
+    .unwrap();
+    let todo = todos.data.first().expect("There are no todos");
+    let response = get_todo_req(todo.uuid, "").await;
+    assert_eq!(response.status().as_u16(), 200);
+    let etag = response.headers().get("ETag").unwrap().to_str().unwrap();
+    let last_modified = response
+        .headers()
+        .get("Last-Modified")
+        .unwrap()
+        .to_str()
+        .unwrap();
+
+    let response = get_todo_req_with_headers(todo.uuid, "", &[("If-None-Match", etag)]).await;
+    assert_eq!(response.status().as_u16(), 304);
+    assert_eq!(response.headers().get("ETag").unwrap(), etag);
+    let response =
+        get_todo_req_with_headers(todo.uuid, "", &[("If-Modified-Since", last_modified)]).await;
+    assert_eq!(response.status().as_u16(), 304);
+    let response =
+        get_todo_req_with_headers(todo.uuid, "", &[("If-None-Match", "\"other\"")]).await;
+    assert_eq!(response.status().as_u16(), 200);
+    // Another representation of the todo has another entity tag
+    let response =
+        get_todo_req_with_headers(todo.uuid, "fields=uuid", &[("If-None-Match", etag)]).await;
+    assert_eq!(response.status().as_u16(), 200);
+}
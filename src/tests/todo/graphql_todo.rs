@@ -0,0 +1,244 @@
+use std::time::Duration;
+
+use crate::schemas::user::UserSchema;
+use crate::tests::init_test_pool;
+use crate::tests::login::login_req;
+use crate::tests::todo::create_todo::create_todo_req;
+use crate::tests::todo::ws_todo::WsConnection;
+use actix_http::ws::{Frame, Message};
+use actix_web::{rt::time, web, App};
+use futures_util::{SinkExt, StreamExt};
+use serde_json::{json, Value};
+use uuid::Uuid;
+
+async fn user_token() -> String {
+    let user: UserSchema = serde_json::from_slice(
+        login_req("testusername1".to_owned(), "testpassword".to_owned())
+            .await
+            .body()
+            .await
+            .unwrap()
+            .to_vec()
+            .as_slice(),
+    )
+    .unwrap();
+    user.token
+}
+
+async fn graphql_server() -> actix_test::TestServer {
+    let pool = init_test_pool().await;
+    actix_test::start(move || {
+        App::new()
+            .app_data(web::Data::new(pool.clone()))
+            .service(web::scope("/api").configure(crate::api::graphql::init_routes))
+    })
+}
+
+/// Execute the GraphQL document with the variables, returns the response body
+async fn graphql_req(
+    srv: &actix_test::TestServer,
+    token: &str,
+    query: &str,
+    variables: Value,
+) -> Value {
+    let mut res = srv
+        .post("/api/graphql")
+        .bearer_auth(token)
+        .send_json(&json!({"query": query, "variables": variables}))
+        .await
+        .unwrap();
+    assert_eq!(res.status(), 200);
+    serde_json::from_slice(&res.body().await.unwrap()).unwrap()
+}
+
+const CREATE: &str =
+    "mutation($title: String!) { createTodo(title: $title) { uuid title status version } }";
+
+#[actix_web::test]
+#[serial_test::serial]
+async fn graphql_todo_mutations() {
+    let token = user_token().await;
+    let srv = graphql_server().await;
+    let title = format!("graphql_mutation_{}", Uuid::new_v4().simple());
+
+    let res = graphql_req(&srv, &token, CREATE, json!({ "title": title })).await;
+    let todo = &res["data"]["createTodo"];
+    assert_eq!(todo["title"], title.as_str());
+    assert_eq!(todo["status"], "PENDING");
+    let uuid = todo["uuid"].as_str().unwrap().to_owned();
+    let version = todo["version"].as_u64().unwrap();
+
+    // The title should be unique
+    let res = graphql_req(&srv, &token, CREATE, json!({ "title": title })).await;
+    assert_eq!(res["errors"][0]["extensions"]["status"], 400);
+
+    let update = "mutation($uuid: UUID!, $version: Int) { \
+                  updateTodo(uuid: $uuid, status: COMPLETED, version: $version) { status completedAt version } }";
+    let res = graphql_req(
+        &srv,
+        &token,
+        update,
+        json!({"uuid": uuid, "version": version + 1}),
+    )
+    .await;
+    assert_eq!(res["errors"][0]["extensions"]["status"], 412);
+
+    let res = graphql_req(
+        &srv,
+        &token,
+        update,
+        json!({"uuid": uuid, "version": version}),
+    )
+    .await;
+    let todo = &res["data"]["updateTodo"];
+    assert_eq!(todo["status"], "COMPLETED");
+    assert!(todo["completedAt"].is_i64());
+    assert_eq!(todo["version"], version + 1);
+
+    let res = graphql_req(
+        &srv,
+        &token,
+        "mutation($uuid: UUID!) { deleteTodo(uuid: $uuid) { uuid } }",
+        json!({ "uuid": uuid }),
+    )
+    .await;
+    assert_eq!(res["data"]["deleteTodo"]["uuid"], uuid.as_str());
+
+    // The deleted todo is in the trash
+    let res = graphql_req(
+        &srv,
+        &token,
+        "query($uuid: UUID!) { todo(uuid: $uuid) { uuid } }",
+        json!({ "uuid": uuid }),
+    )
+    .await;
+    assert_eq!(res["data"]["todo"], Value::Null);
+}
+
+#[actix_web::test]
+#[serial_test::serial]
+async fn graphql_todo_pagination() {
+    let token = user_token().await;
+    let srv = graphql_server().await;
+    let prefix = format!("graphql_page_{}", Uuid::new_v4().simple());
+    for idx in 0..3 {
+        graphql_req(
+            &srv,
+            &token,
+            CREATE,
+            json!({ "title": format!("{prefix}_{idx}") }),
+        )
+        .await;
+    }
+
+    let query = "query($title: String, $after: String) { me { name todos(filter: { title: $title, status: PENDING }, \
+                 orderBy: CREATED_AT, order: OLDER, first: 2, after: $after) { totalCount \
+                 pageInfo { hasNextPage hasPreviousPage endCursor } edges { node { title } } } } }";
+    let res = graphql_req(&srv, &token, query, json!({ "title": prefix })).await;
+    assert_eq!(res["data"]["me"]["name"], "testusername1");
+    let page = &res["data"]["me"]["todos"];
+    assert_eq!(page["totalCount"], 3);
+    assert_eq!(page["pageInfo"]["hasNextPage"], true);
+    assert_eq!(page["pageInfo"]["hasPreviousPage"], false);
+    assert_eq!(page["edges"][0]["node"]["title"], format!("{prefix}_0"));
+    assert_eq!(page["edges"][1]["node"]["title"], format!("{prefix}_1"));
+
+    let after = page["pageInfo"]["endCursor"].clone();
+    let res = graphql_req(
+        &srv,
+        &token,
+        query,
+        json!({"title": prefix, "after": after}),
+    )
+    .await;
+    let page = &res["data"]["me"]["todos"];
+    assert_eq!(page["pageInfo"]["hasNextPage"], false);
+    assert_eq!(page["pageInfo"]["hasPreviousPage"], true);
+    assert_eq!(page["edges"].as_array().unwrap().len(), 1);
+    assert_eq!(page["edges"][0]["node"]["title"], format!("{prefix}_2"));
+
+    // The last possible cursor is after all the todos
+    let res = graphql_req(
+        &srv,
+        &token,
+        query,
+        json!({"title": prefix, "after": usize::MAX.to_string()}),
+    )
+    .await;
+    assert!(res["errors"].is_null(), "{res}");
+    let page = &res["data"]["me"]["todos"];
+    assert_eq!(page["pageInfo"]["hasNextPage"], false);
+    assert!(page["edges"].as_array().unwrap().is_empty());
+}
+
+#[actix_web::test]
+#[serial_test::serial]
+async fn graphql_todo_unauthorized() {
+    let srv = graphql_server().await;
+    let res = srv
+        .post("/api/graphql")
+        .send_json(&json!({"query": "{ me { name } }"}))
+        .await
+        .unwrap();
+    assert_eq!(res.status(), 400);
+
+    let res = srv
+        .post("/api/graphql")
+        .bearer_auth("invalid")
+        .send_json(&json!({"query": "{ me { name } }"}))
+        .await
+        .unwrap();
+    assert_eq!(res.status(), 401);
+}
+
+/// Read the messages until one of them has the given type
+async fn read_message(connection: &mut impl WsConnection, message_type: &str) -> Value {
+    loop {
+        let frame = time::timeout(Duration::from_secs(10), connection.next())
+            .await
+            .expect("Timeout while waiting for the message")
+            .unwrap()
+            .unwrap();
+        if let Frame::Text(text) = frame {
+            let message: Value = serde_json::from_slice(&text).unwrap();
+            if message["type"] == message_type {
+                return message;
+            }
+        }
+    }
+}
+
+#[actix_web::test]
+#[serial_test::serial]
+async fn graphql_todo_subscription() {
+    let token = user_token().await;
+    let srv = graphql_server().await;
+    let (_, mut connection) = awc::Client::new()
+        .ws(srv.url("/api/graphql"))
+        .protocols(["graphql-transport-ws"])
+        .connect()
+        .await
+        .unwrap();
+
+    // The connection is authenticated by the `connection_init` payload
+    let messages = [
+        json!({"type": "connection_init", "payload": {"token": token}}),
+        json!({"id": "1", "type": "subscribe", "payload": {
+            "query": "subscription { todoEvents { event todo { title } } }"
+        }}),
+    ];
+    for message in messages {
+        connection
+            .send(Message::Text(message.to_string().into()))
+            .await
+            .unwrap();
+    }
+    read_message(&mut connection, "connection_ack").await;
+
+    let title = format!("graphql_event_{}", Uuid::new_v4().simple());
+    create_todo_req(title.clone(), "pending".to_owned()).await;
+    let message = read_message(&mut connection, "next").await;
+    let event = &message["payload"]["data"]["todoEvents"];
+    assert_eq!(event["event"], "CREATED");
+    assert_eq!(event["todo"]["title"], title.as_str());
+}
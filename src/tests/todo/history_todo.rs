@@ -0,0 +1,98 @@
+use crate::schemas::todo::{TodoHistoryListSchema, TodoSchema};
+use crate::schemas::user::UserSchema;
+use crate::tests::login::login_req;
+use crate::tests::todo::create_todo::create_todo_req;
+use crate::tests::todo::delete_todo::delete_todo_req;
+use crate::tests::todo::update_todo::update_todo_req_with;
+use crate::tests::{
+    check_content_length, check_content_type, init_test_pool, parse, TestResponseType,
+};
+use actix_web::{web, App};
+use entity::todo_history::Action as HistoryAction;
+use serde_json::json;
+use uuid::Uuid;
+
+pub async fn history_todo_req(uuid: Uuid) -> TestResponseType {
+    let pool = init_test_pool().await;
+    let user: UserSchema = serde_json::from_slice(
+        login_req("testusername1".to_owned(), "testpassword".to_owned())
+            .await
+            .body()
+            .await
+            .unwrap()
+            .to_vec()
+            .as_slice(),
+    )
+    .unwrap();
+    let srv = actix_test::start(move || {
+        App::new()
+            .app_data(web::Data::new(pool.clone()))
+            .service(web::scope("/todo").service(crate::api::todo::history::history))
+    });
+    srv.get(format!("/todo/{}/history", uuid))
+        .insert_header(("Authorization", format!("Bearer {}", user.token)))
+        .send()
+        .await
+        .unwrap()
+}
+
+#[actix_web::test]
+#[serial_test::serial]
+async fn history_todo() {
+    let todo: TodoSchema =
+        parse(create_todo_req("history_todo".to_owned(), "pending".to_owned()).await).await;
+    let res = update_todo_req_with(
+        todo.uuid,
+        json!({"status": "cancelled", "version": todo.version}),
+        &[("X-Request-Id", "history-request")],
+    )
+    .await;
+    assert_eq!(res.status(), 200);
+    assert_eq!(delete_todo_req(todo.uuid).await.status(), 200);
+
+    let res = history_todo_req(todo.uuid).await;
+    check_content_type(&res);
+    check_content_length(&res);
+    assert_eq!(res.status(), 200);
+    let history: TodoHistoryListSchema = parse(res).await;
+    let actions: Vec<_> = history.data.iter().map(|e| e.action.clone()).collect();
+    assert_eq!(
+        actions,
+        [
+            HistoryAction::Created,
+            HistoryAction::Updated,
+            HistoryAction::Deleted
+        ]
+    );
+    assert!(history.data.iter().all(|e| e.actor == "testusername1"));
+
+    assert_eq!(history.data[0].changes["title"]["old"], json!(null));
+    assert_eq!(history.data[0].changes["title"]["new"], "history_todo");
+    let update = &history.data[1];
+    assert_eq!(update.request_id, "history-request");
+    assert_eq!(
+        update.changes,
+        json!({"status": {"old": "pending", "new": "cancelled"}})
+    );
+    assert!(update.created_at >= todo.created_at);
+    assert!(history.data[2].changes["deleted_at"]["new"].is_i64());
+}
+
+#[actix_web::test]
+#[serial_test::serial]
+async fn history_todo_generated_request_id() {
+    let todo: TodoSchema =
+        parse(create_todo_req("history_request_id".to_owned(), "pending".to_owned()).await).await;
+    let history: TodoHistoryListSchema = parse(history_todo_req(todo.uuid).await).await;
+    assert_eq!(history.data.len(), 1);
+    assert!(Uuid::parse_str(&history.data[0].request_id).is_ok());
+}
+
+#[actix_web::test]
+#[serial_test::serial]
+async fn history_invalid_todo() {
+    let res = history_todo_req(Uuid::new_v4()).await;
+    check_content_type(&res);
+    check_content_length(&res);
+    assert_eq!(res.status(), 404);
+}
@@ -0,0 +1,139 @@
+use crate::errors::Error as ApiError;
+use crate::idempotency::Idempotency;
+use crate::schemas::todo::{TodoListSchema, TodoSchema};
+use crate::schemas::user::UserSchema;
+use crate::tests::login::login_req;
+use crate::tests::todo::list_todo::list_todo_req;
+use crate::tests::{
+    check_content_length, check_content_type, init_test_pool, parse, TestResponseType,
+};
+use actix_web::web::JsonConfig;
+use actix_web::{web, App, HttpResponse};
+use serde_json::{json, Value};
+use uuid::Uuid;
+
+pub async fn idempotent_create_req(key: &str, payload: Value) -> TestResponseType {
+    let pool = init_test_pool().await;
+    let user: UserSchema = serde_json::from_slice(
+        login_req("testusername1".to_owned(), "testpassword".to_owned())
+            .await
+            .body()
+            .await
+            .unwrap()
+            .to_vec()
+            .as_slice(),
+    )
+    .unwrap();
+    let srv = actix_test::start(move || {
+        App::new()
+            .app_data(web::Data::new(pool.clone()))
+            .app_data(JsonConfig::default().error_handler(|err, _| ApiError::from(err).into()))
+            .service(
+                web::scope("/todo")
+                    .wrap(Idempotency)
+                    .service(crate::api::todo::create::create),
+            )
+    });
+    srv.post("/todo")
+        .insert_header(("Authorization", format!("Bearer {}", user.token)))
+        .insert_header(("Idempotency-Key", key))
+        .send_json(&payload)
+        .await
+        .unwrap()
+}
+
+#[actix_web::test]
+#[serial_test::serial]
+async fn idempotency_replay_repeated_headers() {
+    let pool = init_test_pool().await;
+    let user: UserSchema =
+        parse(login_req("testusername1".to_owned(), "testpassword".to_owned()).await).await;
+    let srv = actix_test::start(move || {
+        App::new().app_data(web::Data::new(pool.clone())).service(
+            web::resource("/links")
+                .wrap(Idempotency)
+                .route(web::post().to(|| async {
+                    HttpResponse::Ok()
+                        .append_header(("Link", "</first>; rel=\"first\""))
+                        .append_header(("Link", "</last>; rel=\"last\""))
+                        .finish()
+                })),
+        )
+    });
+    let key = format!("links-{}", Uuid::new_v4().simple());
+    for replayed in [false, true] {
+        let res = srv
+            .post("/links")
+            .insert_header(("Authorization", format!("Bearer {}", user.token)))
+            .insert_header(("Idempotency-Key", key.as_str()))
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(res.status(), 200);
+        assert_eq!(res.headers().contains_key("Idempotent-Replayed"), replayed);
+        assert_eq!(res.headers().get_all("Link").count(), 2);
+    }
+}
+
+#[actix_web::test]
+#[serial_test::serial]
+async fn idempotency_replay() {
+    let payload = json!({"title": "idempotent_todo", "status": "pending"});
+    let res = idempotent_create_req("idempotency-replay", payload.clone()).await;
+    assert_eq!(res.status(), 200);
+    assert!(res.headers().get("Idempotent-Replayed").is_none());
+    let todo: TodoSchema = parse(res).await;
+
+    // The retry returns the same todo instead of "already exists" error
+    let res = idempotent_create_req("idempotency-replay", payload).await;
+    check_content_type(&res);
+    check_content_length(&res);
+    assert_eq!(res.status(), 200);
+    assert_eq!(res.headers().get("Idempotent-Replayed").unwrap(), "true");
+    assert!(res.headers().get("ETag").is_some());
+    let replayed: TodoSchema = parse(res).await;
+    assert_eq!(replayed.uuid, todo.uuid);
+    let todos: TodoListSchema = parse(list_todo_req("title=idempotent_todo").await).await;
+    assert_eq!(todos.meta.total, 1);
+}
+
+#[actix_web::test]
+#[serial_test::serial]
+async fn idempotency_different_payload() {
+    let res = idempotent_create_req(
+        "idempotency-conflict",
+        json!({"title": "idempotent_conflict", "status": "pending"}),
+    )
+    .await;
+    assert_eq!(res.status(), 200);
+    let res = idempotent_create_req(
+        "idempotency-conflict",
+        json!({"title": "idempotent_conflict_2", "status": "pending"}),
+    )
+    .await;
+    check_content_type(&res);
+    check_content_length(&res);
+    assert_eq!(res.status(), 409);
+}
+
+#[actix_web::test]
+#[serial_test::serial]
+async fn idempotency_replay_error() {
+    let payload = json!({"title": "", "status": "pending"});
+    for replayed in [false, true] {
+        let res = idempotent_create_req("idempotency-error", payload.clone()).await;
+        check_content_type(&res);
+        assert_eq!(res.status(), 400);
+        assert_eq!(res.headers().get("Idempotent-Replayed").is_some(), replayed);
+    }
+}
+
+#[actix_web::test]
+#[serial_test::serial]
+async fn idempotency_invalid_key() {
+    let payload = json!({"title": "idempotent_invalid_key", "status": "pending"});
+    let res = idempotent_create_req(&"k".repeat(256), payload).await;
+    check_content_type(&res);
+    check_content_length(&res);
+    assert_eq!(res.status(), 400);
+}
@@ -9,9 +9,17 @@ use actix_web::{
     App,
 };
 use entity::todo::Status;
+use serde_json::Value;
 use std::{cmp::Ordering, str::FromStr};
 
 pub async fn list_todo_req(params: &str) -> TestResponseType {
+    list_todo_req_with_headers(params, &[]).await
+}
+
+pub async fn list_todo_req_with_headers(
+    params: &str,
+    headers: &[(&str, &str)],
+) -> TestResponseType {
     let pool = init_test_pool().await;
     let user: UserSchema = serde_json::from_slice(
         login_req("testusername1".to_owned(), "testpassword".to_owned())
@@ -29,11 +37,13 @@ pub async fn list_todo_req(params: &str) -> TestResponseType {
             .app_data(QueryConfig::default().error_handler(|err, _| ApiError::from(err).into()))
             .service(web::scope("/todo").service(crate::api::todo::list::list))
     });
-    srv.get(format!("/todo?{}", params))
-        .insert_header(("Authorization", format!("Bearer {}", user.token)))
-        .send()
-        .await
-        .unwrap()
+    let mut req = srv
+        .get(format!("/todo?{}", params))
+        .insert_header(("Authorization", format!("Bearer {}", user.token)));
+    for header in headers {
+        req = req.insert_header(*header);
+    }
+    req.send().await.unwrap()
 }
 
 #[rustfmt::skip]
@@ -123,3 +133,61 @@ async fn list_todo_endpoint_ordring(#[case] order: &str) {
 
     assert!(todos.all(|t| todo.created_at.cmp(&t.created_at) == ordering));
 }
+
+#[rstest::rstest]
+#[case::all_fields("", 200, &["uuid", "title", "status", "created_at", "updated_at", "completed_at", "version", "deleted_at", "position"])]
+#[case::some_fields("fields=uuid,title,status", 200, &["uuid", "title", "status"])]
+#[case::one_field("fields=title", 200, &["title"])]
+#[case::fields_with_owner("fields=uuid&include=owner", 200, &["uuid", "owner"])]
+#[case::all_fields_with_owner("include=owner", 200, &["uuid", "title", "status", "created_at", "updated_at", "completed_at", "version", "deleted_at", "position", "owner"])]
+#[case::invalid_field("fields=uuid,user_id", 400, &[])]
+#[case::invalid_include("include=user", 400, &[])]
+#[actix_web::test]
+#[serial_test::serial]
+async fn list_todo_endpoint_fields(
+    #[case] params: &str,
+    #[case] status_code: u16,
+    #[case] fields: &[&str],
+) {
+    let mut list_todo_res = list_todo_req(params).await;
+    check_content_length(&list_todo_res);
+    check_content_type(&list_todo_res);
+    assert_eq!(list_todo_res.status(), status_code);
+    let body: Value =
+        serde_json::from_slice(list_todo_res.body().await.unwrap().to_vec().as_slice()).unwrap();
+    if status_code == 200 {
+        let todos = body["data"].as_array().unwrap();
+        assert!(!todos.is_empty());
+        for todo in todos {
+            let mut keys: Vec<_> = todo.as_object().unwrap().keys().collect();
+            let mut expected: Vec<_> = fields.iter().collect();
+            keys.sort();
+            expected.sort();
+            assert_eq!(keys, expected);
+        }
+    } else {
+        assert!(body["message"].as_str().unwrap().contains("the valid"));
+    }
+}
+
+#[actix_web::test]
+#[serial_test::serial]
+async fn list_todo_endpoint_conditional() {
+    let response = list_todo_req("").await;
+    assert_eq!(response.status(), 200);
+    let etag = response.headers().get("ETag").unwrap().to_str().unwrap();
+    let last_modified = response
+        .headers()
+        .get("Last-Modified")
+        .unwrap()
+        .to_str()
+        .unwrap();
+
+    let response = list_todo_req_with_headers("", &[("If-None-Match", etag)]).await;
+    assert_eq!(response.status(), 304);
+    let response = list_todo_req_with_headers("", &[("If-Modified-Since", last_modified)]).await;
+    assert_eq!(response.status(), 304);
+    // Another result set has another entity tag
+    let response = list_todo_req_with_headers("limit=1", &[("If-None-Match", etag)]).await;
+    assert_eq!(response.status(), 200);
+}
@@ -1,6 +1,24 @@
+mod bulk_todo;
+mod caldav_todo;
+mod calendar_todo;
+mod concurrency_todo;
 mod create_todo;
 mod delete_todo;
 mod delete_todos;
+mod events_todo;
 mod get_todo;
+mod graphql_todo;
+mod history_todo;
+mod idempotency_todo;
 mod list_todo;
+mod move_todo;
+mod notification_todo;
+mod patch_todo;
+mod stats_todo;
+mod sync_todo;
+mod transfer_todo;
+mod trash_todo;
+mod undo_todo;
 mod update_todo;
+mod webhook_todo;
+mod ws_todo;
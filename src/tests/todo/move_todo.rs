@@ -0,0 +1,222 @@
+use crate::api::todo::rank::{rank_between, spread_ranks};
+use crate::schemas::todo::{TodoListSchema, TodoSchema};
+use crate::schemas::user::UserSchema;
+use crate::tests::login::login_req;
+use crate::tests::todo::create_todo::create_todo_req;
+use crate::tests::todo::delete_todo::delete_todo_req;
+use crate::tests::todo::list_todo::list_todo_req;
+use crate::tests::{
+    check_content_length, check_content_type, init_test_pool, parse, TestResponseType,
+};
+use actix_web::{web, App};
+use entity::todo::{Column as TodoColumn, Entity as TodoEntity};
+use sea_orm::{sea_query::Expr, ColumnTrait, EntityTrait, QueryFilter};
+use serde_json::{json, Value};
+use uuid::Uuid;
+
+pub async fn move_todo_req(uuid: Uuid, payload: Value) -> TestResponseType {
+    let pool = init_test_pool().await;
+    let user: UserSchema = serde_json::from_slice(
+        login_req("testusername1".to_owned(), "testpassword".to_owned())
+            .await
+            .body()
+            .await
+            .unwrap()
+            .to_vec()
+            .as_slice(),
+    )
+    .unwrap();
+    let srv = actix_test::start(move || {
+        App::new()
+            .app_data(web::Data::new(pool.clone()))
+            .service(web::scope("/todo").service(crate::api::todo::move_todo::move_todo))
+    });
+    srv.post(format!("/todo/{uuid}/move"))
+        .insert_header(("Authorization", format!("Bearer {}", user.token)))
+        .send_json(&payload)
+        .await
+        .unwrap()
+}
+
+/// Create todos with the given titles, returns them in the creation order
+async fn create_todos(titles: &[&str]) -> Vec<TodoSchema> {
+    let mut todos = Vec::new();
+    for title in titles {
+        todos.push(parse(create_todo_req(title.to_string(), "pending".to_owned()).await).await);
+    }
+    todos
+}
+
+/// Returns the titles of the todos that contain the given title, in the manual order
+async fn ordered_titles(title: &str) -> Vec<String> {
+    let list: TodoListSchema =
+        parse(list_todo_req(&format!("title={title}&order_by=position&limit=100")).await).await;
+    list.data.into_iter().map(|todo| todo.title).collect()
+}
+
+#[actix_web::test]
+#[serial_test::serial]
+async fn move_todo() {
+    let todos = create_todos(&["move_todo_a", "move_todo_b", "move_todo_c"]).await;
+    assert!(todos[0].position < todos[1].position && todos[1].position < todos[2].position);
+    assert_eq!(
+        ordered_titles("move_todo_").await,
+        ["move_todo_a", "move_todo_b", "move_todo_c"]
+    );
+
+    let res = move_todo_req(todos[2].uuid, json!({"after": todos[0].uuid})).await;
+    check_content_type(&res);
+    check_content_length(&res);
+    assert_eq!(res.status(), 200);
+    let moved: TodoSchema = parse(res).await;
+    assert_eq!(moved.version, todos[2].version + 1);
+    assert!(todos[0].position < moved.position && moved.position < todos[1].position);
+    assert_eq!(
+        ordered_titles("move_todo_").await,
+        ["move_todo_a", "move_todo_c", "move_todo_b"]
+    );
+
+    // To the end of the list
+    let res = move_todo_req(todos[0].uuid, json!({"after": todos[1].uuid})).await;
+    assert_eq!(res.status(), 200);
+    assert_eq!(
+        ordered_titles("move_todo_").await,
+        ["move_todo_c", "move_todo_b", "move_todo_a"]
+    );
+    // To the start of the list
+    let res = move_todo_req(todos[1].uuid, json!({"before": todos[2].uuid})).await;
+    assert_eq!(res.status(), 200);
+    assert_eq!(
+        ordered_titles("move_todo_").await,
+        ["move_todo_b", "move_todo_c", "move_todo_a"]
+    );
+    // Between two todos
+    let res = move_todo_req(
+        todos[0].uuid,
+        json!({"after": todos[1].uuid, "before": todos[2].uuid}),
+    )
+    .await;
+    assert_eq!(res.status(), 200);
+    assert_eq!(
+        ordered_titles("move_todo_").await,
+        ["move_todo_b", "move_todo_a", "move_todo_c"]
+    );
+}
+
+#[actix_web::test]
+#[serial_test::serial]
+async fn move_todo_invalid() {
+    let todos = create_todos(&["move_invalid_a", "move_invalid_b"]).await;
+    let res = move_todo_req(todos[0].uuid, json!({})).await;
+    check_content_type(&res);
+    check_content_length(&res);
+    assert_eq!(res.status(), 400);
+
+    let res = move_todo_req(todos[0].uuid, json!({"after": todos[0].uuid})).await;
+    assert_eq!(res.status(), 400);
+
+    // The neighbors are in the wrong order
+    let res = move_todo_req(
+        Uuid::nil(),
+        json!({"after": todos[1].uuid, "before": todos[0].uuid}),
+    )
+    .await;
+    assert_eq!(res.status(), 404);
+    let third = create_todos(&["move_invalid_c"]).await.remove(0);
+    let res = move_todo_req(
+        third.uuid,
+        json!({"after": todos[1].uuid, "before": todos[0].uuid}),
+    )
+    .await;
+    assert_eq!(res.status(), 400);
+
+    let res = move_todo_req(todos[0].uuid, json!({"after": Uuid::new_v4()})).await;
+    assert_eq!(res.status(), 404);
+
+    let res = move_todo_req(
+        todos[0].uuid,
+        json!({"after": todos[1].uuid, "version": todos[0].version + 1}),
+    )
+    .await;
+    assert_eq!(res.status(), 412);
+}
+
+#[actix_web::test]
+#[serial_test::serial]
+async fn move_todo_rebalance() {
+    let todos = create_todos(&[
+        "move_rebalance_a",
+        "move_rebalance_b",
+        "move_rebalance_c",
+        "move_rebalance_trashed",
+    ])
+    .await;
+    let trashed: TodoSchema = parse(delete_todo_req(todos[3].uuid).await).await;
+    // Two adjacent todos with the longest positions, there is no short position between them
+    let pool = init_test_pool().await;
+    let low = format!("{}1", todos[0].position.repeat(31));
+    for (todo, position) in [(&todos[0], low.clone()), (&todos[1], low.clone() + "1")] {
+        TodoEntity::update_many()
+            .col_expr(TodoColumn::Position, Expr::value(position))
+            .filter(TodoColumn::Uuid.eq(todo.uuid))
+            .exec(&pool)
+            .await
+            .unwrap();
+    }
+
+    let res = move_todo_req(
+        todos[2].uuid,
+        json!({"after": todos[0].uuid, "before": todos[1].uuid}),
+    )
+    .await;
+    assert_eq!(res.status(), 200);
+    let list: TodoListSchema =
+        parse(list_todo_req("title=move_rebalance_&order_by=position").await).await;
+    let titles: Vec<_> = list.data.iter().map(|todo| todo.title.as_str()).collect();
+    assert_eq!(
+        titles,
+        ["move_rebalance_a", "move_rebalance_c", "move_rebalance_b"]
+    );
+    assert!(list.data.iter().all(|todo| todo.position.len() <= 32));
+
+    // The todo in the trash is not rebalanced
+    let trashed_after = TodoEntity::find()
+        .filter(TodoColumn::Uuid.eq(trashed.uuid))
+        .one(&pool)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(trashed_after.version, trashed.version);
+    assert_eq!(trashed_after.position, trashed.position);
+}
+
+#[test]
+fn move_todo_ranks() {
+    assert_eq!(rank_between(None, None).unwrap(), "V");
+    assert_eq!(rank_between(Some("V"), None).unwrap(), "W");
+    assert_eq!(rank_between(Some("z"), None).unwrap(), "zV");
+    assert_eq!(rank_between(None, Some("1")).unwrap(), "0V");
+    assert_eq!(rank_between(Some("1"), Some("2")).unwrap(), "1V");
+    assert_eq!(rank_between(Some("1"), Some("11")).unwrap(), "10V");
+    assert_eq!(rank_between(Some("A"), Some("Az")), Some("AU".to_owned()));
+    assert_eq!(rank_between(Some("B"), Some("A")), None);
+    assert_eq!(rank_between(Some("A"), Some("A")), None);
+    assert_eq!(rank_between(Some("A0"), None), None);
+    assert_eq!(rank_between(Some("A-"), None), None);
+
+    // Always inserting at the same place keeps the order
+    let (mut low, high) = ("V".to_owned(), "W".to_owned());
+    for _ in 0..100 {
+        let rank = rank_between(Some(&low), Some(&high)).unwrap();
+        assert!(low < rank && rank < high && !rank.ends_with('0'));
+        low = rank;
+    }
+
+    let ranks = spread_ranks(1000);
+    assert_eq!(ranks.len(), 1000);
+    assert!(ranks.windows(2).all(|ranks| ranks[0] < ranks[1]));
+    assert!(ranks
+        .iter()
+        .all(|rank| rank.len() <= 3 && !rank.ends_with('0')));
+    assert!(spread_ranks(0).is_empty());
+}
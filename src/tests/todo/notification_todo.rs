@@ -0,0 +1,441 @@
+use std::io::{BufRead, BufReader, Write};
+use std::net::{TcpListener, TcpStream};
+use std::sync::{atomic::Ordering, Arc, Mutex};
+use std::{env, thread};
+
+use crate::api::webhook::utils::sign;
+use crate::notifications;
+use crate::schemas::notification::{
+    InboxMessageSchema, InboxSchema, NotificationListSchema, NotificationPreferenceSchema,
+    NotificationPreferencesSchema,
+};
+use crate::schemas::todo::{TodoEventSchema, TodoEventType, TodoSchema};
+use crate::schemas::user::UserSchema;
+use crate::tests::login::login_req;
+use crate::tests::todo::create_todo::create_todo_req;
+use crate::tests::todo::delete_todo::delete_todo_req;
+use crate::tests::todo::webhook_todo::Receiver;
+use crate::tests::{
+    check_content_length, check_content_type, init_test_pool, parse, TestResponseType,
+};
+use actix_http::Method;
+use actix_web::{web, App};
+use chrono::Utc;
+use entity::notification::Status as NotificationStatus;
+use entity::notification_preference::Channel;
+use serde_json::{json, Value};
+use uuid::Uuid;
+
+/// A local SMTP sink, accepts all the emails and records their data
+#[derive(Clone, Default)]
+struct SmtpSink {
+    messages: Arc<Mutex<Vec<String>>>,
+}
+
+impl SmtpSink {
+    /// Start the sink on a random port, returns its URL
+    fn start(&self) -> String {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("smtp://{}", listener.local_addr().unwrap());
+        let messages = Arc::clone(&self.messages);
+        thread::spawn(move || {
+            for stream in listener.incoming().flatten() {
+                session(stream, &messages).ok();
+            }
+        });
+        url
+    }
+
+    fn take(&self) -> Vec<String> {
+        std::mem::take(&mut *self.messages.lock().unwrap())
+    }
+}
+
+/// Reply to the SMTP commands of the client, and record the data of the emails
+fn session(mut stream: TcpStream, messages: &Mutex<Vec<String>>) -> std::io::Result<()> {
+    let mut reader = BufReader::new(stream.try_clone()?);
+    let mut line = String::new();
+    stream.write_all(b"220 localhost ESMTP\r\n")?;
+    loop {
+        line.clear();
+        if reader.read_line(&mut line)? == 0 {
+            return Ok(());
+        }
+        match line.trim_end().to_ascii_uppercase().as_str() {
+            "DATA" => {
+                stream.write_all(b"354 End data with <CR><LF>.<CR><LF>\r\n")?;
+                let mut data = String::new();
+                loop {
+                    line.clear();
+                    if reader.read_line(&mut line)? == 0 || line == ".\r\n" {
+                        break;
+                    }
+                    data.push_str(&line);
+                }
+                messages.lock().unwrap().push(data);
+                stream.write_all(b"250 OK\r\n")?;
+            }
+            "QUIT" => return stream.write_all(b"221 Bye\r\n"),
+            _ => stream.write_all(b"250 OK\r\n")?,
+        }
+    }
+}
+
+/// Returns a URL of a closed port, the emails to it are failed
+fn closed_smtp_url() -> String {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    format!("smtp://{}", listener.local_addr().unwrap())
+}
+
+pub async fn notification_req(
+    method: Method,
+    path: &str,
+    payload: Option<Value>,
+) -> TestResponseType {
+    let pool = init_test_pool().await;
+    let user: UserSchema = serde_json::from_slice(
+        login_req("testusername1".to_owned(), "testpassword".to_owned())
+            .await
+            .body()
+            .await
+            .unwrap()
+            .to_vec()
+            .as_slice(),
+    )
+    .unwrap();
+    let srv = actix_test::start(move || {
+        App::new()
+            .app_data(web::Data::new(pool.clone()))
+            .service(web::scope("/api").configure(crate::api::notification::init_routes))
+    });
+    let req = srv
+        .request(method, srv.url(&format!("/api/notifications{path}")))
+        .insert_header(("Authorization", format!("Bearer {}", user.token)));
+    match payload {
+        Some(payload) => req.send_json(&payload).await,
+        None => req.send().await,
+    }
+    .unwrap()
+}
+
+async fn update_preferences(data: Value) -> TestResponseType {
+    notification_req(Method::PUT, "/preferences", Some(json!({ "data": data }))).await
+}
+
+/// Returns the token of the verification email, checks that it's sent to the address
+fn verification_token(email: &str, address: &str) -> String {
+    assert!(email.contains(address));
+    assert!(email.contains("Subject: Verify your email address"));
+    email
+        .lines()
+        .map(str::trim)
+        .find(|line| line.len() == 64 && line.chars().all(|c| c.is_ascii_hexdigit()))
+        .unwrap()
+        .to_owned()
+}
+
+async fn verify_email(token: &str) -> TestResponseType {
+    notification_req(
+        Method::POST,
+        "/preferences/verify",
+        Some(json!({ "token": token })),
+    )
+    .await
+}
+
+async fn dispatch_due(now: i64) -> u64 {
+    notifications::dispatch_due(now, &init_test_pool().await)
+        .await
+        .unwrap()
+}
+
+async fn create_todo() -> TodoSchema {
+    parse(
+        create_todo_req(
+            format!("notification_todo_{}", Uuid::new_v4().simple()),
+            "pending".to_owned(),
+        )
+        .await,
+    )
+    .await
+}
+
+#[actix_web::test]
+#[serial_test::serial]
+async fn update_preferences_invalid() {
+    env::remove_var("SMTP_URL");
+    let email = json!([{"channel": "email", "target": "user@example.com"}]);
+    assert_eq!(update_preferences(email.clone()).await.status(), 400);
+
+    env::set_var("SMTP_URL", closed_smtp_url());
+    assert_eq!(update_preferences(email).await.status(), 200);
+    for data in [
+        json!([{"channel": "email", "target": "user@"}]),
+        json!([{"channel": "email"}]),
+        json!([{"channel": "webhook", "target": "ftp://example.com/hook"}]),
+        json!([{"channel": "webhook"}]),
+        json!([{"channel": "inbox", "target": "user@example.com"}]),
+        json!([{"channel": "inbox"}, {"channel": "inbox", "events": ["todo.created"]}]),
+        json!([{"channel": "inbox", "events": []}]),
+        json!([{"channel": "inbox", "events": ["todo.moved"]}]),
+        json!([{"channel": "sms", "target": "+10000000000"}]),
+    ] {
+        assert_eq!(update_preferences(data).await.status(), 400);
+    }
+    env::remove_var("SMTP_URL");
+
+    // The invalid preferences don't replace the current ones
+    let res = notification_req(Method::GET, "/preferences", None).await;
+    assert_eq!(res.status(), 200);
+    let preferences: NotificationPreferencesSchema = parse(res).await;
+    assert_eq!(preferences.data.len(), 1);
+    assert_eq!(preferences.data[0].channel, Channel::Email);
+    assert_eq!(update_preferences(json!([])).await.status(), 200);
+}
+
+#[actix_web::test]
+#[serial_test::serial]
+async fn inbox_message_not_found() {
+    let res = notification_req(
+        Method::POST,
+        &format!("/inbox/{}/read", Uuid::new_v4()),
+        None,
+    )
+    .await;
+    assert_eq!(res.status(), 404);
+}
+
+#[actix_web::test]
+#[serial_test::serial]
+async fn email_verification() {
+    let sink = SmtpSink::default();
+    env::set_var("SMTP_URL", sink.start());
+    let receiver = Receiver::default();
+    let receiver_srv = receiver.start();
+    let data = json!([
+        {"channel": "email", "target": "verify@example.com"},
+        {"channel": "webhook", "target": receiver_srv.url("/hook")},
+    ]);
+
+    let preferences: NotificationPreferencesSchema =
+        parse(update_preferences(data.clone()).await).await;
+    assert!(!preferences.data[0].verified);
+    assert!(preferences.data[0].secret.is_none());
+    let secret = preferences.data[1].secret.clone().unwrap();
+
+    // The verification email is sent by the dispatcher
+    assert!(sink.take().is_empty());
+    let notifications: NotificationListSchema =
+        parse(notification_req(Method::GET, "?status=pending", None).await).await;
+    assert_eq!(notifications.data.len(), 1);
+    assert_eq!(
+        notifications.data[0].event,
+        notifications::VERIFICATION_EVENT
+    );
+    assert_eq!(notifications.data[0].todo_uuid, None);
+    assert_eq!(dispatch_due(Utc::now().naive_utc().timestamp()).await, 1);
+    let emails = sink.take();
+    assert_eq!(emails.len(), 1);
+    let token = verification_token(&emails[0], "verify@example.com");
+
+    // The unverified address doesn't get the notifications
+    create_todo().await;
+    let notifications: NotificationListSchema =
+        parse(notification_req(Method::GET, "?status=pending", None).await).await;
+    assert_eq!(notifications.data.len(), 1);
+    assert_eq!(notifications.data[0].channel, Channel::Webhook);
+    assert_eq!(dispatch_due(Utc::now().naive_utc().timestamp()).await, 1);
+    assert!(sink.take().is_empty());
+    let requests = receiver.take();
+    assert_eq!(requests.len(), 1);
+    let (signature, _, body) = &requests[0];
+    assert_eq!(signature, &sign(&secret, body));
+
+    assert_eq!(
+        verify_email(&Uuid::new_v4().simple().to_string())
+            .await
+            .status(),
+        400
+    );
+    let res = verify_email(&token).await;
+    assert_eq!(res.status(), 200);
+    let preference: NotificationPreferenceSchema = parse(res).await;
+    assert_eq!(preference.channel, Channel::Email);
+    assert!(preference.verified);
+    assert_eq!(verify_email(&token).await.status(), 400);
+
+    // The verified address and the webhook secret are kept if the targets are unchanged
+    let preferences: NotificationPreferencesSchema = parse(update_preferences(data).await).await;
+    assert!(preferences.data[0].verified);
+    assert!(preferences.data[1].secret.is_none());
+    assert!(sink.take().is_empty());
+    create_todo().await;
+    assert_eq!(dispatch_due(Utc::now().naive_utc().timestamp()).await, 2);
+    assert_eq!(sink.take().len(), 1);
+    let (signature, _, body) = &receiver.take()[0];
+    assert_eq!(signature, &sign(&secret, body));
+
+    // A new address should be verified again, only the last verification email is sent
+    let data = json!([{"channel": "email", "target": "other@example.com"}]);
+    assert_eq!(update_preferences(data.clone()).await.status(), 200);
+    let preferences: NotificationPreferencesSchema = parse(update_preferences(data).await).await;
+    assert!(!preferences.data[0].verified);
+    assert_eq!(dispatch_due(Utc::now().naive_utc().timestamp()).await, 1);
+    let emails = sink.take();
+    assert_eq!(emails.len(), 1);
+    assert_ne!(verification_token(&emails[0], "other@example.com"), token);
+
+    assert_eq!(update_preferences(json!([])).await.status(), 200);
+    env::remove_var("SMTP_URL");
+}
+
+#[actix_web::test]
+#[serial_test::serial]
+async fn notifications_dispatch() {
+    let sink = SmtpSink::default();
+    env::set_var("SMTP_URL", sink.start());
+    let receiver = Receiver::default();
+    let receiver_srv = receiver.start();
+
+    let res = update_preferences(json!([
+        {"channel": "email", "target": "notify@example.com", "events": ["todo.created"]},
+        {"channel": "webhook", "target": receiver_srv.url("/hook")},
+        {"channel": "inbox", "events": ["todo.deleted", "todo.created"]},
+    ]))
+    .await;
+    check_content_type(&res);
+    check_content_length(&res);
+    assert_eq!(res.status(), 200);
+    let preferences: NotificationPreferencesSchema = parse(res).await;
+    assert_eq!(preferences.data.len(), 3);
+    assert_eq!(preferences.data[0].channel, Channel::Email);
+    let secret = preferences.data[1].secret.clone().unwrap();
+    assert_eq!(dispatch_due(Utc::now().naive_utc().timestamp()).await, 1);
+    let token = verification_token(&sink.take()[0], "notify@example.com");
+    assert_eq!(verify_email(&token).await.status(), 200);
+    assert_eq!(preferences.data[1].events, TodoEventType::ALL.to_vec());
+    assert_eq!(
+        preferences.data[2].events,
+        vec![TodoEventType::Created, TodoEventType::Deleted]
+    );
+    let res = notification_req(Method::GET, "/preferences", None).await;
+    let listed: NotificationPreferencesSchema = parse(res).await;
+    assert_eq!(listed.data.len(), 3);
+    assert_eq!(listed.data[2].target, None);
+    assert!(listed.data[0].verified);
+    assert!(listed.data[1].secret.is_none());
+
+    // The notifications are queued with the change, and sent by the dispatcher
+    let todo = create_todo().await;
+    assert!(sink.take().is_empty());
+    let notifications: NotificationListSchema =
+        parse(notification_req(Method::GET, "?status=pending", None).await).await;
+    assert_eq!(notifications.data.len(), 3);
+    assert_eq!(dispatch_due(Utc::now().naive_utc().timestamp()).await, 3);
+
+    let emails = sink.take();
+    assert_eq!(emails.len(), 1);
+    assert!(emails[0].contains("notify@example.com"));
+    assert!(emails[0].contains(&format!("Subject: New todo: {}", todo.title)));
+    assert!(emails[0].contains(&todo.uuid.to_string()));
+
+    let requests = receiver.take();
+    assert_eq!(requests.len(), 1);
+    let (signature, event, body) = &requests[0];
+    assert_eq!(signature, &sign(&secret, body));
+    assert_eq!(event, "todo.created");
+    let payload: TodoEventSchema = serde_json::from_str(body).unwrap();
+    assert_eq!(payload.event, TodoEventType::Created);
+    assert_eq!(payload.todo.uuid, todo.uuid);
+
+    let inbox: InboxSchema = parse(notification_req(Method::GET, "/inbox", None).await).await;
+    assert_eq!(inbox.unread, 1);
+    assert_eq!(inbox.data.len(), 1);
+    assert_eq!(inbox.data[0].todo_uuid, todo.uuid);
+    assert_eq!(inbox.data[0].event, "todo.created");
+    assert!(inbox.data[0].read_at.is_none());
+
+    let notifications: NotificationListSchema =
+        parse(notification_req(Method::GET, "", None).await).await;
+    let notifications = notifications
+        .data
+        .into_iter()
+        .filter(|notification| notification.todo_uuid == Some(todo.uuid))
+        .collect::<Vec<_>>();
+    assert_eq!(notifications.len(), 3);
+    assert!(notifications.iter().all(|notification| notification.status
+        == NotificationStatus::Sent
+        && notification.attempts == 1
+        && notification.sent_at.is_some()));
+
+    // Mark the inbox message as read
+    let res = notification_req(
+        Method::POST,
+        &format!("/inbox/{}/read", inbox.data[0].uuid),
+        None,
+    )
+    .await;
+    assert_eq!(res.status(), 200);
+    let message: InboxMessageSchema = parse(res).await;
+    assert!(message.read_at.is_some());
+    let inbox: InboxSchema =
+        parse(notification_req(Method::GET, "/inbox?unread=true", None).await).await;
+    assert_eq!(inbox.unread, 0);
+    assert!(inbox.data.is_empty());
+
+    // Only the chosen events are sent to each channel
+    assert_eq!(delete_todo_req(todo.uuid).await.status(), 200);
+    assert_eq!(dispatch_due(Utc::now().naive_utc().timestamp()).await, 2);
+    assert!(sink.take().is_empty());
+    assert_eq!(receiver.take().len(), 1);
+    let inbox: InboxSchema = parse(notification_req(Method::GET, "/inbox", None).await).await;
+    assert_eq!(inbox.unread, 1);
+    assert_eq!(inbox.data.len(), 2);
+    assert_eq!(inbox.data[0].event, "todo.deleted");
+
+    // The failed notifications are retried with backoff, then marked as failed
+    env::set_var("SMTP_URL", closed_smtp_url());
+    receiver.status.store(500, Ordering::SeqCst);
+    create_todo().await;
+    let now = Utc::now().naive_utc().timestamp();
+    assert_eq!(dispatch_due(now).await, 3);
+    let pending: NotificationListSchema =
+        parse(notification_req(Method::GET, "?status=pending", None).await).await;
+    assert_eq!(pending.data.len(), 2);
+    assert!(pending
+        .data
+        .iter()
+        .all(|notification| notification.attempts == 1
+            && notification.error.is_some()
+            && notification.next_attempt_at
+                == Some(now + crate::api::webhook::utils::retry_delay(1))));
+    assert_eq!(dispatch_due(now).await, 0);
+
+    let mut later = now;
+    for attempt in 2..=notifications::notification_max_attempts() {
+        later += crate::api::webhook::utils::retry_delay(attempt - 1);
+        assert_eq!(dispatch_due(later).await, 2);
+    }
+    assert_eq!(dispatch_due(later + 1_000_000).await, 0);
+    let failed: NotificationListSchema =
+        parse(notification_req(Method::GET, "?status=failed", None).await).await;
+    assert_eq!(failed.data.len(), 2);
+    assert!(failed
+        .data
+        .iter()
+        .all(|notification| notification.next_attempt_at.is_none()
+            && notification.attempts == notifications::notification_max_attempts()));
+    let requests = receiver.take();
+    assert_eq!(
+        requests.len() as u32,
+        notifications::notification_max_attempts()
+    );
+    assert!(requests.iter().all(|(_, event, _)| event == "todo.created"));
+
+    // Disable the notifications
+    let res = update_preferences(json!([])).await;
+    assert_eq!(res.status(), 200);
+    env::remove_var("SMTP_URL");
+    create_todo().await;
+    assert_eq!(dispatch_due(Utc::now().naive_utc().timestamp()).await, 0);
+}
@@ -0,0 +1,200 @@
+use crate::schemas::message::MessageSchema;
+use crate::schemas::todo::TodoSchema;
+use crate::schemas::user::UserSchema;
+use crate::tests::login::login_req;
+use crate::tests::todo::create_todo::create_todo_req;
+use crate::tests::{
+    check_content_length, check_content_type, init_test_pool, parse, TestResponseType,
+};
+use actix_web::{web, App};
+use entity::todo::Status as TodoStatus;
+use serde_json::{json, Value};
+use uuid::Uuid;
+
+const MERGE_PATCH: &str = "application/merge-patch+json";
+const JSON_PATCH: &str = "application/json-patch+json";
+
+pub async fn patch_todo_req(
+    uuid: Uuid,
+    content_type: &str,
+    payload: Value,
+    headers: &[(&str, &str)],
+) -> TestResponseType {
+    let pool = init_test_pool().await;
+    let user: UserSchema = serde_json::from_slice(
+        login_req("testusername1".to_owned(), "testpassword".to_owned())
+            .await
+            .body()
+            .await
+            .unwrap()
+            .to_vec()
+            .as_slice(),
+    )
+    .unwrap();
+    let srv = actix_test::start(move || {
+        App::new()
+            .app_data(web::Data::new(pool.clone()))
+            .service(web::scope("/todo").service(crate::api::todo::patch::patch_todo))
+    });
+    let mut req = srv
+        .patch(format!("/todo/{uuid}"))
+        .insert_header(("Authorization", format!("Bearer {}", user.token)))
+        .insert_header(("Content-Type", content_type));
+    for header in headers {
+        req = req.insert_header(*header);
+    }
+    req.send_body(payload.to_string()).await.unwrap()
+}
+
+async fn create(title: &str) -> TodoSchema {
+    parse(create_todo_req(title.to_owned(), "pending".to_owned()).await).await
+}
+
+#[actix_web::test]
+#[serial_test::serial]
+async fn patch_todo_merge_patch() {
+    let todo = create("patch_merge").await;
+    let res = patch_todo_req(todo.uuid, MERGE_PATCH, json!({"status": "completed"}), &[]).await;
+    check_content_type(&res);
+    check_content_length(&res);
+    assert_eq!(res.status(), 200);
+    let patched: TodoSchema = parse(res).await;
+    assert_eq!(patched.title, "patch_merge");
+    assert_eq!(patched.status, TodoStatus::Completed);
+    assert!(patched.completed_at.is_some());
+    assert_eq!(patched.version, todo.version + 1);
+
+    // An empty patch doesn't change the todo
+    let res = patch_todo_req(todo.uuid, MERGE_PATCH, json!({}), &[]).await;
+    let unchanged: TodoSchema = parse(res).await;
+    assert_eq!(unchanged.version, patched.version);
+}
+
+#[actix_web::test]
+#[serial_test::serial]
+async fn patch_todo_json_patch() {
+    let todo = create("patch_json").await;
+    let res = patch_todo_req(
+        todo.uuid,
+        JSON_PATCH,
+        json!([
+            {"op": "test", "path": "/version", "value": todo.version},
+            {"op": "replace", "path": "/title", "value": "patch_json_renamed"},
+            {"op": "replace", "path": "/status", "value": "progress"}
+        ]),
+        &[],
+    )
+    .await;
+    assert_eq!(res.status(), 200);
+    let patched: TodoSchema = parse(res).await;
+    assert_eq!(patched.title, "patch_json_renamed");
+    assert_eq!(patched.status, TodoStatus::Progress);
+
+    // The version is changed, so the `test` operation fails with the current todo
+    let res = patch_todo_req(
+        todo.uuid,
+        JSON_PATCH,
+        json!([
+            {"op": "test", "path": "/version", "value": todo.version},
+            {"op": "replace", "path": "/status", "value": "pending"}
+        ]),
+        &[],
+    )
+    .await;
+    check_content_type(&res);
+    assert_eq!(res.status(), 412);
+    let current: TodoSchema = parse(res).await;
+    assert_eq!(current.version, patched.version);
+    assert_eq!(current.status, TodoStatus::Progress);
+
+    // The other failed `test` operations are conflicts
+    let res = patch_todo_req(
+        todo.uuid,
+        JSON_PATCH,
+        json!([
+            {"op": "test", "path": "/title", "value": "patch_json_other"},
+            {"op": "replace", "path": "/status", "value": "pending"}
+        ]),
+        &[],
+    )
+    .await;
+    check_content_type(&res);
+    assert_eq!(res.status(), 409);
+}
+
+#[actix_web::test]
+#[serial_test::serial]
+async fn patch_todo_version() {
+    let todo = create("patch_version").await;
+    let res = patch_todo_req(
+        todo.uuid,
+        MERGE_PATCH,
+        json!({"status": "cancelled", "version": todo.version + 1}),
+        &[],
+    )
+    .await;
+    assert_eq!(res.status(), 412);
+    let etag = format!("\"{}-{}\"", todo.uuid.simple(), todo.version + 1);
+    let res = patch_todo_req(
+        todo.uuid,
+        MERGE_PATCH,
+        json!({"status": "cancelled"}),
+        &[("If-Match", &etag)],
+    )
+    .await;
+    assert_eq!(res.status(), 412);
+}
+
+#[actix_web::test]
+#[serial_test::serial]
+async fn patch_todo_validations() {
+    let todo = create("patch_validations").await;
+    create("patch_validations_exists").await;
+    for (content_type, payload) in [
+        (MERGE_PATCH, json!({"title": ""})),
+        (MERGE_PATCH, json!({"title": "patch_validations_exists"})),
+        (MERGE_PATCH, json!({"title": null})),
+        (MERGE_PATCH, json!({"status": "done"})),
+        (MERGE_PATCH, json!({"created_at": 0})),
+        (MERGE_PATCH, json!({"description": "invalid field"})),
+        (MERGE_PATCH, json!(["not an object"])),
+        (JSON_PATCH, json!([{"op": "remove", "path": "/uuid"}])),
+        (
+            JSON_PATCH,
+            json!([{"op": "replace", "path": "/invalid/path", "value": 1}]),
+        ),
+        (JSON_PATCH, json!({"op": "replace"})),
+    ] {
+        let res = patch_todo_req(todo.uuid, content_type, payload.clone(), &[]).await;
+        check_content_type(&res);
+        check_content_length(&res);
+        assert_eq!(res.status(), 400, "{payload}");
+    }
+    let todo_after: TodoSchema =
+        parse(patch_todo_req(todo.uuid, MERGE_PATCH, json!({}), &[]).await).await;
+    assert_eq!(todo_after.version, todo.version);
+}
+
+#[rstest::rstest]
+#[case::json("application/json")]
+#[case::text("text/plain")]
+#[case::missing("")]
+#[actix_web::test]
+#[serial_test::serial]
+async fn patch_unsupported_content_type(#[case] content_type: &str) {
+    let todo = create(&format!("patch_content_type_{}", Uuid::new_v4().simple())).await;
+    let res = patch_todo_req(todo.uuid, content_type, json!({"status": "completed"}), &[]).await;
+    check_content_type(&res);
+    assert_eq!(res.status(), 415);
+    let message: MessageSchema = parse(res).await;
+    assert!(message.message.contains(MERGE_PATCH), "{}", message.message);
+    assert!(message.message.contains(JSON_PATCH), "{}", message.message);
+}
+
+#[actix_web::test]
+#[serial_test::serial]
+async fn patch_invalid_todo() {
+    let res = patch_todo_req(Uuid::new_v4(), MERGE_PATCH, json!({}), &[]).await;
+    check_content_type(&res);
+    assert_eq!(res.status(), 404);
+}
@@ -0,0 +1,74 @@
+use crate::errors::Error as ApiError;
+use crate::schemas::todo::TodoStatsSchema;
+use crate::schemas::user::UserSchema;
+use crate::tests::login::login_req;
+use crate::tests::{check_content_length, check_content_type, init_test_pool, TestResponseType};
+use actix_web::web::{self, QueryConfig};
+use actix_web::App;
+
+pub async fn stats_todo_req(params: &str) -> TestResponseType {
+    let pool = init_test_pool().await;
+    let user: UserSchema = serde_json::from_slice(
+        login_req("testusername1".to_owned(), "testpassword".to_owned())
+            .await
+            .body()
+            .await
+            .unwrap()
+            .to_vec()
+            .as_slice(),
+    )
+    .unwrap();
+    let srv = actix_test::start(move || {
+        App::new()
+            .app_data(web::Data::new(pool.clone()))
+            .app_data(QueryConfig::default().error_handler(|err, _| ApiError::from(err).into()))
+            .service(web::scope("/todo").service(crate::api::todo::stats::stats))
+    });
+    srv.get(format!("/todo/stats?{params}"))
+        .insert_header(("Authorization", format!("Bearer {}", user.token)))
+        .send()
+        .await
+        .unwrap()
+}
+
+#[rstest::rstest]
+#[case::default_range("", 200)]
+#[case::day_period("period=day", 200)]
+#[case::week_period("period=week", 200)]
+#[case::custom_range("from=0&to=86400", 200)]
+#[case::bad_period("period=month", 400)]
+#[case::bad_range("from=86400&to=0", 400)]
+#[case::too_large_range("from=0&period=day", 400)]
+#[case::min_from("from=-9223372036854775808&to=0", 400)]
+#[case::min_to("to=-9223372036854775808", 400)]
+#[case::max_to("from=0&to=9223372036854775807&period=week", 400)]
+#[actix_web::test]
+#[serial_test::serial]
+async fn stats_todo_endpoint(#[case] params: &str, #[case] status_code: u16) {
+    let res = stats_todo_req(params).await;
+    check_content_type(&res);
+    check_content_length(&res);
+    assert_eq!(res.status(), status_code);
+}
+
+#[actix_web::test]
+#[serial_test::serial]
+async fn stats_todo_counts() {
+    let mut res = stats_todo_req("period=week").await;
+    assert_eq!(res.status(), 200);
+    let stats: TodoStatsSchema =
+        serde_json::from_slice(res.body().await.unwrap().to_vec().as_slice()).unwrap();
+    // The todos that created in `create_todo` tests, one todo for each status
+    assert_eq!(stats.total, 4);
+    assert_eq!(stats.status.completed, 1);
+    assert_eq!(stats.status.pending, 1);
+    assert_eq!(stats.status.progress, 1);
+    assert_eq!(stats.status.cancelled, 1);
+    assert_eq!(stats.trend.iter().map(|t| t.created).sum::<u64>(), 4);
+    assert_eq!(stats.trend.iter().map(|t| t.completed).sum::<u64>(), 1);
+    assert_eq!(stats.average_completion_time, Some(0));
+    assert!(stats
+        .trend
+        .windows(2)
+        .all(|t| t[0].end == t[1].start && t[0].start < t[1].start));
+}
@@ -0,0 +1,219 @@
+use crate::api::todo::utils;
+use crate::schemas::sync::{SyncChangeStatus, SyncPushResultSchema, SyncSchema};
+use crate::schemas::todo::TodoSchema;
+use crate::schemas::user::UserSchema;
+use crate::tests::login::login_req;
+use crate::tests::todo::create_todo::create_todo_req;
+use crate::tests::todo::delete_todo::delete_todo_req;
+use crate::tests::todo::update_todo::update_todo_req;
+use crate::tests::{
+    check_content_length, check_content_type, init_test_pool, parse, TestResponseType,
+};
+use actix_web::{web, App};
+use chrono::Utc;
+use serde_json::{json, Value};
+use uuid::Uuid;
+
+pub async fn sync_req(params: &str) -> TestResponseType {
+    let pool = init_test_pool().await;
+    let user: UserSchema = serde_json::from_slice(
+        login_req("testusername1".to_owned(), "testpassword".to_owned())
+            .await
+            .body()
+            .await
+            .unwrap()
+            .to_vec()
+            .as_slice(),
+    )
+    .unwrap();
+    let srv = actix_test::start(move || {
+        App::new()
+            .app_data(web::Data::new(pool.clone()))
+            .service(web::scope("/api").service(crate::api::sync::sync))
+    });
+    srv.get(format!("/api/sync?{params}"))
+        .insert_header(("Authorization", format!("Bearer {}", user.token)))
+        .send()
+        .await
+        .unwrap()
+}
+
+pub async fn sync_push_req(payload: Value) -> TestResponseType {
+    let pool = init_test_pool().await;
+    let user: UserSchema = serde_json::from_slice(
+        login_req("testusername1".to_owned(), "testpassword".to_owned())
+            .await
+            .body()
+            .await
+            .unwrap()
+            .to_vec()
+            .as_slice(),
+    )
+    .unwrap();
+    let srv = actix_test::start(move || {
+        App::new()
+            .app_data(web::Data::new(pool.clone()))
+            .service(web::scope("/api").service(crate::api::sync::push))
+    });
+    srv.post("/api/sync")
+        .insert_header(("Authorization", format!("Bearer {}", user.token)))
+        .send_json(&payload)
+        .await
+        .unwrap()
+}
+
+#[actix_web::test]
+#[serial_test::serial]
+async fn sync_todo() {
+    let updated: TodoSchema =
+        parse(create_todo_req("sync_todo_updated".to_owned(), "pending".to_owned()).await).await;
+    let deleted: TodoSchema =
+        parse(create_todo_req("sync_todo_deleted".to_owned(), "pending".to_owned()).await).await;
+
+    let res = sync_req("").await;
+    check_content_type(&res);
+    check_content_length(&res);
+    assert_eq!(res.status(), 200);
+    let full: SyncSchema = parse(res).await;
+    assert!(full.todos.iter().any(|todo| todo.uuid == updated.uuid));
+    assert!(full.todos.iter().any(|todo| todo.uuid == deleted.uuid));
+    assert!(full.deleted.is_empty());
+
+    assert_eq!(
+        update_todo_req(updated.uuid, "sync_todo_updated", "completed")
+            .await
+            .status(),
+        200
+    );
+    assert_eq!(delete_todo_req(deleted.uuid).await.status(), 200);
+    let created: TodoSchema =
+        parse(create_todo_req("sync_todo_created".to_owned(), "pending".to_owned()).await).await;
+
+    let delta: SyncSchema = parse(sync_req(&format!("since={}", full.token)).await).await;
+    let uuids: Vec<_> = delta.todos.iter().map(|todo| todo.uuid).collect();
+    assert_eq!(uuids.len(), 2);
+    assert!(uuids.contains(&updated.uuid) && uuids.contains(&created.uuid));
+    let synced = delta.todos.iter().find(|t| t.uuid == updated.uuid).unwrap();
+    assert_eq!(synced.status.as_str(), "completed");
+    assert_eq!(delta.deleted.len(), 1);
+    assert_eq!(delta.deleted[0].uuid, deleted.uuid);
+    assert_ne!(delta.token, full.token);
+
+    // Nothing is changed since the last sync
+    let empty: SyncSchema = parse(sync_req(&format!("since={}", delta.token)).await).await;
+    assert!(empty.todos.is_empty() && empty.deleted.is_empty());
+    assert_eq!(empty.token, delta.token);
+}
+
+#[actix_web::test]
+#[serial_test::serial]
+async fn sync_todo_purged() {
+    let full: SyncSchema = parse(sync_req("").await).await;
+    let todo: TodoSchema =
+        parse(create_todo_req("sync_todo_purged".to_owned(), "pending".to_owned()).await).await;
+    assert_eq!(delete_todo_req(todo.uuid).await.status(), 200);
+    let pool = init_test_pool().await;
+    utils::purge_trash(Utc::now().naive_utc().timestamp() + 1, &pool)
+        .await
+        .unwrap();
+
+    // The todo is created and deleted after the last sync, it's reported as deleted only
+    let delta: SyncSchema = parse(sync_req(&format!("since={}", full.token)).await).await;
+    assert!(delta.todos.is_empty());
+    assert_eq!(delta.deleted.len(), 1);
+    assert_eq!(delta.deleted[0].uuid, todo.uuid);
+}
+
+#[rstest::rstest]
+#[case::not_number("since=abc")]
+#[case::negative("since=-1")]
+#[case::future("since=4000000000")]
+#[actix_web::test]
+#[serial_test::serial]
+async fn sync_todo_invalid_token(#[case] params: &str) {
+    let res = sync_req(params).await;
+    check_content_type(&res);
+    check_content_length(&res);
+    assert_eq!(res.status(), 400);
+}
+
+#[actix_web::test]
+#[serial_test::serial]
+async fn sync_push() {
+    let first: TodoSchema =
+        parse(create_todo_req("sync_push_first".to_owned(), "pending".to_owned()).await).await;
+    let second: TodoSchema =
+        parse(create_todo_req("sync_push_second".to_owned(), "pending".to_owned()).await).await;
+
+    let res = sync_push_req(json!({"changes": [
+        {"op": "update", "uuid": first.uuid, "version": first.version, "title": "sync_push_renamed"},
+        {"op": "update", "uuid": second.uuid, "version": second.version + 1, "status": "completed"},
+        {"op": "create", "title": "sync_push_created"},
+        {"op": "create", "title": ""},
+        {"op": "delete", "uuid": second.uuid, "version": second.version},
+        {"op": "delete", "uuid": Uuid::new_v4(), "version": 1},
+        {"op": "create", "title": "sync_push_created", "status": "completed"},
+        {"op": "update", "uuid": first.uuid},
+    ]}))
+    .await;
+    check_content_type(&res);
+    check_content_length(&res);
+    assert_eq!(res.status(), 200);
+    let result: SyncPushResultSchema = parse(res).await;
+    let statuses: Vec<_> = result.results.iter().map(|r| r.status).collect();
+    assert_eq!(
+        statuses,
+        [
+            SyncChangeStatus::Applied,
+            SyncChangeStatus::Conflict,
+            SyncChangeStatus::Applied,
+            SyncChangeStatus::Rejected,
+            SyncChangeStatus::Applied,
+            SyncChangeStatus::Rejected,
+            SyncChangeStatus::Rejected,
+            SyncChangeStatus::Rejected,
+        ]
+    );
+    assert_eq!(result.applied, 3);
+
+    let renamed = result.results[0].todo.as_ref().unwrap();
+    assert_eq!(renamed.title, "sync_push_renamed");
+    assert_eq!(renamed.version, first.version + 1);
+    // The server copy of the conflicted todo
+    let conflict = result.results[1].todo.as_ref().unwrap();
+    assert_eq!(conflict.uuid, second.uuid);
+    assert_eq!(conflict.status.as_str(), "pending");
+    assert!(result.results[2].uuid.is_some());
+    let errors: Vec<_> = result
+        .results
+        .iter()
+        .filter_map(|r| r.error.as_ref().map(|e| e.status))
+        .collect();
+    assert_eq!(errors, [400, 404, 400, 400]);
+    assert_eq!(
+        result.results[6].error.as_ref().unwrap().message,
+        "The todo `sync_push_created` is already exists"
+    );
+
+    let delta: SyncSchema = parse(sync_req("").await).await;
+    assert!(delta
+        .todos
+        .iter()
+        .any(|todo| todo.title == "sync_push_renamed"));
+    assert!(delta
+        .todos
+        .iter()
+        .all(|todo| todo.uuid != second.uuid && !todo.title.is_empty()));
+}
+
+#[actix_web::test]
+#[serial_test::serial]
+async fn sync_push_too_many_changes() {
+    let changes: Vec<_> = (0..101)
+        .map(|idx| json!({"op": "create", "title": format!("sync_push_many_{idx}")}))
+        .collect();
+    let res = sync_push_req(json!({ "changes": changes })).await;
+    check_content_type(&res);
+    check_content_length(&res);
+    assert_eq!(res.status(), 400);
+}
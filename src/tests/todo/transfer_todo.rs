@@ -0,0 +1,431 @@
+use crate::schemas::todo::{ImportResultSchema, TodoSchema};
+use crate::schemas::user::UserSchema;
+use crate::tests::login::login_req;
+use crate::tests::todo::create_todo::create_todo_req;
+use crate::tests::todo::delete_todo::delete_todo_req;
+use crate::tests::{check_content_length, init_test_pool, parse, TestResponseType};
+use actix_web::{web, App};
+use entity::todo::Status as TodoStatus;
+use serde_json::json;
+
+async fn transfer_server() -> (actix_test::TestServer, String) {
+    let pool = init_test_pool().await;
+    let user: UserSchema = serde_json::from_slice(
+        login_req("testusername1".to_owned(), "testpassword".to_owned())
+            .await
+            .body()
+            .await
+            .unwrap()
+            .to_vec()
+            .as_slice(),
+    )
+    .unwrap();
+    let srv = actix_test::start(move || {
+        App::new().app_data(web::Data::new(pool.clone())).service(
+            web::scope("/api/todos")
+                .service(crate::api::todo::export::export)
+                .service(crate::api::todo::import::import),
+        )
+    });
+    (srv, user.token)
+}
+
+/// The server is returned with the response, the exported file is streamed while the server is running
+pub async fn export_req(params: &str) -> (actix_test::TestServer, TestResponseType) {
+    let (srv, token) = transfer_server().await;
+    let res = srv
+        .get(format!("/api/todos/export?{params}"))
+        .insert_header(("Authorization", format!("Bearer {token}")))
+        .send()
+        .await
+        .unwrap();
+    (srv, res)
+}
+
+async fn export_body(params: &str) -> String {
+    let (_srv, res) = export_req(params).await;
+    body(res).await
+}
+
+pub async fn import_req(params: &str, body: impl Into<String>) -> TestResponseType {
+    let (srv, token) = transfer_server().await;
+    srv.post(format!("/api/todos/import?{params}"))
+        .insert_header(("Authorization", format!("Bearer {token}")))
+        .send_body(body.into())
+        .await
+        .unwrap()
+}
+
+async fn body(mut res: TestResponseType) -> String {
+    String::from_utf8(res.body().await.unwrap().to_vec()).unwrap()
+}
+
+#[actix_web::test]
+#[serial_test::serial]
+async fn export_todos() {
+    let first: TodoSchema =
+        parse(create_todo_req("export_todo_first".to_owned(), "pending".to_owned()).await).await;
+    let second: TodoSchema =
+        parse(create_todo_req("export_todo, \"second\"".to_owned(), "completed".to_owned()).await)
+            .await;
+
+    let (_srv, res) = export_req("").await;
+    assert_eq!(res.status(), 200);
+    assert_eq!(
+        res.headers().get("Content-Type").unwrap(),
+        "application/json"
+    );
+    assert_eq!(
+        res.headers().get("Content-Disposition").unwrap(),
+        "attachment; filename=\"todos.json\""
+    );
+    let todos: Vec<TodoSchema> = parse(res).await;
+    let first_idx = todos.iter().position(|t| t.uuid == first.uuid).unwrap();
+    let second_idx = todos.iter().position(|t| t.uuid == second.uuid).unwrap();
+    assert!(first_idx < second_idx);
+    assert!(todos.windows(2).all(|t| t[0].position <= t[1].position));
+
+    let (_srv, res) = export_req("format=csv").await;
+    assert_eq!(res.status(), 200);
+    assert_eq!(
+        res.headers().get("Content-Type").unwrap(),
+        "text/csv; charset=utf-8"
+    );
+    let csv = body(res).await;
+    let mut lines = csv.lines();
+    assert_eq!(
+        lines.next().unwrap(),
+        "uuid,title,status,created_at,updated_at,completed_at,version,deleted_at,position"
+    );
+    assert_eq!(lines.count(), todos.len());
+    assert!(csv.contains(&format!(
+        "{},\"export_todo, \"\"second\"\"\",completed,",
+        second.uuid
+    )));
+
+    let (_srv, res) = export_req("format=xml").await;
+    assert_eq!(res.status(), 400);
+}
+
+#[actix_web::test]
+#[serial_test::serial]
+async fn import_todos_merge() {
+    let payload = json!([
+        {"title": "import_todo_json", "status": "pending"},
+        {"title": "", "status": "pending"},
+        {"title": "import_todo_json", "status": "completed"},
+        {"title": "import_todo_no_status"},
+        {"title": "import_todo_invalid_status", "status": "done"},
+    ]);
+    let res = import_req("", payload.to_string()).await;
+    check_content_length(&res);
+    assert_eq!(res.status(), 200);
+    let result: ImportResultSchema = parse(res).await;
+    assert_eq!(result.imported, 1);
+    assert_eq!(result.trashed, 0);
+    assert_eq!(
+        result.errors.iter().map(|e| e.row).collect::<Vec<_>>(),
+        vec![2, 3, 4, 5]
+    );
+    assert!(result.errors.iter().all(|e| e.error.status == 400));
+    assert_eq!(result.errors[0].error.message, "The todo title is empty");
+    assert_eq!(
+        result.errors[1].error.message,
+        "The todo `import_todo_json` is already exists"
+    );
+
+    let csv =
+        "status,title,note\ncompleted,\"import_todo, csv\",ignored\npending,import_todo_json,\n";
+    let res = import_req("format=csv&mode=merge", csv).await;
+    assert_eq!(res.status(), 200);
+    let result: ImportResultSchema = parse(res).await;
+    assert_eq!(result.imported, 1);
+    assert_eq!(result.errors.len(), 1);
+    assert_eq!(result.errors[0].row, 2);
+
+    let todos: Vec<TodoSchema> = serde_json::from_str(&export_body("").await).unwrap();
+    let imported = todos
+        .iter()
+        .find(|todo| todo.title == "import_todo, csv")
+        .unwrap();
+    assert_eq!(imported.status, TodoStatus::Completed);
+    assert!(imported.completed_at.is_some());
+}
+
+#[actix_web::test]
+#[serial_test::serial]
+async fn import_export_times() {
+    let payload = json!([
+        {"title": "import_times_completed", "status": "completed", "created_at": 1682899200, "completed_at": 1682985600},
+        {"title": "import_times_pending", "status": "pending", "created_at": 1682899200, "completed_at": 1682985600},
+    ]);
+    let res = import_req("", payload.to_string()).await;
+    assert_eq!(res.status(), 200);
+    let result: ImportResultSchema = parse(res).await;
+    assert_eq!(result.imported, 2);
+
+    let times = |todos: &[TodoSchema]| {
+        ["import_times_completed", "import_times_pending"].map(|title| {
+            let todo = todos.iter().find(|todo| todo.title == title).unwrap();
+            (todo.created_at, todo.completed_at)
+        })
+    };
+    let todos: Vec<TodoSchema> = serde_json::from_str(&export_body("").await).unwrap();
+    // The completion time is ignored if the todo is not completed
+    assert_eq!(
+        times(&todos),
+        [(1682899200, Some(1682985600)), (1682899200, None)]
+    );
+
+    // The times are kept after exporting and importing the file again
+    for format in ["json", "csv"] {
+        let exported = export_body(&format!("format={format}&title=import_times_")).await;
+        let todos: Vec<TodoSchema> =
+            serde_json::from_str(&export_body("title=import_times_").await).unwrap();
+        for todo in todos {
+            assert_eq!(delete_todo_req(todo.uuid).await.status(), 200);
+        }
+        let res = import_req(&format!("format={format}"), exported).await;
+        assert_eq!(res.status(), 200);
+        let result: ImportResultSchema = parse(res).await;
+        assert_eq!(result.imported, 2, "{format}");
+        let todos: Vec<TodoSchema> = serde_json::from_str(&export_body("").await).unwrap();
+        assert_eq!(
+            times(&todos),
+            [(1682899200, Some(1682985600)), (1682899200, None)],
+            "{format}"
+        );
+    }
+}
+
+#[actix_web::test]
+#[serial_test::serial]
+async fn import_invalid_file() {
+    for (params, body) in [
+        ("", "{\"title\": \"import_todo\"}"),
+        ("", "not json"),
+        ("format=csv", "name,state\nimport_todo,pending\n"),
+        ("format=xml", "[]"),
+        ("mode=append", "[]"),
+    ] {
+        let res = import_req(params, body).await;
+        assert_eq!(res.status(), 400, "{params} {body}");
+    }
+}
+
+#[actix_web::test]
+#[serial_test::serial]
+async fn import_todos_replace() {
+    let exported = export_body("format=csv").await;
+    let before: Vec<TodoSchema> = serde_json::from_str(&export_body("").await).unwrap();
+
+    let res = import_req("format=csv&mode=replace", exported).await;
+    assert_eq!(res.status(), 200);
+    let result: ImportResultSchema = parse(res).await;
+    assert!(result.errors.is_empty());
+    assert_eq!(result.trashed, before.len() as u64);
+    assert_eq!(result.imported, before.len() as u64);
+
+    let after: Vec<TodoSchema> = serde_json::from_str(&export_body("").await).unwrap();
+    let fields = |todo: &TodoSchema| {
+        (
+            todo.title.clone(),
+            todo.status.clone(),
+            todo.created_at,
+            todo.completed_at,
+        )
+    };
+    assert_eq!(
+        before.iter().map(fields).collect::<Vec<_>>(),
+        after.iter().map(fields).collect::<Vec<_>>()
+    );
+    assert!(before
+        .iter()
+        .all(|todo| after.iter().all(|new| new.uuid != todo.uuid)));
+}
+
+#[actix_web::test]
+#[serial_test::serial]
+async fn import_export_todotxt() {
+    let dated = [
+        "(A) 2023-05-01 todotxt call mom +family @phone due:2023-05-10",
+        "x 2023-05-03 2023-05-02 todotxt buy milk @store",
+        "2023-05-04 todotxt write  report +work status:progress",
+        "2023-05-06 todotxt dropped idea status:cancelled",
+    ];
+    let file = format!(
+        "{}\n{}\n\ntodotxt without date\n{}\r\n{}\n2023-05-07 todotxt invalid status:completed\nx \n",
+        dated[0], dated[1], dated[2], dated[3]
+    );
+    let res = import_req("format=todotxt", file).await;
+    assert_eq!(res.status(), 200);
+    let result: ImportResultSchema = parse(res).await;
+    assert_eq!(result.imported, 5);
+    assert_eq!(
+        result.errors.iter().map(|e| e.row).collect::<Vec<_>>(),
+        vec![7, 8]
+    );
+
+    let todos: Vec<TodoSchema> = serde_json::from_str(&export_body("").await).unwrap();
+    let find = |title: &str| todos.iter().find(|todo| todo.title == title).unwrap();
+    let called = find("(A) todotxt call mom +family @phone due:2023-05-10");
+    assert_eq!(called.status, TodoStatus::Pending);
+    assert_eq!(called.created_at, 1682899200);
+    let bought = find("todotxt buy milk @store");
+    assert_eq!(bought.status, TodoStatus::Completed);
+    assert_eq!(bought.created_at, 1682985600);
+    assert_eq!(bought.completed_at, Some(1683072000));
+    assert_eq!(
+        find("todotxt write  report +work").status,
+        TodoStatus::Progress
+    );
+    assert_eq!(find("todotxt dropped idea").status, TodoStatus::Cancelled);
+    assert_ne!(find("todotxt without date").created_at, 0);
+
+    let (_srv, res) = export_req("format=todotxt").await;
+    assert_eq!(res.status(), 200);
+    assert_eq!(
+        res.headers().get("Content-Disposition").unwrap(),
+        "attachment; filename=\"todos.txt\""
+    );
+    let exported = body(res).await;
+    let lines = exported
+        .lines()
+        .filter(|line| line.contains("todotxt") && !line.contains("without date"))
+        .collect::<Vec<_>>();
+    assert_eq!(lines, dated);
+}
+
+#[actix_web::test]
+#[serial_test::serial]
+async fn import_export_ics() {
+    let file = "BEGIN:VCALENDAR\r\nVERSION:2.0\r\nPRODID:-//Test//EN\r\n\
+        BEGIN:VEVENT\r\nSUMMARY:ics event\r\nEND:VEVENT\r\n\
+        BEGIN:VTODO\r\nUID:1\r\nCREATED:20230501T080000Z\r\nSUMMARY:ics needs\\, action\r\nSTATUS:NEEDS-ACTION\r\n\
+        BEGIN:VALARM\r\nACTION:DISPLAY\r\nSUMMARY:ics alarm\r\nEND:VALARM\r\nEND:VTODO\r\n\
+        BEGIN:VTODO\r\nUID:2\r\nCREATED;VALUE=DATE:20230502\r\nCOMPLETED:20230503T101500Z\r\nSUMMARY:ics a very long\r\n  completed todo\r\nSTATUS:COMPLETED\r\nEND:VTODO\r\n\
+        BEGIN:VTODO\r\nUID:3\r\nSUMMARY:ics in process\r\nSTATUS:IN-PROCESS\r\nEND:VTODO\r\n\
+        BEGIN:VTODO\r\nUID:4\r\nSUMMARY:ics cancelled\r\nSTATUS:CANCELLED\r\nEND:VTODO\r\n\
+        BEGIN:VTODO\r\nUID:5\r\nSTATUS:NEEDS-ACTION\r\nEND:VTODO\r\n\
+        BEGIN:VTODO\r\nUID:6\r\nSUMMARY:ics invalid status\r\nSTATUS:TENTATIVE\r\nEND:VTODO\r\n\
+        END:VCALENDAR\r\n";
+    let res = import_req("format=ics", file).await;
+    assert_eq!(res.status(), 200);
+    let result: ImportResultSchema = parse(res).await;
+    assert_eq!(result.imported, 4);
+    assert_eq!(
+        result.errors.iter().map(|e| e.row).collect::<Vec<_>>(),
+        vec![5, 6]
+    );
+    assert_eq!(result.errors[0].error.message, "The todo has no `SUMMARY`");
+    assert_eq!(
+        result.errors[1].error.message,
+        "The status `TENTATIVE` is not a todo status"
+    );
+
+    let todos: Vec<TodoSchema> = serde_json::from_str(&export_body("").await).unwrap();
+    let find = |title: &str| todos.iter().find(|todo| todo.title == title).unwrap();
+    let pending = find("ics needs, action");
+    assert_eq!(pending.status, TodoStatus::Pending);
+    assert_eq!(pending.created_at, 1682928000);
+    let completed = find("ics a very long completed todo");
+    assert_eq!(completed.status, TodoStatus::Completed);
+    assert_eq!(completed.created_at, 1682985600);
+    assert_eq!(completed.completed_at, Some(1683108900));
+    assert_eq!(find("ics in process").status, TodoStatus::Progress);
+    assert_eq!(find("ics cancelled").status, TodoStatus::Cancelled);
+
+    let (_srv, res) = export_req("format=ics").await;
+    assert_eq!(res.status(), 200);
+    assert_eq!(
+        res.headers().get("Content-Type").unwrap(),
+        "text/calendar; charset=utf-8"
+    );
+    let exported = body(res).await;
+    assert!(exported.starts_with("BEGIN:VCALENDAR\r\n"));
+    assert!(exported.ends_with("END:VCALENDAR\r\n"));
+    assert_eq!(exported.matches("BEGIN:VTODO\r\n").count(), todos.len());
+    assert!(exported.lines().all(|line| line.len() <= 75));
+    assert!(exported.contains(&format!(
+        "UID:{}\r\nDTSTAMP:20230503T101500Z\r\nCREATED:20230502T000000Z\r\n",
+        completed.uuid
+    )));
+    assert!(exported.contains("SUMMARY:ics needs\\, action\r\nSTATUS:NEEDS-ACTION\r\n"));
+    assert!(exported.contains("STATUS:COMPLETED\r\nCOMPLETED:20230503T101500Z\r\n"));
+
+    let res = import_req("format=ics", "BEGIN:VTODO\r\nEND:VTODO\r\n").await;
+    assert_eq!(res.status(), 400);
+}
+
+#[actix_web::test]
+#[serial_test::serial]
+async fn import_export_markdown() {
+    let file = "# Sprint notes\n\nSome paragraph.\n\n\
+        - [ ] markdown write the report +work\n\
+        - [x] markdown ship the release +work\n\
+        * [X] ~~markdown dropped idea~~\n\
+        1. [ ] markdown call mom +family\n\
+        - plain list item\n\
+        - [ ]\n\
+        - [ ] markdown write the report +work\n\
+        \n## Progress\n\n  - [ ] markdown review the PR +work\n";
+    let res = import_req("format=markdown", file).await;
+    assert_eq!(res.status(), 200);
+    let result: ImportResultSchema = parse(res).await;
+    assert_eq!(result.imported, 5);
+    assert_eq!(
+        result.errors.iter().map(|e| e.row).collect::<Vec<_>>(),
+        vec![10, 11]
+    );
+    assert_eq!(result.errors[0].error.message, "The task item has no title");
+
+    let (_srv, res) = export_req("format=markdown&title=markdown").await;
+    assert_eq!(res.status(), 200);
+    assert_eq!(
+        res.headers().get("Content-Type").unwrap(),
+        "text/markdown; charset=utf-8"
+    );
+    assert_eq!(
+        res.headers().get("Content-Disposition").unwrap(),
+        "attachment; filename=\"todos.md\""
+    );
+    assert_eq!(
+        body(res).await,
+        "- [ ] markdown write the report +work\n\
+         - [x] markdown ship the release +work\n\
+         - [x] ~~markdown dropped idea~~\n\
+         - [ ] markdown call mom +family\n\
+         - [ ] markdown review the PR +work\n"
+    );
+
+    assert_eq!(
+        export_body("format=markdown&title=markdown&group_by=status").await,
+        "## Pending\n\n\
+         - [ ] markdown write the report +work\n\
+         - [ ] markdown call mom +family\n\n\
+         ## Progress\n\n\
+         - [ ] markdown review the PR +work\n\n\
+         ## Completed\n\n\
+         - [x] markdown ship the release +work\n\n\
+         ## Cancelled\n\n\
+         - [x] ~~markdown dropped idea~~\n"
+    );
+    assert_eq!(
+        export_body("format=markdown&title=markdown&group_by=project").await,
+        "## family\n\n\
+         - [ ] markdown call mom +family\n\n\
+         ## work\n\n\
+         - [ ] markdown write the report +work\n\
+         - [x] markdown ship the release +work\n\
+         - [ ] markdown review the PR +work\n\n\
+         ## No project\n\n\
+         - [x] ~~markdown dropped idea~~\n"
+    );
+    assert_eq!(
+        export_body("format=markdown&title=markdown&status=completed").await,
+        "- [x] markdown ship the release +work\n"
+    );
+
+    let (_srv, res) = export_req("format=json&group_by=status").await;
+    assert_eq!(res.status(), 400);
+}
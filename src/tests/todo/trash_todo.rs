@@ -0,0 +1,160 @@
+use std::time::{Duration, SystemTime};
+
+use crate::api::todo::utils;
+use crate::schemas::todo::{TodoListSchema, TodoSchema};
+use crate::schemas::user::UserSchema;
+use crate::tests::login::login_req;
+use crate::tests::todo::create_todo::create_todo_req;
+use crate::tests::todo::delete_todo::delete_todo_req;
+use crate::tests::todo::list_todo::{list_todo_req, list_todo_req_with_headers};
+use crate::tests::{
+    check_content_length, check_content_type, init_test_pool, parse, TestResponseType,
+};
+use actix_web::{http::header::HttpDate, web, App};
+use chrono::Utc;
+use uuid::Uuid;
+
+async fn user_token() -> String {
+    let user: UserSchema = serde_json::from_slice(
+        login_req("testusername1".to_owned(), "testpassword".to_owned())
+            .await
+            .body()
+            .await
+            .unwrap()
+            .to_vec()
+            .as_slice(),
+    )
+    .unwrap();
+    user.token
+}
+
+pub async fn list_trash_req(params: &str) -> TestResponseType {
+    let pool = init_test_pool().await;
+    let token = user_token().await;
+    let srv = actix_test::start(move || {
+        App::new()
+            .app_data(web::Data::new(pool.clone()))
+            .service(web::scope("/todo").service(crate::api::todo::trash::list_trash))
+    });
+    srv.get(format!("/todo/trash?{params}"))
+        .insert_header(("Authorization", format!("Bearer {token}")))
+        .send()
+        .await
+        .unwrap()
+}
+
+pub async fn restore_todo_req(uuid: Uuid) -> TestResponseType {
+    let pool = init_test_pool().await;
+    let token = user_token().await;
+    let srv = actix_test::start(move || {
+        App::new()
+            .app_data(web::Data::new(pool.clone()))
+            .service(web::scope("/todo").service(crate::api::todo::trash::restore))
+    });
+    srv.post(format!("/todo/{uuid}/restore"))
+        .insert_header(("Authorization", format!("Bearer {token}")))
+        .send()
+        .await
+        .unwrap()
+}
+
+#[actix_web::test]
+#[serial_test::serial]
+async fn trash_and_restore_todo() {
+    let todo: TodoSchema =
+        parse(create_todo_req("trashed_todo".to_owned(), "pending".to_owned()).await).await;
+    let res = delete_todo_req(todo.uuid).await;
+    assert_eq!(res.status(), 200);
+    let trashed: TodoSchema = parse(res).await;
+    assert!(trashed.deleted_at.is_some());
+
+    let res = list_trash_req("title=trashed_todo").await;
+    check_content_type(&res);
+    check_content_length(&res);
+    assert_eq!(res.status(), 200);
+    let trash: TodoListSchema = parse(res).await;
+    assert_eq!(trash.meta.total, 1);
+    assert_eq!(trash.data[0].uuid, todo.uuid);
+    let todos: TodoListSchema = parse(list_todo_req("title=trashed_todo").await).await;
+    assert_eq!(todos.meta.total, 0);
+
+    // The title of the trashed todo can be used again
+    let same_title: TodoSchema =
+        parse(create_todo_req("trashed_todo".to_owned(), "pending".to_owned()).await).await;
+    let res = restore_todo_req(todo.uuid).await;
+    check_content_type(&res);
+    assert_eq!(res.status(), 400);
+
+    assert_eq!(delete_todo_req(same_title.uuid).await.status(), 200);
+    let res = restore_todo_req(todo.uuid).await;
+    assert_eq!(res.status(), 200);
+    let restored: TodoSchema = parse(res).await;
+    assert!(restored.deleted_at.is_none());
+    assert_eq!(restored.version, todo.version + 2);
+    assert_eq!(restore_todo_req(todo.uuid).await.status(), 404);
+    let todos: TodoListSchema = parse(list_todo_req("title=trashed_todo").await).await;
+    assert_eq!(todos.meta.total, 1);
+}
+
+#[actix_web::test]
+#[serial_test::serial]
+async fn trash_restore_invalid_todo() {
+    let res = restore_todo_req(Uuid::new_v4()).await;
+    check_content_type(&res);
+    check_content_length(&res);
+    assert_eq!(res.status(), 404);
+}
+
+#[actix_web::test]
+#[serial_test::serial]
+async fn trash_purge() {
+    let trash: TodoListSchema = parse(list_trash_req("limit=500").await).await;
+    assert!(trash.meta.total > 0, "There are no todos in the trash");
+    let first_deleted_at = trash
+        .data
+        .iter()
+        .filter_map(|t| t.deleted_at)
+        .min()
+        .unwrap();
+    let pool = init_test_pool().await;
+    // The retention period is not over yet
+    let purged = utils::purge_trash(first_deleted_at, &pool).await.unwrap();
+    assert_eq!(purged, 0);
+    let purged = utils::purge_trash(Utc::now().naive_utc().timestamp() + 1, &pool)
+        .await
+        .unwrap();
+    assert!(purged >= trash.meta.total);
+    let trash: TodoListSchema = parse(list_trash_req("").await).await;
+    assert_eq!(trash.meta.total, 0);
+}
+
+#[actix_web::test]
+#[serial_test::serial]
+async fn trash_todo_modifies_list() {
+    let todo: TodoSchema = parse(
+        create_todo_req(
+            format!("trash_modified_{}", Uuid::new_v4().simple()),
+            "pending".to_owned(),
+        )
+        .await,
+    )
+    .await;
+    let res = list_todo_req("").await;
+    assert_eq!(res.status(), 200);
+    let last_modified: HttpDate = res
+        .headers()
+        .get("Last-Modified")
+        .unwrap()
+        .to_str()
+        .unwrap()
+        .parse()
+        .unwrap();
+    // A time before the todo is trashed, the `Last-Modified` header has a precision of seconds
+    let since = HttpDate::from(SystemTime::from(last_modified) - Duration::from_secs(1));
+
+    assert_eq!(delete_todo_req(todo.uuid).await.status(), 200);
+    let res = list_todo_req_with_headers("", &[("If-Modified-Since", &since.to_string())]).await;
+    assert_eq!(res.status(), 200);
+    let list: TodoListSchema = parse(res).await;
+    assert!(list.data.iter().all(|listed| listed.uuid != todo.uuid));
+}
@@ -0,0 +1,138 @@
+use crate::schemas::todo::{BulkTodoResultSchema, TodoHistoryListSchema, TodoSchema};
+use crate::schemas::undo::UndoResultSchema;
+use crate::schemas::user::UserSchema;
+use crate::tests::login::login_req;
+use crate::tests::todo::bulk_todo::bulk_todo_req;
+use crate::tests::todo::create_todo::create_todo_req;
+use crate::tests::todo::get_todo::get_todo_req;
+use crate::tests::todo::history_todo::history_todo_req;
+use crate::tests::todo::update_todo::update_todo_req_with;
+use crate::tests::{
+    check_content_length, check_content_type, init_test_pool, parse, TestResponseType,
+};
+use actix_web::{web, App};
+use entity::todo::Status as TodoStatus;
+use serde_json::json;
+
+pub async fn undo_req(params: &str) -> TestResponseType {
+    let pool = init_test_pool().await;
+    let user: UserSchema = serde_json::from_slice(
+        login_req("testusername1".to_owned(), "testpassword".to_owned())
+            .await
+            .body()
+            .await
+            .unwrap()
+            .to_vec()
+            .as_slice(),
+    )
+    .unwrap();
+    let srv = actix_test::start(move || {
+        App::new()
+            .app_data(web::Data::new(pool.clone()))
+            .service(crate::api::undo::undo)
+    });
+    srv.post(format!("/undo?{params}"))
+        .insert_header(("Authorization", format!("Bearer {}", user.token)))
+        .send()
+        .await
+        .unwrap()
+}
+
+async fn create(title: &str) -> TodoSchema {
+    parse(create_todo_req(title.to_owned(), "pending".to_owned()).await).await
+}
+
+#[actix_web::test]
+#[serial_test::serial]
+async fn undo_update_and_create() {
+    let todo = create("undo_update").await;
+    let res = update_todo_req_with(
+        todo.uuid,
+        json!({"title": "undo_update_renamed", "status": "completed"}),
+        &[],
+    )
+    .await;
+    assert_eq!(res.status(), 200);
+
+    let res = undo_req("").await;
+    check_content_type(&res);
+    check_content_length(&res);
+    assert_eq!(res.status(), 200);
+    let undo: UndoResultSchema = parse(res).await;
+    assert_eq!(undo.operations.len(), 1);
+    let undone = &undo.operations[0].todos[0];
+    assert_eq!(undone.title, "undo_update");
+    assert_eq!(undone.status, TodoStatus::Pending);
+    assert_eq!(undone.completed_at, None);
+    assert_eq!(undone.version, todo.version + 2);
+
+    // Undo the creation, the todo is moved to the trash
+    let undo: UndoResultSchema = parse(undo_req("").await).await;
+    assert!(undo.operations[0].todos[0].deleted_at.is_some());
+    assert_eq!(get_todo_req(todo.uuid, "").await.status(), 404);
+
+    let history: TodoHistoryListSchema = parse(history_todo_req(todo.uuid).await).await;
+    assert_eq!(history.data.len(), 4);
+}
+
+#[actix_web::test]
+#[serial_test::serial]
+async fn undo_bulk_delete() {
+    let first = create("undo_bulk_1").await;
+    let second = create("undo_bulk_2").await;
+    let result: BulkTodoResultSchema =
+        parse(bulk_todo_req(json!({"uuids": [first.uuid, second.uuid], "action": "delete"})).await)
+            .await;
+    assert_eq!(result.applied, 2);
+
+    let undo: UndoResultSchema = parse(undo_req("steps=1").await).await;
+    assert_eq!(undo.operations.len(), 1);
+    assert_eq!(undo.operations[0].todos.len(), 2);
+    assert!(undo.operations[0]
+        .todos
+        .iter()
+        .all(|todo| todo.deleted_at.is_none()));
+    assert_eq!(get_todo_req(first.uuid, "").await.status(), 200);
+    assert_eq!(get_todo_req(second.uuid, "").await.status(), 200);
+}
+
+#[actix_web::test]
+#[serial_test::serial]
+async fn undo_conflict() {
+    let first = create("undo_conflict_1").await;
+    let second = create("undo_conflict_2").await;
+    // The first and the last requests have the same request id, so they are one operation,
+    // but the second request changed the first todo after it
+    for (uuid, status, request_id) in [
+        (first.uuid, "progress", "undo-conflict-1"),
+        (first.uuid, "completed", "undo-conflict-2"),
+        (second.uuid, "cancelled", "undo-conflict-1"),
+    ] {
+        let res = update_todo_req_with(
+            uuid,
+            json!({ "status": status }),
+            &[("X-Request-Id", request_id)],
+        )
+        .await;
+        assert_eq!(res.status(), 200);
+    }
+
+    let res = undo_req("").await;
+    check_content_type(&res);
+    check_content_length(&res);
+    assert_eq!(res.status(), 409);
+    // Nothing is undone
+    let second: TodoSchema = parse(get_todo_req(second.uuid, "").await).await;
+    assert_eq!(second.status, TodoStatus::Cancelled);
+}
+
+#[actix_web::test]
+#[serial_test::serial]
+async fn undo_invalid_steps() {
+    for params in ["steps=0", "steps=51"] {
+        let res = undo_req(params).await;
+        check_content_type(&res);
+        check_content_length(&res);
+        assert_eq!(res.status(), 400);
+    }
+}
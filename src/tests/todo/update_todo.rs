@@ -4,12 +4,28 @@ use crate::tests::login::login_req;
 use crate::tests::{check_content_length, check_content_type, init_test_pool, TestResponseType};
 use actix_web::{web, App};
 use entity::todo::Status as TodoStatus;
-use serde_json::json;
+use serde_json::{json, Value};
 use uuid::Uuid;
 
 use super::create_todo::create_todo_req;
 
 pub async fn update_todo_req(uuid: Uuid, title: &str, status: &str) -> TestResponseType {
+    update_todo_req_with(
+        uuid,
+        json! {{
+            "title": title,
+            "status": status
+        }},
+        &[],
+    )
+    .await
+}
+
+pub async fn update_todo_req_with(
+    uuid: Uuid,
+    payload: Value,
+    headers: &[(&str, &str)],
+) -> TestResponseType {
     let pool = init_test_pool().await;
     let user: UserSchema = serde_json::from_slice(
         login_req("testusername1".to_owned(), "testpassword".to_owned())
@@ -26,14 +42,13 @@ pub async fn update_todo_req(uuid: Uuid, title: &str, status: &str) -> TestRespo
             .app_data(web::Data::new(pool.clone()))
             .service(web::scope("/todo").service(crate::api::todo::update::update_todo))
     });
-    srv.put(format!("todo/{uuid}"))
-        .insert_header(("Authorization", format!("Bearer {}", user.token)))
-        .send_json(&json! {{
-            "title": title,
-            "status": status
-        }})
-        .await
-        .unwrap()
+    let mut req = srv
+        .put(format!("todo/{uuid}"))
+        .insert_header(("Authorization", format!("Bearer {}", user.token)));
+    for header in headers {
+        req = req.insert_header(*header);
+    }
+    req.send_json(&payload).await.unwrap()
 }
 
 #[actix_web::test]
@@ -60,3 +75,67 @@ async fn update_invalid_todo() {
     check_content_length(&response);
     assert_eq!(response.status().as_u16(), 404);
 }
+
+#[actix_web::test]
+#[serial_test::serial]
+async fn update_todo_version() {
+    let mut todo = create_todo_req("versioned_todo".to_owned(), "pending".to_owned()).await;
+    let etag = todo
+        .headers()
+        .get("ETag")
+        .unwrap()
+        .to_str()
+        .unwrap()
+        .to_owned();
+    let todo: TodoSchema =
+        serde_json::from_slice(todo.body().await.unwrap().to_vec().as_slice()).unwrap();
+    assert_eq!(todo.version, 1);
+    assert_eq!(etag, todo.etag().to_string());
+
+    let mut response = update_todo_req_with(
+        todo.uuid,
+        json!({"status": "progress"}),
+        &[("If-Match", "\"other\"")],
+    )
+    .await;
+    assert_eq!(response.status().as_u16(), 412);
+    let current: TodoSchema =
+        serde_json::from_slice(response.body().await.unwrap().to_vec().as_slice()).unwrap();
+    assert_eq!(current.version, 1);
+    assert_eq!(current.status, TodoStatus::Pending);
+
+    let mut response =
+        update_todo_req_with(todo.uuid, json!({"status": "progress", "version": 1}), &[]).await;
+    assert_eq!(response.status().as_u16(), 200);
+    let etag = response
+        .headers()
+        .get("ETag")
+        .unwrap()
+        .to_str()
+        .unwrap()
+        .to_owned();
+    let updated: TodoSchema =
+        serde_json::from_slice(response.body().await.unwrap().to_vec().as_slice()).unwrap();
+    assert_eq!(updated.version, 2);
+
+    // The changes are based on an old version
+    let mut response =
+        update_todo_req_with(todo.uuid, json!({"status": "cancelled", "version": 1}), &[]).await;
+    assert_eq!(response.status().as_u16(), 412);
+    let current: TodoSchema =
+        serde_json::from_slice(response.body().await.unwrap().to_vec().as_slice()).unwrap();
+    assert_eq!(current.version, 2);
+    assert_eq!(current.status, TodoStatus::Progress);
+
+    let mut response = update_todo_req_with(
+        todo.uuid,
+        json!({"status": "completed"}),
+        &[("If-Match", &etag)],
+    )
+    .await;
+    assert_eq!(response.status().as_u16(), 200);
+    let updated: TodoSchema =
+        serde_json::from_slice(response.body().await.unwrap().to_vec().as_slice()).unwrap();
+    assert_eq!(updated.version, 3);
+    assert_eq!(updated.status, TodoStatus::Completed);
+}
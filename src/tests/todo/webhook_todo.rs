@@ -0,0 +1,325 @@
+use std::{
+    env,
+    sync::{
+        atomic::{AtomicU16, Ordering},
+        Arc, Mutex,
+    },
+};
+
+use crate::api::webhook::utils;
+use crate::schemas::todo::{TodoEventSchema, TodoEventType, TodoSchema};
+use crate::schemas::user::UserSchema;
+use crate::schemas::webhook::{
+    WebhookDeliveryListSchema, WebhookDeliverySchema, WebhookListSchema, WebhookSchema,
+};
+use crate::tests::login::login_req;
+use crate::tests::todo::create_todo::create_todo_req;
+use crate::tests::todo::delete_todo::delete_todo_req;
+use crate::tests::{
+    check_content_length, check_content_type, init_test_pool, parse, TestResponseType,
+};
+use actix_http::{Method, StatusCode};
+use actix_web::{web, App, HttpRequest, HttpResponse};
+use chrono::Utc;
+use entity::webhook_delivery::Status as DeliveryStatus;
+use serde_json::{json, Value};
+use uuid::Uuid;
+
+/// A local webhook receiver, records the received requests and responds with the given status
+#[derive(Clone, Default)]
+pub struct Receiver {
+    /// The received requests, the signature, event headers and the body
+    pub requests: Arc<Mutex<Vec<(String, String, String)>>>,
+    /// The status code of the responses
+    pub status: Arc<AtomicU16>,
+}
+
+impl Receiver {
+    /// Start the receiver on a local address, the local hosts are allowed to receive the webhooks
+    pub fn start(&self) -> actix_test::TestServer {
+        env::set_var("WEBHOOK_ALLOWED_HOSTS", "localhost,127.0.0.1");
+        self.status.store(200, Ordering::SeqCst);
+        let receiver = self.clone();
+        actix_test::start(move || {
+            App::new()
+                .app_data(web::Data::new(receiver.clone()))
+                .route("/hook", web::post().to(receive))
+        })
+    }
+
+    pub fn take(&self) -> Vec<(String, String, String)> {
+        std::mem::take(&mut *self.requests.lock().unwrap())
+    }
+}
+
+async fn receive(req: HttpRequest, body: String, receiver: web::Data<Receiver>) -> HttpResponse {
+    let header = |name| {
+        req.headers()
+            .get(name)
+            .and_then(|value| value.to_str().ok())
+            .unwrap_or_default()
+            .to_owned()
+    };
+    receiver.requests.lock().unwrap().push((
+        header(utils::SIGNATURE_HEADER),
+        header(utils::EVENT_HEADER),
+        body,
+    ));
+    HttpResponse::new(StatusCode::from_u16(receiver.status.load(Ordering::SeqCst)).unwrap())
+}
+
+pub async fn webhook_req(method: Method, path: &str, payload: Option<Value>) -> TestResponseType {
+    let pool = init_test_pool().await;
+    let user: UserSchema = serde_json::from_slice(
+        login_req("testusername1".to_owned(), "testpassword".to_owned())
+            .await
+            .body()
+            .await
+            .unwrap()
+            .to_vec()
+            .as_slice(),
+    )
+    .unwrap();
+    let srv = actix_test::start(move || {
+        App::new()
+            .app_data(web::Data::new(pool.clone()))
+            .service(web::scope("/api").configure(crate::api::webhook::init_routes))
+    });
+    let req = srv
+        .request(method, srv.url(&format!("/api/webhooks{path}")))
+        .insert_header(("Authorization", format!("Bearer {}", user.token)));
+    match payload {
+        Some(payload) => req.send_json(&payload).await,
+        None => req.send().await,
+    }
+    .unwrap()
+}
+
+async fn deliveries(webhook: &WebhookSchema, params: &str) -> WebhookDeliveryListSchema {
+    let res = webhook_req(
+        Method::GET,
+        &format!("/{}/deliveries{params}", webhook.uuid),
+        None,
+    )
+    .await;
+    assert_eq!(res.status(), 200);
+    parse(res).await
+}
+
+async fn deliver_due(now: i64) -> u64 {
+    utils::deliver_due(now, &init_test_pool().await)
+        .await
+        .unwrap()
+}
+
+#[actix_web::test]
+#[serial_test::serial]
+async fn create_webhook_invalid() {
+    for url in ["example.com/hook", "ftp://example.com/hook", "http://"] {
+        let res = webhook_req(Method::POST, "", Some(json!({ "url": url }))).await;
+        assert_eq!(res.status(), 400);
+    }
+    let res = webhook_req(
+        Method::POST,
+        "",
+        Some(json!({"url": "https://example.com/hook", "events": []})),
+    )
+    .await;
+    assert_eq!(res.status(), 400);
+    let res = webhook_req(
+        Method::POST,
+        "",
+        Some(json!({"url": "https://example.com/hook", "events": ["todo.moved"]})),
+    )
+    .await;
+    assert_eq!(res.status(), 400);
+}
+
+#[rstest::rstest]
+#[case::loopback("http://127.0.0.1/hook")]
+#[case::localhost("http://localhost:8080/hook")]
+#[case::private("https://10.0.0.1/hook")]
+#[case::link_local("http://169.254.169.254/latest/meta-data")]
+#[case::unspecified("http://0.0.0.0/hook")]
+#[case::loopback_v6("http://[::1]/hook")]
+#[case::mapped_v6("http://[::ffff:192.168.1.1]/hook")]
+#[actix_web::test]
+#[serial_test::serial]
+async fn create_webhook_private_url(#[case] url: &str) {
+    env::remove_var("WEBHOOK_ALLOWED_HOSTS");
+    let res = webhook_req(Method::POST, "", Some(json!({ "url": url }))).await;
+    check_content_type(&res);
+    assert_eq!(res.status(), 400);
+}
+
+#[actix_web::test]
+#[serial_test::serial]
+async fn webhook_not_found() {
+    let res = webhook_req(Method::DELETE, &format!("/{}", Uuid::new_v4()), None).await;
+    assert_eq!(res.status(), 404);
+    let res = webhook_req(
+        Method::GET,
+        &format!("/{}/deliveries", Uuid::new_v4()),
+        None,
+    )
+    .await;
+    assert_eq!(res.status(), 404);
+}
+
+#[actix_web::test]
+#[serial_test::serial]
+async fn webhook_deliveries() {
+    let receiver = Receiver::default();
+    let receiver_srv = receiver.start();
+
+    let res = webhook_req(
+        Method::POST,
+        "",
+        Some(json!({"url": receiver_srv.url("/hook"), "events": ["todo.created"]})),
+    )
+    .await;
+    check_content_type(&res);
+    check_content_length(&res);
+    assert_eq!(res.status(), 200);
+    let webhook: WebhookSchema = parse(res).await;
+    assert_eq!(webhook.events, vec![TodoEventType::Created]);
+    let secret = webhook.secret.clone().unwrap();
+
+    let webhooks: WebhookListSchema = parse(webhook_req(Method::GET, "", None).await).await;
+    let listed = webhooks
+        .data
+        .iter()
+        .find(|listed| listed.uuid == webhook.uuid)
+        .unwrap();
+    assert!(listed.secret.is_none());
+
+    // Only the subscribed events are delivered
+    let todo: TodoSchema =
+        parse(create_todo_req("webhook_todo_delivered".to_owned(), "pending".to_owned()).await)
+            .await;
+    assert_eq!(delete_todo_req(todo.uuid).await.status(), 200);
+    assert_eq!(deliver_due(Utc::now().naive_utc().timestamp()).await, 1);
+    let requests = receiver.take();
+    assert_eq!(requests.len(), 1);
+    let (signature, event, body) = &requests[0];
+    assert_eq!(signature, &utils::sign(&secret, body));
+    assert_eq!(event, "todo.created");
+    let payload: TodoEventSchema = serde_json::from_str(body).unwrap();
+    assert_eq!(payload.event, TodoEventType::Created);
+    assert_eq!(payload.todo.uuid, todo.uuid);
+
+    let delivered = deliveries(&webhook, "").await.data;
+    assert_eq!(delivered.len(), 1);
+    assert_eq!(delivered[0].status, DeliveryStatus::Succeeded);
+    assert_eq!(delivered[0].attempts, 1);
+    assert_eq!(delivered[0].response_status, Some(200));
+    assert!(delivered[0].delivered_at.is_some());
+
+    // The failed deliveries are retried with backoff, then moved to the dead-letter list
+    receiver.status.store(500, Ordering::SeqCst);
+    let todo: TodoSchema =
+        parse(create_todo_req("webhook_todo_failed".to_owned(), "pending".to_owned()).await).await;
+    let now = Utc::now().naive_utc().timestamp();
+    assert_eq!(deliver_due(now).await, 1);
+    let pending = deliveries(&webhook, "?status=pending").await.data;
+    assert_eq!(pending.len(), 1);
+    assert_eq!(pending[0].attempts, 1);
+    assert_eq!(pending[0].response_status, Some(500));
+    assert!(pending[0].error.is_some());
+    assert_eq!(
+        pending[0].next_attempt_at,
+        Some(now + utils::retry_delay(1))
+    );
+    assert_eq!(deliver_due(now).await, 0);
+
+    let mut later = now;
+    for attempt in 2..=utils::webhook_max_attempts() {
+        later += utils::retry_delay(attempt - 1);
+        assert_eq!(deliver_due(later).await, 1);
+    }
+    assert_eq!(deliver_due(later + 1_000_000).await, 0);
+    assert_eq!(receiver.take().len() as u32, utils::webhook_max_attempts());
+    let failed = deliveries(&webhook, "?status=failed").await.data;
+    assert_eq!(failed.len(), 1);
+    assert_eq!(failed[0].uuid, pending[0].uuid);
+    assert!(failed[0].next_attempt_at.is_none());
+
+    // Redeliver the dead-letter delivery
+    receiver.status.store(200, Ordering::SeqCst);
+    let res = webhook_req(
+        Method::POST,
+        &format!("/{}/deliveries/{}/redeliver", webhook.uuid, failed[0].uuid),
+        None,
+    )
+    .await;
+    assert_eq!(res.status(), 200);
+    let redelivered: WebhookDeliverySchema = parse(res).await;
+    assert_eq!(redelivered.status, DeliveryStatus::Pending);
+    assert_eq!(redelivered.attempts, 0);
+    assert_eq!(deliver_due(Utc::now().naive_utc().timestamp()).await, 1);
+    let requests = receiver.take();
+    assert_eq!(requests.len(), 1);
+    let payload: TodoEventSchema = serde_json::from_str(&requests[0].2).unwrap();
+    assert_eq!(payload.todo.uuid, todo.uuid);
+    assert!(deliveries(&webhook, "?status=failed").await.data.is_empty());
+
+    let res = webhook_req(
+        Method::POST,
+        &format!("/{}/deliveries/{}/redeliver", webhook.uuid, Uuid::new_v4()),
+        None,
+    )
+    .await;
+    assert_eq!(res.status(), 404);
+
+    // The deleted webhooks don't receive the events
+    let res = webhook_req(Method::DELETE, &format!("/{}", webhook.uuid), None).await;
+    assert_eq!(res.status(), 200);
+    let webhooks: WebhookListSchema = parse(webhook_req(Method::GET, "", None).await).await;
+    assert!(webhooks
+        .data
+        .iter()
+        .all(|listed| listed.uuid != webhook.uuid));
+    create_todo_req("webhook_todo_deleted".to_owned(), "pending".to_owned()).await;
+    assert_eq!(deliver_due(Utc::now().naive_utc().timestamp()).await, 0);
+    assert!(receiver.take().is_empty());
+}
+
+#[actix_web::test]
+#[serial_test::serial]
+async fn webhook_delivery_private_url() {
+    let receiver = Receiver::default();
+    let receiver_srv = receiver.start();
+    let res = webhook_req(
+        Method::POST,
+        "",
+        Some(json!({"url": receiver_srv.url("/hook"), "events": ["todo.created"]})),
+    )
+    .await;
+    assert_eq!(res.status(), 200);
+    let webhook: WebhookSchema = parse(res).await;
+
+    // The local receiver is not allowed anymore, the delivery is refused before connecting
+    env::remove_var("WEBHOOK_ALLOWED_HOSTS");
+    create_todo_req(
+        format!("webhook_private_{}", Uuid::new_v4().simple()),
+        "pending".to_owned(),
+    )
+    .await;
+    assert_eq!(deliver_due(Utc::now().naive_utc().timestamp()).await, 1);
+    assert!(receiver.take().is_empty());
+    let pending = deliveries(&webhook, "?status=pending").await.data;
+    assert_eq!(pending.len(), 1);
+    assert!(pending[0].response_status.is_none());
+    assert!(pending[0]
+        .error
+        .as_deref()
+        .unwrap_or_default()
+        .contains("non-public address"));
+
+    assert_eq!(
+        webhook_req(Method::DELETE, &format!("/{}", webhook.uuid), None)
+            .await
+            .status(),
+        200
+    );
+}
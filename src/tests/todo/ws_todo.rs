@@ -0,0 +1,185 @@
+use std::time::Duration;
+
+use crate::schemas::todo::TodoSchema;
+use crate::schemas::user::UserSchema;
+use crate::schemas::ws::WsMessageSchema;
+use crate::tests::login::login_req;
+use crate::tests::todo::create_todo::create_todo_req;
+use crate::tests::{check_content_type, init_test_pool};
+use actix_http::ws::{Frame, Message, ProtocolError};
+use actix_web::{rt::time, web, App};
+use futures_util::{Sink, SinkExt, Stream, StreamExt};
+use serde_json::{json, Value};
+
+/// A WebSocket client connection
+pub trait WsConnection:
+    Sink<Message, Error = ProtocolError> + Stream<Item = Result<Frame, ProtocolError>> + Unpin
+{
+}
+
+impl<T> WsConnection for T where
+    T: Sink<Message, Error = ProtocolError> + Stream<Item = Result<Frame, ProtocolError>> + Unpin
+{
+}
+
+async fn user_token() -> String {
+    let user: UserSchema = serde_json::from_slice(
+        login_req("testusername1".to_owned(), "testpassword".to_owned())
+            .await
+            .body()
+            .await
+            .unwrap()
+            .to_vec()
+            .as_slice(),
+    )
+    .unwrap();
+    user.token
+}
+
+async fn ws_server() -> actix_test::TestServer {
+    let pool = init_test_pool().await;
+    actix_test::start(move || {
+        App::new()
+            .app_data(web::Data::new(pool.clone()))
+            .service(web::scope("/api").service(crate::api::ws::ws))
+    })
+}
+
+/// Connect to the WebSocket, the server is returned to keep it running while using the connection
+pub async fn ws_connect() -> (actix_test::TestServer, impl WsConnection) {
+    let token = user_token().await;
+    let srv = ws_server().await;
+    let (_, connection) = awc::Client::new()
+        .ws(srv.url("/api/ws"))
+        .bearer_auth(token)
+        .connect()
+        .await
+        .unwrap();
+    (srv, connection)
+}
+
+async fn send(connection: &mut impl WsConnection, message: Value) {
+    connection
+        .send(Message::Text(message.to_string().into()))
+        .await
+        .unwrap();
+}
+
+/// Read the messages until one of them matches the predicate
+async fn read_until(
+    connection: &mut impl WsConnection,
+    predicate: impl Fn(&WsMessageSchema) -> bool,
+) -> WsMessageSchema {
+    loop {
+        let frame = time::timeout(Duration::from_secs(10), connection.next())
+            .await
+            .expect("Timeout while waiting for the message")
+            .unwrap()
+            .unwrap();
+        if let Frame::Text(text) = frame {
+            let message = serde_json::from_slice(&text).unwrap();
+            if predicate(&message) {
+                return message;
+            }
+        }
+    }
+}
+
+fn is_reply(id: Option<&str>) -> impl Fn(&WsMessageSchema) -> bool + '_ {
+    move |message| match message {
+        WsMessageSchema::Result { id: reply, .. } | WsMessageSchema::Error { id: reply, .. } => {
+            reply.as_deref() == id
+        }
+        WsMessageSchema::Event(_) => false,
+    }
+}
+
+#[actix_web::test]
+#[serial_test::serial]
+async fn ws_todo_commands() {
+    let (_srv, mut connection) = ws_connect().await;
+
+    send(
+        &mut connection,
+        json!({"id": "create", "command": {"op": "create", "title": "ws_todo_command"}}),
+    )
+    .await;
+    let WsMessageSchema::Result { todo, .. } =
+        read_until(&mut connection, is_reply(Some("create"))).await
+    else {
+        panic!("The todo is not created");
+    };
+    assert_eq!(todo.title, "ws_todo_command");
+    assert_eq!(todo.status.as_str(), "pending");
+
+    send(
+        &mut connection,
+        json!({"id": "update", "command": {"op": "update", "uuid": todo.uuid, "version": todo.version + 1, "status": "completed"}}),
+    )
+    .await;
+    let WsMessageSchema::Error { error, .. } =
+        read_until(&mut connection, is_reply(Some("update"))).await
+    else {
+        panic!("The outdated update is applied");
+    };
+    assert_eq!(error.status, 412);
+
+    send(
+        &mut connection,
+        json!({"id": "duplicate", "command": {"op": "create", "title": "ws_todo_command"}}),
+    )
+    .await;
+    let WsMessageSchema::Error { error, .. } =
+        read_until(&mut connection, is_reply(Some("duplicate"))).await
+    else {
+        panic!("The duplicate todo is created");
+    };
+    assert_eq!(error.status, 400);
+
+    send(&mut connection, json!({"command": "invalid"})).await;
+    let WsMessageSchema::Error { error, .. } = read_until(&mut connection, is_reply(None)).await
+    else {
+        panic!("The invalid command is applied");
+    };
+    assert_eq!(error.status, 400);
+
+    send(
+        &mut connection,
+        json!({"id": "delete", "command": {"op": "delete", "uuid": todo.uuid}}),
+    )
+    .await;
+    let WsMessageSchema::Result { todo: deleted, .. } =
+        read_until(&mut connection, is_reply(Some("delete"))).await
+    else {
+        panic!("The todo is not deleted");
+    };
+    assert!(deleted.deleted_at.is_some());
+}
+
+#[actix_web::test]
+#[serial_test::serial]
+async fn ws_todo_events() {
+    let (_srv, mut connection) = ws_connect().await;
+    let mut res = create_todo_req("ws_todo_event".to_owned(), "pending".to_owned()).await;
+    let todo: TodoSchema = serde_json::from_slice(&res.body().await.unwrap()).unwrap();
+
+    let message = read_until(
+        &mut connection,
+        |message| matches!(message, WsMessageSchema::Event(event) if event.todo.uuid == todo.uuid),
+    )
+    .await;
+    let WsMessageSchema::Event(event) = message else {
+        unreachable!()
+    };
+    assert_eq!(event.event.as_str(), "todo.created");
+}
+
+#[actix_web::test]
+#[serial_test::serial]
+async fn ws_todo_not_upgrade() {
+    let token = user_token().await;
+    let srv = ws_server().await;
+    let res = srv.get("/api/ws").bearer_auth(token).send().await.unwrap();
+    check_content_type(&res);
+    assert_eq!(res.status(), 400);
+}